@@ -1,7 +1,7 @@
 use crate::{
     analysis::{
         function_parameters::{async_param_to_remove, CParameter},
-        functions::{find_function, find_index_to_ignore, finish_function_name},
+        functions::{find_finish_function, find_index_to_ignore},
         imports::Imports,
         out_parameters::use_function_return_for_result,
         ref_mode::RefMode,
@@ -97,9 +97,7 @@ impl Bounds {
             if let Some(bound_type) = Bounds::type_for(env, par.typ, par.nullable) {
                 ret = Some(Bounds::get_to_glib_extra(&bound_type));
                 if r#async && (par.name == "callback" || par.name.ends_with("_callback")) {
-                    let func_name = func.c_identifier.as_ref().unwrap();
-                    let finish_func_name = finish_function_name(func_name);
-                    if let Some(function) = find_function(env, &finish_func_name) {
+                    if let Some(function) = find_finish_function(env, func, configured_functions) {
                         // FIXME: This should work completely based on the analysis of the finish() function
                         // but that a) happens afterwards and b) is not accessible from here either.
                         let mut out_parameters =
@@ -119,11 +119,21 @@ fn ffi_inner(env: &Env, tid: TypeId, inner: &str) -> Result {
                         declared_c_type,
                         typ.get_name()
                     );
-                    warn_main!(tid, "{}", msg);
+                    warn_main!(
+                        tid,
+                        "{}{}",
+                        msg,
+                        source_hint(env.library.type_(tid).source_position())
+                    );
                     return Err(TypeError::Mismatch(msg));
                 }
             } else {
-                warn_main!(tid, "Type `{}` missing c_type", typ.get_name());
+                warn_main!(
+                    tid,
+                    "Type `{}` missing c_type{}",
+                    typ.get_name(),
+                    source_hint(env.library.type_(tid).source_position())
+                );
             }
             fix_name(env, tid, inner)
         }
@@ -153,7 +163,12 @@ fn ffi_inner(env: &Env, tid: TypeId, inner: &str) -> Result {
                             glib_name,
                             env.library.type_(tid).get_name()
                         );
-                        warn_main!(tid, "{}", msg);
+                        warn_main!(
+                            tid,
+                            "{}{}",
+                            msg,
+                            source_hint(env.library.type_(tid).source_position())
+                        );
                         Err(TypeError::Mismatch(msg))
                     }
                 } else {
@@ -165,7 +180,12 @@ fn ffi_inner(env: &Env, tid: TypeId, inner: &str) -> Result {
                     env.library.type_(tid).get_name(),
                     inner
                 );
-                warn_main!(tid, "{}", msg);
+                warn_main!(
+                    tid,
+                    "{}{}",
+                    msg,
+                    source_hint(env.library.type_(tid).source_position())
+                );
                 Err(TypeError::Mismatch(msg))
             }
         }
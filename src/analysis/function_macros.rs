@@ -0,0 +1,184 @@
+use crate::{
+    config::{function_macros::FunctionMacro, WorkMode},
+    env::Env,
+    library::{self, MAIN_NAMESPACE},
+    nameutil,
+    version::Version,
+};
+use log::warn;
+
+#[derive(Debug)]
+pub struct Info {
+    /// Name of the generated Rust function.
+    pub name: String,
+    pub c_identifier: String,
+    /// Names and Rust types of the parameters.
+    pub parameters: Vec<(String, String)>,
+    pub ret: Option<String>,
+    pub body: String,
+    pub version: Option<Version>,
+    pub deprecated_version: Option<Version>,
+    pub tests: Vec<Vec<String>>,
+}
+
+/// Analyzes the function macros of the main namespace opted in through `[[function_macro]]`.
+pub fn analyze(env: &Env) -> Vec<Info> {
+    let ns = env.library.namespace(MAIN_NAMESPACE);
+    let mut macros = Vec::new();
+
+    for configured in &env.config.function_macros {
+        let func_macro = match ns
+            .function_macros
+            .iter()
+            .find(|m| m.name == configured.name || m.c_identifier == configured.name)
+        {
+            Some(func_macro) => func_macro,
+            None => {
+                warn!("Function macro {}.{} not found", ns.name, configured.name);
+                continue;
+            }
+        };
+        if env.is_totally_deprecated(func_macro.deprecated_version) {
+            continue;
+        }
+        if let Some(info) = analyze_function_macro(env, func_macro, configured) {
+            macros.push(info);
+        }
+    }
+
+    macros
+}
+
+fn analyze_function_macro(
+    env: &Env,
+    func_macro: &library::FunctionMacro,
+    configured: &FunctionMacro,
+) -> Option<Info> {
+    let ns = env.library.namespace(MAIN_NAMESPACE);
+    let is_sys = env.config.work_mode == WorkMode::Sys;
+    let name = configured.rename.clone().unwrap_or_else(|| {
+        if is_sys {
+            func_macro.c_identifier.to_lowercase()
+        } else {
+            func_macro.name.to_lowercase()
+        }
+    });
+    let clashes = ns.functions.iter().any(|f| {
+        if is_sys {
+            f.c_identifier.as_ref() == Some(&name)
+        } else {
+            f.name == name
+        }
+    });
+    if clashes {
+        warn!(
+            "Function macro {} clashes with function {}, use `rename`",
+            func_macro.c_identifier, name
+        );
+        return None;
+    }
+
+    let param_names = func_macro
+        .parameters
+        .iter()
+        .map(|p| nameutil::mangle_keywords(&**p).into_owned());
+
+    let (parameters, ret, body) = match configured.body {
+        Some(ref body) => {
+            if configured.parameters.len() != func_macro.parameters.len() {
+                warn!(
+                    "Function macro {} takes {} parameters, {} configured",
+                    func_macro.c_identifier,
+                    func_macro.parameters.len(),
+                    configured.parameters.len()
+                );
+                return None;
+            }
+            (
+                param_names
+                    .zip(configured.parameters.iter().cloned())
+                    .collect(),
+                configured.return_type.clone(),
+                body.clone(),
+            )
+        }
+        None => {
+            let body = if is_sys {
+                type_check_body(env, func_macro)
+            } else {
+                None
+            };
+            match body {
+                Some(body) => (
+                    param_names.map(|p| (p, "gpointer".to_owned())).collect(),
+                    Some("gboolean".to_owned()),
+                    body,
+                ),
+                None => {
+                    warn!(
+                        "Function macro {} can't be expressed in Rust, configure its `body`",
+                        func_macro.c_identifier
+                    );
+                    return None;
+                }
+            }
+        }
+    };
+
+    Some(Info {
+        name,
+        c_identifier: func_macro.c_identifier.clone(),
+        parameters,
+        ret,
+        body,
+        version: configured.version.or(func_macro.version),
+        deprecated_version: func_macro.deprecated_version,
+        tests: configured.tests.clone(),
+    })
+}
+
+/// Infers the expansion of `FOO_IS_BAR(instance)` type check macros.
+fn type_check_body(env: &Env, func_macro: &library::FunctionMacro) -> Option<String> {
+    if func_macro.parameters.len() != 1 || !func_macro.c_identifier.contains("_IS_") {
+        return None;
+    }
+    let type_name = func_macro.c_identifier.replacen("_IS_", "_", 1);
+    let gobject = if env.config.library_name == "GObject" {
+        String::new()
+    } else if env
+        .config
+        .external_libraries
+        .iter()
+        .any(|lib| lib.namespace == "GObject")
+    {
+        format!("{}::", nameutil::crate_name("GObject"))
+    } else {
+        return None;
+    };
+    type_check_call(env, &type_name, &gobject, &func_macro.parameters[0])
+}
+
+fn type_check_call(env: &Env, type_name: &str, gobject: &str, param: &str) -> Option<String> {
+    let ns = env.library.namespace(MAIN_NAMESPACE);
+    let get_type = ns.types.iter().flatten().find_map(|typ| {
+        let (name, get_type) = match typ {
+            library::Type::Class(klass) => (&klass.name, &klass.glib_get_type),
+            library::Type::Interface(iface) => (&iface.name, &iface.glib_get_type),
+            _ => return None,
+        };
+        let full_name = format!("{}.{}", ns.name, name);
+        if env.type_status_sys(&full_name).ignored() {
+            return None;
+        }
+        get_type
+            .strip_suffix("_get_type")
+            .filter(|prefix| prefix.to_uppercase() == type_name)
+            .map(|_| get_type)
+    })?;
+    Some(format!(
+        "{gobject}g_type_check_instance_is_a({param} as *mut {gobject}GTypeInstance, {get_type}())",
+        gobject = gobject,
+        param = nameutil::mangle_keywords(param),
+        get_type = get_type,
+    ))
+}
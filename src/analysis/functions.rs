@@ -1,8 +1,6 @@
 /*
  * TODO: better heuristic (https://bugzilla.gnome.org/show_bug.cgi?id=623635#c5)
- * TODO: ProgressCallback types (not specific to async).
- * TODO: add annotation for methods like g_file_replace_contents_bytes_async where the finish
- * method has a different prefix.
+ * TODO: ProgressCallback types of non-async functions.
  */
 
 use crate::{
@@ -58,6 +56,9 @@ pub struct AsyncTrampoline {
     pub bound_name: char,
     pub output_params: Vec<analysis::Parameter>,
     pub ffi_ret: Option<analysis::Parameter>,
+    /// Callback called while the operation runs, e.g. the `progress_callback` of
+    /// `g_file_copy_async`. It shares the user data of the async callback.
+    pub progress_callback: Option<Trampoline>,
 }
 
 #[derive(Clone, Debug)]
@@ -96,6 +97,7 @@ pub struct Info {
     pub destroys: Vec<Trampoline>,
     pub remove_params: Vec<usize>,
     pub async_future: Option<AsyncFuture>,
+    pub source_position: Option<library::SourcePosition>,
 }
 
 impl Info {
@@ -344,6 +346,7 @@ fn analyze_callbacks(
                 if par.c_type != "GDestroyNotify" {
                     if let Some((mut callback, destroy_index)) = analyze_callback(
                         func_name,
+                        func.source_position.as_ref(),
                         type_tid,
                         env,
                         &par,
@@ -360,8 +363,9 @@ fn analyze_callbacks(
                             if *user_data != callback.user_data_index {
                                 warn_main!(
                                     type_tid,
-                                    "`{}`: Different destructors cannot share the same user data",
-                                    func_name
+                                    "`{}`: Different destructors cannot share the same user data{}",
+                                    func_name,
+                                    library::source_hint(func.source_position.as_ref())
                                 );
                                 *commented = true;
                             }
@@ -376,6 +380,7 @@ fn analyze_callbacks(
                     }
                 } else if let Some((mut callback, _)) = analyze_callback(
                     func_name,
+                    func.source_position.as_ref(),
                     type_tid,
                     env,
                     &par,
@@ -393,8 +398,9 @@ fn analyze_callbacks(
                     } else {
                         warn_main!(
                             type_tid,
-                            "`{}`: no user data point to the destroy callback",
+                            "`{}`: no user data point to the destroy callback{}",
                             func_name,
+                            library::source_hint(func.source_position.as_ref())
                         );
                         *commented = true;
                     }
@@ -426,8 +432,9 @@ fn analyze_callbacks(
             ) {
                 warn_main!(
                     type_tid,
-                    "`{}`: destructor without linked callback",
-                    func_name
+                    "`{}`: destructor without linked callback{}",
+                    func_name,
+                    library::source_hint(func.source_position.as_ref())
                 );
             }
         }
@@ -443,8 +450,9 @@ fn analyze_callbacks(
         *commented = true;
         warn_main!(
             type_tid,
-            "`{}`: Different user data share the same destructors",
-            func.name
+            "`{}`: Different user data share the same destructors{}",
+            func.name,
+            library::source_hint(func.source_position.as_ref())
         );
     }
 
@@ -476,8 +484,9 @@ fn analyze_callbacks(
     } else {
         warn_main!(
             type_tid,
-            "`{}`: this is supposed to be a callback function but no callback was found...",
-            func.name
+            "`{}`: this is supposed to be a callback function but no callback was found...{}",
+            func.name,
+            library::source_hint(func.source_position.as_ref())
         );
         *commented = true;
     }
@@ -516,6 +525,7 @@ fn analyze_function(
     let mut to_glib_extras = HashMap::<usize, String>::new();
     let mut used_types: Vec<String> = Vec::with_capacity(4);
     let mut trampoline = None;
+    let mut progress_callback = None;
     let mut callbacks = Vec::new();
     let mut destroys = Vec::new();
     let mut async_future = None;
@@ -532,8 +542,9 @@ fn analyze_function(
         // https://developer.gnome.org/gio/stable/GTlsPassword.html#g-tls-password-set-value-full
         warn_main!(
             type_tid,
-            "Function \"{}\" with destroy callback without callbacks",
-            func.name
+            "Function \"{}\" with destroy callback without callbacks{}",
+            func.name,
+            library::source_hint(func.source_position.as_ref())
         );
         commented = true;
     }
@@ -548,8 +559,9 @@ fn analyze_function(
             if func.kind == library::FunctionKind::Constructor && is_constructor {
                 warn_main!(
                     type_tid,
-                    "`{}`: config forces 'constructor' on an already gir-annotated 'constructor'",
-                    func_name
+                    "`{}`: config forces 'constructor' on an already gir-annotated 'constructor'{}",
+                    func_name,
+                    library::source_hint(func.source_position.as_ref())
                 );
             }
 
@@ -617,7 +629,11 @@ fn analyze_function(
     if let Some(ref f) = ret.parameter {
         if let Type::Function(_) = env.library.type_(f.lib_par.typ) {
             if env.config.work_mode.is_normal() {
-                warn!("Function \"{}\" returns callback", func.name);
+                warn!(
+                    "Function \"{}\" returns callback{}",
+                    func.name,
+                    library::source_hint(func.source_position.as_ref())
+                );
                 commented = true;
             }
         }
@@ -643,6 +659,31 @@ fn analyze_function(
                     "Wrong instance parameter in {}",
                     func.c_identifier.as_ref().unwrap()
                 );
+                if r#async
+                    && par.c_type != "GAsyncReadyCallback"
+                    && env.library.type_(par.typ).is_function()
+                {
+                    if progress_callback.is_some() {
+                        warn_main!(
+                            type_tid,
+                            "{}: Cannot handle more than one callback besides the async one{}",
+                            func.name,
+                            library::source_hint(func.source_position.as_ref())
+                        );
+                        commented = true;
+                    } else {
+                        progress_callback = analyze_async_progress(
+                            env,
+                            func,
+                            type_tid,
+                            par,
+                            &parameters,
+                            &mut commented,
+                            imports,
+                        );
+                    }
+                    continue;
+                }
                 if let Ok(rust_type) = RustType::builder(env, par.typ)
                     .with_direction(par.direction)
                     .with_try_from_glib(&par.try_from_glib)
@@ -677,22 +718,54 @@ fn analyze_function(
                     configured_functions,
                     &parameters,
                 );
-                let type_error = !(r#async
+                if !(r#async
                     && *env.library.type_(par.typ)
                         == Type::Fundamental(library::Fundamental::Pointer))
-                    && RustType::builder(env, par.typ)
+                {
+                    match RustType::builder(env, par.typ)
                         .with_direction(par.direction)
                         .with_scope(par.scope)
                         .with_try_from_glib(&par.try_from_glib)
                         .try_build_param()
-                        .is_err();
-                if type_error {
-                    commented = true;
+                    {
+                        Ok(_) => {}
+                        // Types ignored by the config are expected to comment functions out.
+                        Err(TypeError::Ignored(_)) => commented = true,
+                        Err(error) => {
+                            warn_main!(
+                                type_tid,
+                                "`{}`: parameter `{}` has unbound type {}{}",
+                                func.name,
+                                par.name,
+                                Err(error).into_string(),
+                                library::source_hint(func.source_position.as_ref())
+                            );
+                            commented = true;
+                        }
+                    }
                 }
             }
             if r#async && trampoline.is_none() {
                 commented = true;
             }
+            if let (Some(trampoline), Some(progress)) =
+                (trampoline.as_mut(), progress_callback.as_ref())
+            {
+                // Both callbacks receive the same boxed user data, which is only freed once the
+                // async callback has been called.
+                let async_user_data_index = async_user_data_index(&parameters);
+                for transformation in &mut parameters.transformations {
+                    if transformation.ind_c == progress.user_data_index
+                        || Some(transformation.ind_c) == async_user_data_index
+                    {
+                        transformation.transformation_type = TransformationType::ToGlibDirect {
+                            name: "user_data".to_owned(),
+                        };
+                    }
+                }
+                trampoline.progress_callback = progress_callback;
+                imports.add("std::cell::RefCell");
+            }
         } else {
             analyze_callbacks(
                 env,
@@ -747,8 +820,9 @@ fn analyze_function(
     if unsupported_outs {
         warn_main!(
             type_tid,
-            "Function {} has unsupported outs",
-            func.c_identifier.as_ref().unwrap_or(&func.name)
+            "Function {} has unsupported outs{}",
+            func.c_identifier.as_ref().unwrap_or(&func.name),
+            library::source_hint(func.source_position.as_ref())
         );
         commented = true;
     } else if status.need_generate() && !commented {
@@ -853,6 +927,7 @@ fn analyze_function(
         callbacks,
         destroys,
         remove_params: cross_user_data_check.values().cloned().collect::<Vec<_>>(),
+        source_position: func.source_position.clone(),
     }
 }
 
@@ -896,10 +971,13 @@ fn analyze_async(
         // Checks for /*Ignored*/ or other error comments
         *commented |= callback_type.contains("/*");
         let func_name = func.c_identifier.as_ref().unwrap();
-        let finish_func_name = finish_function_name(func_name);
+        let finish_function = find_finish_function(env, func, configured_functions);
+        let finish_func_name = finish_function
+            .and_then(|function| function.c_identifier.clone())
+            .unwrap_or_else(|| finish_function_name(func_name));
         let mut output_params = vec![];
         let mut ffi_ret = None;
-        if let Some(function) = find_function(env, &finish_func_name) {
+        if let Some(function) = finish_function {
             if use_function_return_for_result(
                 env,
                 function.ret.typ,
@@ -930,8 +1008,9 @@ fn analyze_async(
             warn_main!(
                 type_tid,
                 "{}: Cannot handle callbacks and async parameters at the same time for the \
-                 moment",
-                func.name
+                 moment{}",
+                func.name,
+                library::source_hint(func.source_position.as_ref())
             );
             *commented = true;
             return false;
@@ -940,14 +1019,16 @@ fn analyze_async(
             if success_parameters.is_empty() {
                 warn_main!(
                     type_tid,
-                    "{}: missing success parameters for async future",
-                    func.name
+                    "{}: missing success parameters for async future{}",
+                    func.name,
+                    library::source_hint(func.source_position.as_ref())
                 );
             } else if error_parameters.is_empty() {
                 warn_main!(
                     type_tid,
-                    "{}: missing error parameters for async future",
-                    func.name
+                    "{}: missing error parameters for async future{}",
+                    func.name,
+                    library::source_hint(func.source_position.as_ref())
                 );
             }
             *commented = true;
@@ -963,6 +1044,7 @@ fn analyze_async(
             bound_name,
             output_params,
             ffi_ret,
+            progress_callback: None,
         });
 
         if !no_future {
@@ -985,8 +1067,102 @@ fn analyze_async(
     }
 }
 
+/// Index in `c_parameters` of the user data of the `GAsyncReadyCallback`.
+fn async_user_data_index(parameters: &function_parameters::Parameters) -> Option<usize> {
+    // The `closure` indexes don't count the instance parameter
+    let offset = parameters
+        .c_parameters
+        .iter()
+        .take_while(|p| p.instance_parameter)
+        .count();
+    parameters
+        .c_parameters
+        .iter()
+        .find(|p| p.c_type == "GAsyncReadyCallback")
+        .and_then(|p| p.user_data_index)
+        .map(|index| index + offset)
+}
+
+/// Analyzes a callback parameter of an async function other than the `GAsyncReadyCallback`,
+/// like the `progress_callback` of `g_file_copy_async`.
+///
+/// The closure is taken boxed, since it's called an unknown number of times until the
+/// operation finishes.
+fn analyze_async_progress(
+    env: &Env,
+    func: &library::Function,
+    type_tid: library::TypeId,
+    par: &CParameter,
+    parameters: &function_parameters::Parameters,
+    commented: &mut bool,
+    imports: &mut Imports,
+) -> Option<Trampoline> {
+    let func_name = func.c_identifier.as_ref().unwrap_or(&func.name);
+    if par.destroy_index.is_some() {
+        warn_main!(
+            type_tid,
+            "function `{}`'s callback `{}` with destroy notify isn't supported in async functions{}",
+            func_name,
+            par.name,
+            library::source_hint(func.source_position.as_ref())
+        );
+        *commented = true;
+        return None;
+    }
+    let c_parameters = parameters
+        .c_parameters
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| !p.instance_parameter)
+        .map(|(pos, p)| (p, pos))
+        .collect::<Vec<_>>();
+    let (mut callback, _) = analyze_callback(
+        func_name,
+        func.source_position.as_ref(),
+        type_tid,
+        env,
+        par,
+        &None,
+        commented,
+        imports,
+        &c_parameters,
+        env.library.type_(par.typ),
+    )?;
+    let user_data = &parameters.c_parameters[callback.user_data_index];
+    if !function_parameters::async_param_to_remove(&user_data.name) {
+        warn_main!(
+            type_tid,
+            "function `{}`'s callback `{}` has unexpected user data `{}`{}",
+            func_name,
+            par.name,
+            user_data.name,
+            library::source_hint(func.source_position.as_ref())
+        );
+        *commented = true;
+        return None;
+    }
+    let closure = match RustType::builder(env, par.typ)
+        .with_direction(par.direction)
+        .with_scope(ParameterScope::Call)
+        .try_build()
+    {
+        Ok(closure) => format!("Box_<dyn {} + Send + 'static>", closure.as_str()),
+        Err(_) => {
+            *commented = true;
+            return None;
+        }
+    };
+    callback.bound_name = if *par.nullable {
+        format!("Option<{}>", closure)
+    } else {
+        closure
+    };
+    Some(callback)
+}
+
 fn analyze_callback(
     func_name: &str,
+    position: Option<&library::SourcePosition>,
     type_tid: library::TypeId,
     env: &Env,
     par: &CParameter,
@@ -1002,19 +1178,23 @@ fn analyze_callback(
         if par.c_type != "GDestroyNotify" {
             if let Some(user_data) = par.user_data_index {
                 if user_data >= c_parameters.len() {
-                    warn_main!(type_tid,
-                               "function `{}` has an invalid user data index of {} when there are {} parameters",
-                               func_name,
-                               user_data,
-                               c_parameters.len());
+                    warn_main!(
+                        type_tid,
+                        "function `{}` has an invalid user data index of {} when there are {} parameters{}",
+                        func_name,
+                        user_data,
+                        c_parameters.len(),
+                        library::source_hint(position)
+                    );
                     return None;
                 } else if !is_gpointer(&c_parameters[user_data].0.c_type) {
                     *commented = true;
                     warn_main!(
                         type_tid,
-                        "function `{}`'s callback `{}` has invalid user data",
+                        "function `{}`'s callback `{}` has invalid user data{}",
                         func_name,
-                        par.name
+                        par.name,
+                        library::source_hint(position)
                     );
                     return None;
                 }
@@ -1022,9 +1202,10 @@ fn analyze_callback(
                 *commented = true;
                 warn_main!(
                     type_tid,
-                    "function `{}`'s callback `{}` without associated user data",
+                    "function `{}`'s callback `{}` without associated user data{}",
                     func_name,
-                    par.name
+                    par.name,
+                    library::source_hint(position)
                 );
                 return None;
             }
@@ -1033,10 +1214,11 @@ fn analyze_callback(
                     warn_main!(
                         type_tid,
                         "function `{}` has an invalid destroy index of {} when there are {} \
-                         parameters",
+                         parameters{}",
                         func_name,
                         destroy_index,
-                        c_parameters.len()
+                        c_parameters.len(),
+                        library::source_hint(position)
                     );
                     return None;
                 }
@@ -1044,9 +1226,10 @@ fn analyze_callback(
                     *commented = true;
                     warn_main!(
                         type_tid,
-                        "function `{}`'s callback `{}` has invalid destroy callback",
+                        "function `{}`'s callback `{}` has invalid destroy callback{}",
                         func_name,
-                        par.name
+                        par.name,
+                        library::source_hint(position)
                     );
                     return None;
                 }
@@ -1061,8 +1244,9 @@ fn analyze_callback(
             *commented = true;
             warn_main!(
                 type_tid,
-                "Closure type `{}` doesn't provide user data",
-                par.c_type
+                "Closure type `{}` doesn't provide user data{}",
+                par.c_type,
+                library::source_hint(position)
             );
             return None;
         }
@@ -1099,9 +1283,10 @@ fn analyze_callback(
         if par.c_type != "GDestroyNotify" && c_parameters.len() <= user_data_index {
             warn_main!(
                 type_tid,
-                "`{}`: Invalid user data index of `{}`",
+                "`{}`: Invalid user data index of `{}`{}",
                 func.name,
-                user_data_index
+                user_data_index,
+                library::source_hint(position)
             );
             *commented = true;
             None
@@ -1111,9 +1296,10 @@ fn analyze_callback(
         } {
             warn_main!(
                 type_tid,
-                "`{}`: Invalid destroy index of `{}`",
+                "`{}`: Invalid destroy index of `{}`{}",
                 func.name,
-                par.destroy_index.unwrap()
+                par.destroy_index.unwrap(),
+                library::source_hint(position)
             );
             *commented = true;
             None
@@ -1138,7 +1324,12 @@ fn analyze_callback(
                         {
                             Ok(rust_type) => rust_type.into_string(),
                             Err(_) => {
-                                warn_main!(type_tid, "`{}`: unknown type", func.name);
+                                warn_main!(
+                                    type_tid,
+                                    "`{}`: unknown type{}",
+                                    func.name,
+                                    library::source_hint(position)
+                                );
                                 return None;
                             }
                         },
@@ -1169,35 +1360,74 @@ fn analyze_callback(
 }
 
 pub fn find_function<'a>(env: &'a Env, c_identifier: &str) -> Option<&'a Function> {
-    let find = |functions: &'a [Function]| -> Option<&'a Function> {
-        for function in functions {
-            if let Some(ref func_c_identifier) = function.c_identifier {
-                if func_c_identifier == c_identifier {
-                    return Some(function);
-                }
-            }
-        }
-        None
-    };
+    function_lists(env)
+        .into_iter()
+        .flatten()
+        .find(|function| function.c_identifier.as_deref() == Some(c_identifier))
+}
 
+/// Functions of the namespace and of its classes and interfaces, grouped by owner.
+fn function_lists(env: &Env) -> Vec<&[Function]> {
+    let mut lists = Vec::new();
     if let Some(index) = env.library.find_namespace(&env.config.library_name) {
         let namespace = env.library.namespace(index);
-        if let Some(f) = find(&namespace.functions) {
-            return Some(f);
-        }
+        lists.push(&namespace.functions[..]);
         for typ in &namespace.types {
             if let Some(Type::Class(ref class)) = *typ {
-                if let Some(f) = find(&class.functions) {
-                    return Some(f);
-                }
+                lists.push(&class.functions[..]);
             } else if let Some(Type::Interface(ref interface)) = *typ {
-                if let Some(f) = find(&interface.functions) {
-                    return Some(f);
-                }
+                lists.push(&interface.functions[..]);
             }
         }
     }
-    None
+    lists
+}
+
+/// Finds the function finishing the async function `func`.
+///
+/// The `finish_function` from the config takes precedence over the `glib:finish-func` and
+/// `glib:async-func` GIR attributes, which take precedence over the `_finish` naming convention.
+pub fn find_finish_function<'a>(
+    env: &'a Env,
+    func: &library::Function,
+    configured_functions: &[&config::functions::Function],
+) -> Option<&'a Function> {
+    let func_name = func.c_identifier.as_ref().unwrap_or(&func.name);
+    if let Some(finish_function) = configured_functions
+        .iter()
+        .find_map(|f| f.finish_function.as_ref())
+    {
+        let function = find_function(env, finish_function);
+        if function.is_none() {
+            warn!(
+                "`{}`: configured finish function `{}` not found{}",
+                func_name,
+                finish_function,
+                library::source_hint(func.source_position.as_ref())
+            );
+        }
+        return function;
+    }
+
+    // Attributes of the GIR refer to functions of the same owner by name
+    let siblings = function_lists(env).into_iter().find(|functions| {
+        functions
+            .iter()
+            .any(|f| f.c_identifier.is_some() && f.c_identifier == func.c_identifier)
+    });
+    if let Some(siblings) = siblings {
+        let function = match func.finish_func {
+            Some(ref finish_func) => siblings.iter().find(|f| &f.name == finish_func),
+            None => siblings
+                .iter()
+                .find(|f| f.async_func.as_ref() == Some(&func.name)),
+        };
+        if function.is_some() {
+            return function;
+        }
+    }
+
+    find_function(env, &finish_function_name(func_name))
 }
 
 /// Given async function name tries to guess the name of finish function.
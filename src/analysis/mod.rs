@@ -16,6 +16,7 @@ pub mod conversion_type;
 pub mod enums;
 pub mod ffi_type;
 pub mod flags;
+pub mod function_macros;
 pub mod function_parameters;
 pub use function_parameters::Parameter;
 pub mod functions;
@@ -36,12 +37,14 @@ pub mod safety_assertion_mode;
 pub mod signals;
 pub mod signatures;
 pub mod special_functions;
+pub mod subclass;
 pub mod supertypes;
 pub mod symbols;
 pub mod trampoline_parameters;
 pub mod trampolines;
 pub mod try_from_glib;
 pub mod types;
+pub mod virtual_methods;
 
 #[derive(Debug, Default)]
 pub struct Analysis {
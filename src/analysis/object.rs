@@ -2,7 +2,13 @@ use super::{
     child_properties::ChildProperties, imports::Imports, info_base::InfoBase,
     signatures::Signatures, *,
 };
-use crate::{config::gobjects::GObject, env::Env, library, nameutil::*, traits::*};
+use crate::{
+    config::{gobjects::GObject, WorkMode},
+    env::Env,
+    library,
+    nameutil::*,
+    traits::*,
+};
 use log::info;
 use std::ops::Deref;
 
@@ -26,6 +32,7 @@ pub struct Info {
     pub builder_postprocess: Option<String>,
     pub child_properties: ChildProperties,
     pub signatures: Signatures,
+    pub subclass: Option<subclass::Info>,
 }
 
 impl Info {
@@ -134,6 +141,12 @@ pub fn class(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<Info>
     let child_properties =
         child_properties::analyze(env, obj.child_properties.as_ref(), class_tid, &mut imports);
 
+    let subclass = if obj.generate_subclass || env.config.work_mode == WorkMode::Subclass {
+        subclass::analyze(env, obj, klass, class_tid)
+    } else {
+        None
+    };
+
     let has_methods = functions
         .iter()
         .any(|f| f.kind == library::FunctionKind::Method && f.status.need_generate());
@@ -206,6 +219,7 @@ pub fn class(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<Info>
         builder_postprocess: obj.builder_postprocess.clone(),
         child_properties,
         signatures,
+        subclass,
     };
 
     Some(info)
@@ -290,6 +304,12 @@ pub fn interface(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<I
 
     let has_functions = !base.functions().is_empty();
 
+    let subclass = if obj.generate_subclass || env.config.work_mode == WorkMode::Subclass {
+        subclass::analyze_interface(env, obj, iface, iface_tid)
+    } else {
+        None
+    };
+
     let info = Info {
         base,
         c_type: iface.c_type.clone(),
@@ -305,6 +325,7 @@ pub fn interface(env: &Env, obj: &GObject, deps: &[library::TypeId]) -> Option<I
         notify_signals,
         properties,
         signatures,
+        subclass,
         ..Default::default()
     };
 
@@ -140,14 +140,18 @@ fn analyze_property(
     let mut notifiable = !prop.construct_only;
     if generate_set && generate.contains(PropertyGenerateFlags::GET) && !readable {
         warn!(
-            "Attempt to generate getter for notreadable property \"{}.{}\"",
-            type_name, name
+            "Attempt to generate getter for notreadable property \"{}.{}\"{}",
+            type_name,
+            name,
+            library::source_hint(env.library.type_(type_tid).source_position())
         );
     }
     if generate_set && generate.contains(PropertyGenerateFlags::SET) && !writable {
         warn!(
-            "Attempt to generate setter for nonwritable property \"{}.{}\"",
-            type_name, name
+            "Attempt to generate setter for nonwritable property \"{}.{}\"{}",
+            type_name,
+            name,
+            library::source_hint(env.library.type_(type_tid).source_position())
         );
     }
     readable &= generate.contains(PropertyGenerateFlags::GET);
@@ -156,6 +160,21 @@ fn analyze_property(
         notifiable = generate.contains(PropertyGenerateFlags::NOTIFY);
     }
 
+    let functions = match env.library.type_(type_tid) {
+        library::Type::Class(klass) => &klass.functions[..],
+        library::Type::Interface(iface) => &iface.functions[..],
+        _ => &[],
+    };
+    // Accessors declared in the GIR take precedence over the name based lookup
+    let check_get_func_names = match prop.getter(functions) {
+        Some(func) => vec![func.name.clone()],
+        None => check_get_func_names,
+    };
+    let check_set_func_name = match prop.setter(functions) {
+        Some(func) => func.name.clone(),
+        None => set_func_name.clone(),
+    };
+
     if readable {
         for check_get_func_name in check_get_func_names {
             let (has, version) = Signature::has_for_property(
@@ -184,8 +203,14 @@ fn analyze_property(
         }
     }
     if writable {
-        let (has, version) =
-            Signature::has_for_property(env, &set_func_name, false, prop.typ, signatures, deps);
+        let (has, version) = Signature::has_for_property(
+            env,
+            &check_set_func_name,
+            false,
+            prop.typ,
+            signatures,
+            deps,
+        );
         if has {
             // There is a matching set func
             if env.is_totally_deprecated(version) || version <= prop_version {
@@ -251,8 +276,10 @@ fn analyze_property(
             if !*nullable {
                 //TODO: support non-nullable setter if found any
                 warn!(
-                    "Non nullable setter for property generated as nullable \"{}.{}\"",
-                    type_name, name
+                    "Non nullable setter for property generated as nullable \"{}.{}\"{}",
+                    type_name,
+                    name,
+                    library::source_hint(env.library.type_(type_tid).source_position())
                 );
             }
         }
@@ -307,13 +334,18 @@ fn analyze_property(
                     scope: library::ParameterScope::None,
                     closure: None,
                     destroy: None,
+                    attributes: Default::default(),
                 },
                 is_action: false,
                 is_detailed: false, // well, technically this *is* an instance of a detailed signal, but we "pre-detailed" it
+                when: Some(library::SignalWhen::First),
+                no_recurse: true,
+                no_hooks: true,
                 version: prop_version,
                 deprecated_version: prop.deprecated_version,
                 doc: None,
                 doc_deprecated: None,
+                attributes: Default::default(),
             },
             type_tid,
             generate_trait,
@@ -342,6 +374,11 @@ fn analyze_property(
                 deprecated_version: prop.deprecated_version,
                 doc_hidden: false,
                 is_detailed: false, // see above comment
+                when: Some(library::SignalWhen::First),
+                no_recurse: true,
+                no_hooks: true,
+                details: Vec::new(),
+                stream: None,
             })
         } else {
             None
@@ -401,3 +438,54 @@ pub fn get_property_ref_modes(
     let nullable = library::Nullable(set_in_ref_mode.is_ref());
     (get_out_ref_mode, set_in_ref_mode, nullable)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::env::tests::env;
+
+    /// The `(is_get, func_name)` of the analyzed properties of `Test.Widget` named `name`.
+    fn accessors(config: &str, name: &str) -> Vec<(bool, String)> {
+        env(config).analysis.objects["Test.Widget"]
+            .properties
+            .iter()
+            .filter(|p| p.name == name)
+            .map(|p| (p.is_get, p.func_name.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn declared_accessors() {
+        let config = |min_cfg_version: &str, ignore_store_title: bool| {
+            format!(
+                r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "{}"
+
+[[object]]
+name = "Test.Widget"
+status = "generate"
+    [[object.function]]
+    name = "store_title"
+    ignore = {}
+"#,
+                min_cfg_version, ignore_store_title
+            )
+        };
+        assert_eq!(
+            accessors(&config("1.0", false), "label"),
+            vec![(true, "label".to_owned()), (false, "set_label".to_owned())]
+        );
+        assert_eq!(accessors(&config("1.2", false), "title"), vec![]);
+        // The declared setter is newer than the property
+        assert_eq!(
+            accessors(&config("1.0", false), "title"),
+            vec![(false, "set_property_title".to_owned())]
+        );
+        // The declared setter isn't bound
+        assert_eq!(
+            accessors(&config("1.2", true), "title"),
+            vec![(false, "set_title".to_owned())]
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use super::{imports::Imports, info_base::InfoBase, record_type::RecordType, *};
+use super::{
+    conversion_type::ConversionType, imports::Imports, info_base::InfoBase,
+    record_type::RecordType, rust_type::RustType, *,
+};
 use crate::{
     config::{
         derives::{Derive, Derives},
@@ -10,7 +13,7 @@ use crate::{
     traits::*,
     version::Version,
 };
-use log::info;
+use log::{info, warn};
 use std::ops::Deref;
 
 #[derive(Debug, Default)]
@@ -18,11 +21,25 @@ pub struct Info {
     pub base: InfoBase,
     pub glib_get_type: Option<(String, Option<Version>)>,
     pub is_boxed: bool,
+    /// Whether the record is a plain value wrapping its sys struct.
+    pub is_direct: bool,
+    /// Fields of a direct record, accessed through getters and setters.
+    pub fields: Vec<Field>,
     pub derives: Derives,
     pub init_function_expression: Option<String>,
     pub clear_function_expression: Option<String>,
 }
 
+#[derive(Debug)]
+pub struct Field {
+    /// Name of the field in the sys struct.
+    pub name: String,
+    pub getter_name: String,
+    pub setter_name: String,
+    pub typ: String,
+    pub conversion_type: ConversionType,
+}
+
 impl Deref for Info {
     type Target = InfoBase;
 
@@ -77,7 +94,13 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
 
     let record: &library::Record = type_.maybe_ref()?;
 
-    let is_boxed = RecordType::of(&record) == RecordType::AutoBoxed;
+    let record_type = match (obj.direct, RecordType::of(&env.library, record)) {
+        (Some(true), _) => RecordType::Direct,
+        (Some(false), RecordType::Direct) => RecordType::AutoBoxed,
+        (_, record_type) => record_type,
+    };
+    let is_boxed = record_type == RecordType::AutoBoxed;
+    let is_direct = record_type == RecordType::Direct;
 
     let mut imports = Imports::with_defined(&env.library, &name);
 
@@ -111,6 +134,9 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
 
     let mut derives = if let Some(ref derives) = obj.derives {
         derives.clone()
+    } else if is_direct {
+        // Sys structs only implement `Clone`, `Copy` and `Debug`
+        Vec::new()
     } else {
         let derives = vec![Derive {
             names: vec![
@@ -157,9 +183,21 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
         None
     };
 
+    let fields = if is_direct {
+        if glib_get_type.is_some() {
+            imports.add("glib::StaticType");
+            imports.add("glib::value::FromValue");
+            imports.add("glib::value::ToValue");
+        }
+        analyze_fields(env, record, &functions, &mut imports)
+    } else {
+        Vec::new()
+    };
+
     // Check if we have to make use of the GType and the generic
     // boxed functions.
-    if !is_shared
+    if !is_direct
+        && !is_shared
         && (!specials.has_trait(special_functions::Type::Copy)
             || !specials.has_trait(special_functions::Type::Free))
     {
@@ -179,7 +217,11 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
                 );
             }
         } else {
-            error!("Missing memory management functions for {}", full_name);
+            error!(
+                "Missing memory management functions for {}{}",
+                full_name,
+                library::source_hint(record.source_position.as_ref())
+            );
         }
     }
 
@@ -201,9 +243,118 @@ pub fn new(env: &Env, obj: &GObject) -> Option<Info> {
         glib_get_type,
         derives,
         is_boxed,
+        is_direct,
+        fields,
         init_function_expression: obj.init_function_expression.clone(),
         clear_function_expression: obj.clear_function_expression.clone(),
     };
 
     Some(info)
 }
+
+fn analyze_fields(
+    env: &Env,
+    record: &library::Record,
+    functions: &[functions::Info],
+    imports: &mut Imports,
+) -> Vec<Field> {
+    imports.add("glib::translate::*");
+    imports.add("std::fmt");
+    imports.add("std::mem");
+
+    let mut fields = Vec::new();
+    for field in &record.fields {
+        if field.private || field.bits.is_some() {
+            continue;
+        }
+        let conversion_type = ConversionType::of(env, field.typ);
+        if !matches!(
+            conversion_type,
+            ConversionType::Direct | ConversionType::Scalar
+        ) {
+            continue;
+        }
+        let typ = match RustType::try_new(env, field.typ) {
+            Ok(typ) => typ,
+            Err(_) => continue,
+        };
+        let getter_name = mangle_keywords(&*field.name).into_owned();
+        let setter_name = format!("set_{}", getter_name.trim_end_matches('_'));
+        if functions
+            .iter()
+            .any(|f| f.codegen_name() == getter_name || f.codegen_name() == setter_name)
+        {
+            warn!(
+                "Field {} of record {} clashes with a function, no accessors generated{}",
+                field.name,
+                record.name,
+                library::source_hint(record.source_position.as_ref())
+            );
+            continue;
+        }
+        imports.add_used_types(typ.used_types());
+        fields.push(Field {
+            name: field.name.clone(),
+            getter_name,
+            setter_name,
+            typ: typ.into_string(),
+            conversion_type,
+        });
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::tests::env;
+
+    #[test]
+    fn boxed_records_opt_in_direct() {
+        let boxed = env(r#"
+[options]
+work_mode = "normal"
+
+[[object]]
+name = "Test.Point"
+status = "generate"
+"#);
+        let point = &boxed.analysis.records["Test.Point"];
+        assert!(point.is_boxed);
+        assert!(!point.is_direct);
+        assert!(point.fields.is_empty());
+
+        let direct = env(r#"
+[options]
+work_mode = "normal"
+
+[[object]]
+name = "Test.Point"
+status = "generate"
+direct = true
+"#);
+        let point = &direct.analysis.records["Test.Point"];
+        assert!(!point.is_boxed);
+        assert!(point.is_direct);
+        let accessors: Vec<_> = point
+            .fields
+            .iter()
+            .map(|f| {
+                (
+                    &f.name[..],
+                    &f.getter_name[..],
+                    &f.setter_name[..],
+                    &f.typ[..],
+                )
+            })
+            .collect();
+        assert_eq!(
+            accessors,
+            vec![
+                ("x", "x", "set_x", "i32"),
+                ("y", "y", "set_y", "i32"),
+                ("type_", "type_", "set_type", "f64"),
+                ("visible", "visible", "set_visible", "bool"),
+            ]
+        );
+    }
+}
@@ -9,12 +9,13 @@ pub enum RecordType {
     Boxed,
     /// Referencecounted record
     Refcounted,
-    //TODO: detect and generate direct records
-    //Direct,
+    /// Plain value record, wrapping its sys struct by value.
+    /// Only detected without glib_get_type, boxed records must opt in
+    Direct,
 }
 
 impl RecordType {
-    pub fn of(record: &library::Record) -> RecordType {
+    pub fn of(library: &library::Library, record: &library::Record) -> RecordType {
         let mut has_copy = false;
         let mut has_free = false;
         let mut has_ref = false;
@@ -39,8 +40,35 @@ impl RecordType {
             RecordType::Refcounted
         } else if has_copy && has_free {
             RecordType::Boxed
+        } else if !has_copy
+            && !has_free
+            && record.glib_get_type.is_none()
+            && is_plain_value(library, record)
+        {
+            RecordType::Direct
         } else {
             RecordType::AutoBoxed
         }
     }
 }
+
+/// Whether the record is a known struct made only of fundamental values.
+fn is_plain_value(library: &library::Library, record: &library::Record) -> bool {
+    use crate::library::Fundamental::*;
+
+    if record.disguised || record.gtype_struct_for.is_some() || record.fields.is_empty() {
+        return false;
+    }
+    record.fields.iter().all(|field| {
+        if field.bits.is_some() || field.c_type.as_ref().map_or(true, |c| c.contains('*')) {
+            return false;
+        }
+        match library.type_(field.typ) {
+            library::Type::Fundamental(fund) => !matches!(
+                fund,
+                None | Pointer | VarArgs | Utf8 | Filename | OsString | Type | Unsupported
+            ),
+            _ => false,
+        }
+    })
+}
@@ -51,7 +51,7 @@ impl RefMode {
             }
             Record(ref record) => {
                 if direction == library::ParameterDirection::In {
-                    if let RecordType::Refcounted = RecordType::of(record) {
+                    if let RecordType::Refcounted = RecordType::of(library, record) {
                         RefMode::ByRef
                     } else {
                         RefMode::ByRefMut
@@ -63,8 +63,9 @@ pub fn analyze(
     let bool_return_error_message = bool_return_is_error.and_then(|m| {
         if typ != TypeId::tid_bool() {
             error!(
-                "Ignoring bool_return_is_error configuration for non-bool returning function {}",
-                func.name
+                "Ignoring bool_return_is_error configuration for non-bool returning function {}{}",
+                func.name,
+                library::source_hint(func.source_position.as_ref())
             );
             None
         } else {
@@ -85,8 +86,9 @@ pub fn analyze(
     let nullable_return_error_message = nullable_return_is_error.and_then(|m| {
         if let Some(library::Parameter { nullable: Nullable(false), ..}) = parameter {
             error!(
-                "Ignoring nullable_return_is_error configuration for non-none returning function {}",
-                func.name
+                "Ignoring nullable_return_is_error configuration for non-none returning function {}{}",
+                func.name,
+                library::source_hint(func.source_position.as_ref())
             );
             None
         } else {
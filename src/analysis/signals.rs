@@ -1,6 +1,6 @@
-use super::{imports::Imports, trampolines};
+use super::{conversion_type::ConversionType, imports::Imports, rust_type::RustType, trampolines};
 use crate::{
-    analysis::trampolines::Trampoline,
+    analysis::{trampoline_parameters::TransformationType, trampolines::Trampoline},
     config::{self, gobjects::GObject},
     env::Env,
     library, nameutil,
@@ -18,6 +18,20 @@ pub struct Info {
     pub deprecated_version: Option<Version>,
     pub doc_hidden: bool,
     pub is_detailed: bool,
+    pub when: Option<library::SignalWhen>,
+    pub no_recurse: bool,
+    pub no_hooks: bool,
+    /// Configured details of a detailed signal.
+    pub details: Vec<String>,
+    pub stream: Option<StreamInfo>,
+}
+
+/// `*_stream` function yielding the signal arguments as owned values.
+#[derive(Debug)]
+pub struct StreamInfo {
+    pub name: String,
+    /// Owned type of each item member and the expression converting the closure argument to it.
+    pub items: Vec<(String, String)>,
 }
 
 pub fn analyze(
@@ -87,8 +101,24 @@ fn analyze_signal(
         version,
     );
 
+    let details: Vec<String> = configured_signals
+        .iter()
+        .flat_map(|f| f.details.iter().cloned())
+        .collect();
+    if !details.is_empty() && !signal.is_detailed {
+        warn_main!(
+            type_tid,
+            "Details configured for signal '{}' of {}, which isn't detailed{}",
+            signal.name,
+            type_tid.full_name(&env.library),
+            library::source_hint(env.library.type_(type_tid).source_position())
+        );
+    }
+
     let action_emit_name = if signal.is_action {
-        imports.add("glib::object::ObjectExt");
+        if signal.ret.typ != Default::default() {
+            imports.add("std::mem");
+        }
         Some(format!("emit_{}", nameutil::signal_to_snake(&signal.name)))
     } else {
         None
@@ -108,6 +138,28 @@ fn analyze_signal(
         imports.add("std::boxed::Box as Box_");
     }
 
+    let stream_requested = configured_signals.iter().find_map(|f| f.stream);
+    let stream = match trampoline {
+        Ok(ref trampoline) if stream_requested.unwrap_or(obj.generate_signal_streams) => {
+            analyze_stream(env, signal, trampoline, &mut used_types)
+        }
+        _ => None,
+    };
+    if stream.is_some() {
+        imports.add_used_types(&used_types);
+        imports.add("std::pin::Pin");
+        imports.add("std::boxed::Box as Box_");
+        imports.add("futures_core::Stream");
+    } else if stream_requested == Some(true) {
+        warn_main!(
+            type_tid,
+            "Can't generate stream for signal '{}' of {}{}",
+            signal.name,
+            type_tid.full_name(&env.library),
+            library::source_hint(env.library.type_(type_tid).source_position())
+        );
+    }
+
     let info = Info {
         connect_name,
         signal_name: signal.name.clone(),
@@ -117,7 +169,151 @@ fn analyze_signal(
         deprecated_version,
         doc_hidden,
         is_detailed: signal.is_detailed,
+        when: signal.when,
+        no_recurse: signal.no_recurse,
+        no_hooks: signal.no_hooks,
+        details,
+        stream,
     };
 
     info
 }
+
+fn analyze_stream(
+    env: &Env,
+    signal: &library::Signal,
+    trampoline: &Trampoline,
+    used_types: &mut Vec<String>,
+) -> Option<StreamInfo> {
+    use crate::library::{Fundamental, Type::*};
+
+    if trampoline.ret.typ != Default::default() || trampoline.inhibit {
+        return None;
+    }
+
+    let mut items = Vec::new();
+    // Skip the self parameter
+    for (pos, par) in trampoline
+        .parameters
+        .rust_parameters
+        .iter()
+        .enumerate()
+        .skip(1)
+    {
+        let transformation = trampoline.parameters.get(pos)?;
+        match transformation.transformation {
+            TransformationType::None => (),
+            _ => return None,
+        }
+        let nullable = *par.nullable;
+        let expr = match env.library.type_(par.typ) {
+            Fundamental(Fundamental::Utf8)
+            | Fundamental(Fundamental::Filename)
+            | Fundamental(Fundamental::OsString) => {
+                if nullable {
+                    format!("{}.map(Into::into)", par.name)
+                } else {
+                    format!("{}.into()", par.name)
+                }
+            }
+            // The sending closure has to be `Send` for thread-safe objects
+            Class(_) | Interface(_) | Record(_)
+                if trampoline.concurrency == library::Concurrency::None =>
+            {
+                if nullable {
+                    format!("{}.cloned()", par.name)
+                } else {
+                    format!("{}.clone()", par.name)
+                }
+            }
+            Class(_) | Interface(_) | Record(_) => return None,
+            _ => match ConversionType::of(env, par.typ) {
+                ConversionType::Direct
+                | ConversionType::Scalar
+                | ConversionType::Option
+                | ConversionType::Result { .. } => par.name.clone(),
+                _ => return None,
+            },
+        };
+        let typ = RustType::builder(env, par.typ)
+            .with_direction(par.direction)
+            .with_nullable(par.nullable)
+            .try_build()
+            .ok()?;
+        used_types.extend(typ.used_types().iter().cloned());
+        items.push((typ.into_string(), expr));
+    }
+
+    Some(StreamInfo {
+        name: format!("{}_stream", nameutil::signal_to_snake(&signal.name)),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env::tests::env;
+
+    /// The stream items of the signals of `Test.Widget` with the given concurrency.
+    fn streams(concurrency: &str) -> Vec<(String, Option<Vec<String>>)> {
+        let env = env(&format!(
+            r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+
+[[object]]
+name = "Test.Widget"
+status = "generate"
+concurrency = "{}"
+generate_signal_streams = true
+"#,
+            concurrency
+        ));
+        let mut streams: Vec<_> = env.analysis.objects["Test.Widget"]
+            .signals
+            .iter()
+            .map(|signal| {
+                let items = signal
+                    .stream
+                    .as_ref()
+                    .map(|stream| stream.items.iter().map(|(typ, _)| typ.clone()).collect());
+                (signal.signal_name.clone(), items)
+            })
+            .collect();
+        streams.sort();
+        streams
+    }
+
+    fn items(items: &[&str]) -> Option<Vec<String>> {
+        Some(items.iter().map(|&item| item.to_owned()).collect())
+    }
+
+    #[test]
+    fn streams_of_thread_safe_objects() {
+        assert_eq!(
+            streams("none"),
+            vec![
+                ("changed".to_owned(), items(&["i32"])),
+                ("clicked".to_owned(), items(&[])),
+                ("fetch-label".to_owned(), None),
+                (
+                    "moved".to_owned(),
+                    items(&["i32", "Option<glib::GString>", "Widget"])
+                ),
+                ("select-item".to_owned(), None),
+            ]
+        );
+        // The sender of a `Send` object can't move the non-`Send` `Widget`
+        assert_eq!(
+            streams("send+sync"),
+            vec![
+                ("changed".to_owned(), items(&["i32"])),
+                ("clicked".to_owned(), items(&[])),
+                ("fetch-label".to_owned(), None),
+                ("moved".to_owned(), None),
+                ("select-item".to_owned(), None),
+            ]
+        );
+    }
+}
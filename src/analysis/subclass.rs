@@ -0,0 +1,172 @@
+use super::{imports::Imports, namespaces, virtual_methods};
+use crate::{
+    config::gobjects::GObject,
+    env::Env,
+    library,
+    nameutil::{module_name, use_glib_if_needed, use_glib_type},
+    traits::*,
+};
+
+/// Subclassing glue for a class or an interface with virtual methods.
+#[derive(Debug)]
+pub struct Info {
+    pub name: String,
+    pub impl_trait_name: String,
+    pub ext_trait_name: String,
+    /// Name of the `*Impl` trait of the closest ancestor providing one, `ObjectImpl` for
+    /// the interfaces.
+    pub parent_impl_trait_name: String,
+    /// Path of the closest ancestor providing an `IsSubclassable` implementation, `None` for
+    /// the interfaces.
+    pub parent_type_name: Option<String>,
+    /// Class or interface structure, with the ffi crate prefix.
+    pub class_struct: String,
+    /// Name of the instance parameter in the generated `*Impl` methods.
+    pub instance_name: String,
+    pub virtual_methods: Vec<virtual_methods::Info>,
+    pub imports: Imports,
+}
+
+pub fn analyze(
+    env: &Env,
+    obj: &GObject,
+    klass: &library::Class,
+    class_tid: library::TypeId,
+) -> Option<Info> {
+    let mut info = analyze_type(
+        env,
+        obj,
+        &klass.name,
+        klass.c_class_type.as_ref()?,
+        klass.type_struct.as_deref(),
+        &klass.virtual_methods,
+        class_tid,
+    )?;
+    let (parent_impl_trait_name, parent_type_name) = parent_impl(env, klass, &mut info.imports);
+    info.parent_impl_trait_name = parent_impl_trait_name;
+    info.parent_type_name = Some(parent_type_name);
+    Some(info)
+}
+
+pub fn analyze_interface(
+    env: &Env,
+    obj: &GObject,
+    iface: &library::Interface,
+    iface_tid: library::TypeId,
+) -> Option<Info> {
+    analyze_type(
+        env,
+        obj,
+        &iface.name,
+        iface.c_class_type.as_ref()?,
+        iface.type_struct.as_deref(),
+        &iface.virtual_methods,
+        iface_tid,
+    )
+}
+
+/// The part shared by the classes and the interfaces, returns `None` if no virtual method
+/// can be generated.
+fn analyze_type(
+    env: &Env,
+    obj: &GObject,
+    name: &str,
+    c_class_type: &str,
+    type_struct: Option<&str>,
+    virtual_methods: &[library::Function],
+    tid: library::TypeId,
+) -> Option<Info> {
+    if virtual_methods.is_empty() {
+        return None;
+    }
+
+    let class_struct = type_struct
+        .and_then(|name| env.library.find_type(tid.ns_id, name))
+        .and_then(|tid| match env.library.type_(tid) {
+            library::Type::Record(record) => Some(record),
+            _ => None,
+        });
+
+    let mut imports = Imports::new(&env.library);
+    imports.add(&format!("crate::{}", name));
+    imports.add(&use_glib_type(env, "subclass::prelude::*"));
+    imports.add(&use_glib_type(env, "translate::*"));
+    imports.add(&use_glib_type(env, "Cast"));
+
+    let virtual_methods =
+        virtual_methods::analyze(env, virtual_methods, class_struct, tid, obj, &mut imports);
+    if !virtual_methods.iter().any(|v| v.trampoline.is_ok()) {
+        return None;
+    }
+
+    let mut instance_name = module_name(name);
+    if virtual_methods
+        .iter()
+        .filter_map(|v| v.trampoline.as_ref().ok())
+        .flat_map(|t| t.parameters.rust_parameters.iter().skip(1))
+        .any(|par| par.name == instance_name)
+    {
+        instance_name = "obj".to_owned();
+    }
+
+    Some(Info {
+        name: name.to_owned(),
+        impl_trait_name: format!("{}Impl", name),
+        ext_trait_name: format!("{}ImplExt", name),
+        parent_impl_trait_name: "ObjectImpl".to_owned(),
+        parent_type_name: None,
+        class_struct: format!("{}::{}", env.main_sys_crate_name(), c_class_type),
+        instance_name,
+        virtual_methods,
+        imports,
+    })
+}
+
+/// Finds the closest ancestor providing an `*Impl` trait, falling back to `ObjectImpl`.
+fn parent_impl(env: &Env, klass: &library::Class, imports: &mut Imports) -> (String, String) {
+    let mut parent = klass.parent;
+    while let Some(tid) = parent {
+        let full_name = tid.full_name(&env.library);
+        if full_name == "GObject.Object" || full_name == "GObject.InitiallyUnowned" {
+            break;
+        }
+        let parent_class: &library::Class = match env.library.type_(tid).maybe_ref() {
+            Some(class) => class,
+            None => break,
+        };
+        if tid.ns_id == namespaces::MAIN {
+            let has_subclass = env
+                .analysis
+                .objects
+                .get(&full_name)
+                .map_or(false, |info| info.subclass.is_some());
+            if has_subclass {
+                let parent_module = env
+                    .config
+                    .objects
+                    .get(&full_name)
+                    .and_then(|obj| obj.module_name.clone())
+                    .unwrap_or_else(|| module_name(&parent_class.name));
+                imports.add(&format!("crate::{}", parent_class.name));
+                imports.add(&format!(
+                    "super::{}::{}Impl",
+                    parent_module, parent_class.name
+                ));
+                return (
+                    format!("{}Impl", parent_class.name),
+                    parent_class.name.clone(),
+                );
+            }
+        } else if !parent_class.virtual_methods.is_empty() {
+            let crate_name = &env.namespaces[tid.ns_id].crate_name;
+            imports.add(&format!("{}::subclass::prelude::*", crate_name));
+            return (
+                format!("{}Impl", parent_class.name),
+                format!("{}::{}", crate_name, parent_class.name),
+            );
+        }
+        parent = parent_class.parent;
+    }
+
+    ("ObjectImpl".to_owned(), use_glib_if_needed(env, "Object"))
+}
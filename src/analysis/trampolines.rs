@@ -53,25 +53,58 @@ pub fn analyze(
     used_types: &mut Vec<String>,
     version: Option<Version>,
 ) -> Result<Trampoline, Vec<String>> {
-    let errors = closure_errors(env, signal);
+    let errors = closure_errors(env, &signal.parameters, &signal.ret);
     if !errors.is_empty() {
         warn_main!(
             type_tid,
-            "Can't generate {} trampoline for signal '{}'",
+            "Can't generate {} trampoline for signal '{}'{}",
             type_tid.full_name(&env.library),
-            signal.name
+            signal.name,
+            library::source_hint(env.library.type_(type_tid).source_position())
         );
         return Err(errors);
     }
 
-    let is_notify = signal.name.starts_with("notify::");
+    Ok(analyze_callback(
+        env,
+        &signal.name,
+        &signal.parameters,
+        &signal.ret,
+        type_tid,
+        in_trait,
+        configured_signals,
+        obj,
+        used_types,
+        version,
+    ))
+}
+
+/// Analyzes a C callback receiving the instance of `type_tid` as first parameter, followed by
+/// `parameters`: a signal handler or a virtual method. The parameters must have passed
+/// `closure_errors`.
+pub fn analyze_callback(
+    env: &Env,
+    callback_name: &str,
+    parameters: &[library::Parameter],
+    ret: &library::Parameter,
+    type_tid: library::TypeId,
+    in_trait: bool,
+    configured_signals: &[&config::signals::Signal],
+    obj: &GObject,
+    used_types: &mut Vec<String>,
+    version: Option<Version>,
+) -> Trampoline {
+    let is_notify = callback_name.starts_with("notify::");
 
-    let name = format!("{}_trampoline", signal_to_snake(&signal.name));
+    let name = format!("{}_trampoline", signal_to_snake(callback_name));
 
     //TODO: move to object.signal.return config
     let inhibit = configured_signals.iter().any(|f| f.inhibit);
-    if inhibit && signal.ret.typ != library::TypeId::tid_bool() {
-        error!("Wrong return type for Inhibit for signal '{}'", signal.name);
+    if inhibit && ret.typ != library::TypeId::tid_bool() {
+        error!(
+            "Wrong return type for Inhibit for signal '{}'",
+            callback_name
+        );
     }
 
     let mut bounds: Bounds = Default::default();
@@ -109,7 +142,7 @@ pub fn analyze(
 
         parameters
     } else {
-        trampoline_parameters::analyze(env, &signal.parameters, type_tid, configured_signals)
+        trampoline_parameters::analyze(env, parameters, type_tid, configured_signals)
     };
 
     if in_trait {
@@ -139,17 +172,17 @@ pub fn analyze(
         }
     }
 
-    let mut ret_nullable = signal.ret.nullable;
+    let mut ret_nullable = ret.nullable;
 
-    if signal.ret.typ != Default::default() {
-        if let Ok(rust_type) = RustType::builder(env, signal.ret.typ)
+    if ret.typ != Default::default() {
+        if let Ok(rust_type) = RustType::builder(env, ret.typ)
             .with_direction(library::ParameterDirection::Out)
             .try_build()
         {
             //No GString
             used_types.extend(rust_type.into_used_types());
         }
-        if let Some(ffi_type) = used_ffi_type(env, signal.ret.typ, &signal.ret.c_type) {
+        if let Some(ffi_type) = used_ffi_type(env, ret.typ, &ret.c_type) {
             used_types.push(ffi_type);
         }
 
@@ -167,10 +200,10 @@ pub fn analyze(
 
     let ret = library::Parameter {
         nullable: ret_nullable,
-        ..signal.ret.clone()
+        ..ret.clone()
     };
 
-    let trampoline = Trampoline {
+    Trampoline {
         name,
         parameters,
         ret,
@@ -185,13 +218,17 @@ pub fn analyze(
         destroy_index: 0,
         nullable: library::Nullable(false),
         type_name: env.library.type_(type_tid).get_name(),
-    };
-    Ok(trampoline)
+    }
 }
 
-fn closure_errors(env: &Env, signal: &library::Signal) -> Vec<String> {
+/// Describes the parameters and return value a trampoline can't convert.
+pub fn closure_errors(
+    env: &Env,
+    parameters: &[library::Parameter],
+    ret: &library::Parameter,
+) -> Vec<String> {
     let mut errors: Vec<String> = Vec::new();
-    for par in &signal.parameters {
+    for par in parameters {
         if let Some(error) = type_error(env, par) {
             errors.push(format!(
                 "{} {}: {}",
@@ -201,12 +238,12 @@ fn closure_errors(env: &Env, signal: &library::Signal) -> Vec<String> {
             ));
         }
     }
-    if signal.ret.typ != Default::default() {
-        if let Some(error) = type_error(env, &signal.ret) {
+    if ret.typ != Default::default() {
+        if let Some(error) = type_error(env, ret) {
             errors.push(format!(
                 "{} return value {}",
                 error,
-                signal.ret.typ.full_name(&env.library)
+                ret.typ.full_name(&env.library)
             ));
         }
     }
@@ -0,0 +1,167 @@
+use super::{conversion_type::ConversionType, imports::Imports, rust_type::RustType, trampolines};
+use crate::{
+    analysis::trampolines::Trampoline,
+    config::{gobjects::GObject, matchable::Matchable},
+    env::Env,
+    library, nameutil,
+    traits::IntoString,
+    version::Version,
+};
+
+#[derive(Debug)]
+pub struct Info {
+    /// Name of the virtual method, also the name of the field in the class structure.
+    pub name: String,
+    /// Name of the `*ImplExt` method chaining up to the parent class implementation.
+    pub parent_name: String,
+    pub trampoline: Result<Trampoline, Vec<String>>,
+    /// Parameters following the instance, as `name: Type`, and return type of the
+    /// commented out declaration of an unsupported virtual method.
+    pub parameters: Vec<String>,
+    pub ret: Option<String>,
+    pub version: Option<Version>,
+    pub deprecated_version: Option<Version>,
+}
+
+pub fn analyze(
+    env: &Env,
+    virtual_methods: &[library::Function],
+    class_struct: Option<&library::Record>,
+    type_tid: library::TypeId,
+    obj: &GObject,
+    imports: &mut Imports,
+) -> Vec<Info> {
+    let mut vfns = Vec::new();
+
+    for func in virtual_methods {
+        let configured_virtual_methods = obj.virtual_methods.matched(&func.name);
+        if configured_virtual_methods
+            .iter()
+            .any(|f| f.status.ignored())
+        {
+            continue;
+        }
+        if env.is_totally_deprecated(func.deprecated_version) {
+            continue;
+        }
+        let version = configured_virtual_methods
+            .iter()
+            .filter_map(|f| f.version)
+            .min()
+            .or(func.version);
+
+        let info = analyze_virtual_method(env, func, class_struct, type_tid, obj, version, imports);
+        vfns.push(info);
+    }
+
+    vfns
+}
+
+fn analyze_virtual_method(
+    env: &Env,
+    func: &library::Function,
+    class_struct: Option<&library::Record>,
+    type_tid: library::TypeId,
+    obj: &GObject,
+    version: Option<Version>,
+    imports: &mut Imports,
+) -> Info {
+    let mut used_types: Vec<String> = Vec::with_capacity(4);
+    let name = nameutil::mangle_keywords(&*func.name).into_owned();
+    let parent_name = format!("parent_{}", func.name);
+
+    let imports = &mut imports.with_defaults(version, &None);
+    let parameters: Vec<library::Parameter> = func
+        .parameters
+        .iter()
+        .filter(|par| !par.instance_parameter)
+        .cloned()
+        .collect();
+
+    let mut errors = virtual_method_errors(env, func);
+    errors.extend(trampolines::closure_errors(env, &parameters, &func.ret));
+    if class_struct.map_or(true, |s| !s.fields.iter().any(|f| f.name == func.name)) {
+        errors.push(format!("No field {} in class structure", func.name));
+    }
+
+    let trampoline = if errors.is_empty() {
+        let mut trampoline = trampolines::analyze_callback(
+            env,
+            &func.name,
+            &parameters,
+            &func.ret,
+            type_tid,
+            false,
+            &[],
+            obj,
+            &mut used_types,
+            version,
+        );
+        trampoline.name = format!(
+            "{}_{}",
+            nameutil::module_name(&trampoline.type_name),
+            func.name
+        );
+        Ok(trampoline)
+    } else {
+        Err(errors)
+    };
+
+    if trampoline.is_ok() {
+        imports.add_used_types(&used_types);
+    }
+
+    Info {
+        name,
+        parent_name,
+        trampoline,
+        parameters: parameters
+            .iter()
+            .map(|par| {
+                format!(
+                    "{}: {}",
+                    nameutil::mangle_keywords(&*par.name),
+                    RustType::try_new(env, par.typ).into_string()
+                )
+            })
+            .collect(),
+        ret: if func.ret.typ == Default::default() {
+            None
+        } else {
+            Some(RustType::try_new(env, func.ret.typ).into_string())
+        },
+        version,
+        deprecated_version: func.deprecated_version,
+    }
+}
+
+fn virtual_method_errors(env: &Env, func: &library::Function) -> Vec<String> {
+    let mut errors = Vec::new();
+    if func.throws {
+        errors.push("Throws".to_owned());
+    }
+    for par in &func.parameters {
+        if par.instance_parameter {
+            continue;
+        }
+        if par.array_length.is_some() {
+            errors.push(format!("Array {}", par.name));
+        } else if let library::Type::Function(_) = env.library.type_(par.typ) {
+            errors.push(format!(
+                "Callback {}: {}",
+                par.name,
+                par.typ.full_name(&env.library)
+            ));
+        }
+    }
+    if func.ret.typ != Default::default()
+        && func.ret.transfer == library::Transfer::None
+        && ConversionType::of(env, func.ret.typ) == ConversionType::Pointer
+    {
+        errors.push(format!(
+            "Transfer none return value {}",
+            func.ret.typ.full_name(&env.library)
+        ));
+    }
+    errors
+}
@@ -0,0 +1,528 @@
+//! Comparison of the API of two versions of a library, used to spot the new API
+//! missing a `version` annotation when updating the GIR files.
+
+use crate::{json::Json, library::*, library_dump::type_ref, report::Kind, version::Version};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Removed,
+    /// What differs between the two versions.
+    Changed(String),
+}
+
+#[derive(Debug)]
+pub struct Entry {
+    pub kind: Kind,
+    /// Name of the object in the config: the type, or `Ns.*` for global functions.
+    pub object: String,
+    /// Name of the function, signal, property or member, `None` for the type itself.
+    pub name: Option<String>,
+    pub change: Change,
+    /// The `version` of the item in the new library.
+    pub version: Option<Version>,
+}
+
+impl Entry {
+    fn new(kind: Kind, object: &str, name: Option<&str>, change: Change) -> Entry {
+        Entry {
+            kind,
+            object: object.into(),
+            name: name.map(ToOwned::to_owned),
+            change,
+            version: None,
+        }
+    }
+
+    fn with_version(mut self, version: Option<Version>) -> Entry {
+        self.version = version;
+        self
+    }
+
+    pub fn full_name(&self) -> String {
+        match self.name {
+            Some(ref name) => format!("{}.{}", self.object.trim_end_matches(".*"), name),
+            None => self.object.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Diff {
+    pub namespace: String,
+    /// Latest version known by the old library.
+    pub old_version: Option<Version>,
+    /// Latest version known by the new library.
+    pub new_version: Option<Version>,
+    pub entries: Vec<Entry>,
+}
+
+impl Diff {
+    /// Compares the main namespaces of `old` and `new`.
+    ///
+    /// The content of added and removed types isn't listed, only the type itself.
+    pub fn new(old: &Library, new: &Library) -> Diff {
+        let old_ns = old.namespace(MAIN_NAMESPACE);
+        let new_ns = new.namespace(MAIN_NAMESPACE);
+        let mut diff = Diff {
+            namespace: new_ns.name.clone(),
+            old_version: old_ns.versions.iter().next_back().copied(),
+            new_version: new_ns.versions.iter().next_back().copied(),
+            entries: Vec::new(),
+        };
+
+        for (name, pair) in pair_up(named_types(old_ns), named_types(new_ns)) {
+            let object = format!("{}.{}", diff.namespace, name);
+            match pair {
+                (Some(typ), None) => {
+                    if let Some(kind) = Kind::from_type(typ) {
+                        diff.push(Entry::new(kind, &object, None, Change::Removed));
+                    }
+                }
+                (None, Some(typ)) => {
+                    if let Some(kind) = Kind::from_type(typ) {
+                        diff.push(
+                            Entry::new(kind, &object, None, Change::Added)
+                                .with_version(typ.get_version()),
+                        );
+                    }
+                }
+                (Some(old_type), Some(new_type)) => {
+                    diff.compare_types(old, old_type, new, new_type, &object)
+                }
+                (None, None) => {}
+            }
+        }
+
+        let object = format!("{}.*", diff.namespace);
+        diff.compare_functions(old, &old_ns.functions, new, &new_ns.functions, &object);
+        diff
+    }
+
+    fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    fn compare_types(
+        &mut self,
+        old: &Library,
+        old_type: &Type,
+        new: &Library,
+        new_type: &Type,
+        object: &str,
+    ) {
+        let (old_kind, new_kind) = match (Kind::from_type(old_type), Kind::from_type(new_type)) {
+            (Some(old_kind), Some(new_kind)) => (old_kind, new_kind),
+            _ => return,
+        };
+        if old_kind != new_kind {
+            let change = Change::Changed(format!("was a {}", old_kind.as_str()));
+            self.push(Entry::new(new_kind, object, None, change));
+            return;
+        }
+
+        self.compare_functions(old, old_type.functions(), new, new_type.functions(), object);
+
+        for (name, pair) in pair_up(signals(old_type), signals(new_type)) {
+            let entry = |change| Entry::new(Kind::Signal, object, Some(name), change);
+            match pair {
+                (Some(_), None) => self.push(entry(Change::Removed)),
+                (None, Some(signal)) => {
+                    self.push(entry(Change::Added).with_version(signal.version))
+                }
+                (Some(old_signal), Some(new_signal)) => {
+                    let mut changes = Vec::new();
+                    if parameters(old, &old_signal.parameters)
+                        != parameters(new, &new_signal.parameters)
+                    {
+                        changes.push("parameters");
+                    }
+                    if parameter(old, &old_signal.ret) != parameter(new, &new_signal.ret) {
+                        changes.push("return value");
+                    }
+                    if !changes.is_empty() {
+                        self.push(entry(Change::Changed(changes.join(", "))));
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+
+        for (name, pair) in pair_up(properties(old_type), properties(new_type)) {
+            let entry = |change| Entry::new(Kind::Property, object, Some(name), change);
+            match pair {
+                (Some(_), None) => self.push(entry(Change::Removed)),
+                (None, Some(property)) => {
+                    self.push(entry(Change::Added).with_version(property.version))
+                }
+                (Some(old_property), Some(new_property)) => {
+                    let mut changes = Vec::new();
+                    if type_ref(old, old_property.typ) != type_ref(new, new_property.typ) {
+                        changes.push("type");
+                    }
+                    if (old_property.readable, old_property.writable)
+                        != (new_property.readable, new_property.writable)
+                    {
+                        changes.push("access");
+                    }
+                    if !changes.is_empty() {
+                        self.push(entry(Change::Changed(changes.join(", "))));
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+
+        for (name, pair) in pair_up(members(old_type), members(new_type)) {
+            let entry = |change| Entry::new(Kind::Member, object, Some(name), change);
+            match pair {
+                (Some(_), None) => self.push(entry(Change::Removed)),
+                (None, Some(member)) => {
+                    self.push(entry(Change::Added).with_version(member.version))
+                }
+                (Some(old_member), Some(new_member)) => {
+                    if old_member.value != new_member.value {
+                        let change = format!("value {} -> {}", old_member.value, new_member.value);
+                        self.push(entry(Change::Changed(change)));
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn compare_functions(
+        &mut self,
+        old: &Library,
+        old_functions: &[Function],
+        new: &Library,
+        new_functions: &[Function],
+        object: &str,
+    ) {
+        for (name, pair) in pair_up(functions(old_functions), functions(new_functions)) {
+            match pair {
+                (Some(func), None) => self.push(Entry::new(
+                    Kind::from_function(func.kind),
+                    object,
+                    Some(name),
+                    Change::Removed,
+                )),
+                (None, Some(func)) => self.push(
+                    Entry::new(
+                        Kind::from_function(func.kind),
+                        object,
+                        Some(name),
+                        Change::Added,
+                    )
+                    .with_version(func.version),
+                ),
+                (Some(old_func), Some(new_func)) => {
+                    let mut changes = Vec::new();
+                    if old_func.kind != new_func.kind {
+                        changes.push("kind");
+                    }
+                    if parameters(old, &old_func.parameters)
+                        != parameters(new, &new_func.parameters)
+                    {
+                        changes.push("parameters");
+                    }
+                    if parameter(old, &old_func.ret) != parameter(new, &new_func.ret) {
+                        changes.push("return value");
+                    }
+                    if old_func.throws != new_func.throws {
+                        changes.push("throws");
+                    }
+                    if !changes.is_empty() {
+                        self.push(Entry::new(
+                            Kind::from_function(new_func.kind),
+                            object,
+                            Some(name),
+                            Change::Changed(changes.join(", ")),
+                        ));
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    /// Whether `entry` is new API without a version newer than the old library.
+    pub fn is_missing_version(&self, entry: &Entry) -> bool {
+        if entry.change != Change::Added {
+            return false;
+        }
+        match (entry.version, self.old_version) {
+            (None, _) => true,
+            (Some(version), Some(old_version)) => version <= old_version,
+            (Some(_), None) => false,
+        }
+    }
+
+    pub fn print(&self) {
+        println!(
+            "API changes of {} from {} to {}:",
+            self.namespace,
+            version_str(self.old_version),
+            version_str(self.new_version)
+        );
+        let (mut added, mut removed, mut changed, mut missing) = (0, 0, 0, 0);
+        for entry in &self.entries {
+            let mut line = match entry.change {
+                Change::Added => {
+                    added += 1;
+                    format!("[ADDED] {} {}", entry.kind.as_str(), entry.full_name())
+                }
+                Change::Removed => {
+                    removed += 1;
+                    format!("[REMOVED] {} {}", entry.kind.as_str(), entry.full_name())
+                }
+                Change::Changed(ref what) => {
+                    changed += 1;
+                    format!(
+                        "[CHANGED] {} {}: {}",
+                        entry.kind.as_str(),
+                        entry.full_name(),
+                        what
+                    )
+                }
+            };
+            if self.is_missing_version(entry) {
+                missing += 1;
+                match entry.version {
+                    Some(version) => line.push_str(&format!(
+                        ", version {} isn't newer than {}",
+                        version,
+                        version_str(self.old_version)
+                    )),
+                    None => line.push_str(", missing version"),
+                }
+            } else if let Some(version) = entry.version.filter(|_| entry.change == Change::Added) {
+                line.push_str(&format!(" ({})", version));
+            }
+            println!("{}", line);
+        }
+        println!(
+            "{} added, {} removed, {} changed, {} missing a version newer than {}",
+            added,
+            removed,
+            changed,
+            missing,
+            version_str(self.old_version)
+        );
+    }
+
+    /// Prints `version` overrides for the new API missing one, in `Gir.toml` syntax.
+    ///
+    /// The suggested version is the latest one known by the new library, when it's newer than
+    /// the old library.
+    pub fn print_version_overrides(&self) {
+        let version = match (self.new_version, self.old_version) {
+            (Some(new_version), Some(old_version)) if new_version <= old_version => None,
+            (new_version, _) => new_version,
+        };
+        let version = match version {
+            Some(version) => version,
+            None => {
+                println!(
+                    "# The new {} knows no version newer than {} to suggest",
+                    self.namespace,
+                    version_str(self.old_version)
+                );
+                return;
+            }
+        };
+
+        let mut objects: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+        for entry in self.entries.iter().filter(|e| self.is_missing_version(e)) {
+            objects.entry(&entry.object).or_default().push(entry);
+        }
+
+        println!(
+            "# Suggested versions for the new API of {} missing a version newer than {}",
+            self.namespace,
+            version_str(self.old_version)
+        );
+        println!(
+            "# Merge each block into the `[[object]]` of the same name when Gir.toml has one,"
+        );
+        println!("# pasting it as is would configure that object twice.");
+        for (object, entries) in objects {
+            println!();
+            println!("[[object]]");
+            println!("name = \"{}\"", object);
+            println!("status = \"generate\"");
+            if entries.iter().any(|e| e.name.is_none()) {
+                println!("version = \"{}\"", version);
+            }
+            for entry in entries {
+                if let Some(ref name) = entry.name {
+                    let section = match entry.kind {
+                        Kind::Member => "member",
+                        Kind::Signal => "signal",
+                        Kind::Property => "property",
+                        _ => "function",
+                    };
+                    println!("    [[object.{}]]", section);
+                    println!("    name = \"{}\"", name);
+                    println!("    version = \"{}\"", version);
+                }
+            }
+        }
+    }
+}
+
+fn version_str(version: Option<Version>) -> String {
+    version.map_or_else(|| "?".into(), |v| v.to_string())
+}
+
+/// Matches the items of both versions by name, in name order.
+#[allow(clippy::type_complexity)]
+fn pair_up<'a, T>(
+    old: Vec<(&'a str, &'a T)>,
+    new: Vec<(&'a str, &'a T)>,
+) -> BTreeMap<&'a str, (Option<&'a T>, Option<&'a T>)> {
+    let mut pairs: BTreeMap<_, (Option<_>, Option<_>)> = BTreeMap::new();
+    for (name, item) in old {
+        pairs.entry(name).or_default().0 = Some(item);
+    }
+    for (name, item) in new {
+        pairs.entry(name).or_default().1 = Some(item);
+    }
+    pairs
+}
+
+/// Named types of the namespace, without the anonymous ones and the class structures.
+fn named_types(ns: &Namespace) -> Vec<(&str, &Type)> {
+    ns.index
+        .iter()
+        .filter(|(name, _)| !name.starts_with('#'))
+        .filter_map(|(name, &id)| {
+            ns.types[id as usize]
+                .as_ref()
+                .map(|typ| (name.as_str(), typ))
+        })
+        .filter(|(_, typ)| {
+            !matches!(
+                typ,
+                Type::Record(Record {
+                    gtype_struct_for: Some(_),
+                    ..
+                })
+            )
+        })
+        .collect()
+}
+
+fn functions(functions: &[Function]) -> Vec<(&str, &Function)> {
+    functions.iter().map(|f| (f.name.as_str(), f)).collect()
+}
+
+fn signals(typ: &Type) -> Vec<(&str, &Signal)> {
+    let signals = match *typ {
+        Type::Class(ref class) => &class.signals,
+        Type::Interface(ref interface) => &interface.signals,
+        _ => return Vec::new(),
+    };
+    signals.iter().map(|s| (s.name.as_str(), s)).collect()
+}
+
+fn properties(typ: &Type) -> Vec<(&str, &Property)> {
+    let properties = match *typ {
+        Type::Class(ref class) => &class.properties,
+        Type::Interface(ref interface) => &interface.properties,
+        _ => return Vec::new(),
+    };
+    properties.iter().map(|p| (p.name.as_str(), p)).collect()
+}
+
+fn members(typ: &Type) -> Vec<(&str, &Member)> {
+    let members = match *typ {
+        Type::Enumeration(ref enum_) => &enum_.members,
+        Type::Bitfield(ref bitfield) => &bitfield.members,
+        _ => return Vec::new(),
+    };
+    members.iter().map(|m| (m.name.as_str(), m)).collect()
+}
+
+/// What makes the API of the parameters: their types, directions and nullability.
+fn parameters(
+    library: &Library,
+    parameters: &[Parameter],
+) -> Vec<(Json, ParameterDirection, bool)> {
+    parameters
+        .iter()
+        .filter(|p| !p.is_error)
+        .map(|p| parameter(library, p))
+        .collect()
+}
+
+fn parameter(library: &Library, par: &Parameter) -> (Json, ParameterDirection, bool) {
+    (type_ref(library, par.typ), par.direction, *par.nullable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(name: &str, ret: TypeId, version: Option<Version>) -> Function {
+        let mut function =
+            crate::library::tests::function(name, &format!("test_{}", name), FunctionKind::Global);
+        function.ret.typ = ret;
+        function.version = version;
+        function
+    }
+
+    fn library(functions: Vec<Function>, version: Version) -> Library {
+        let mut library = Library::new("Test");
+        library.register_version(MAIN_NAMESPACE, version);
+        for func in functions {
+            library.add_function(MAIN_NAMESPACE, func);
+        }
+        library
+    }
+
+    #[test]
+    fn diff_functions() {
+        let v1_0 = Version::Full(1, 0, 0);
+        let v1_2 = Version::Full(1, 2, 0);
+        let old = library(
+            vec![
+                function("init", TypeId::tid_none(), None),
+                function("quit", TypeId::tid_none(), None),
+                function("name", TypeId::tid_utf8(), Some(v1_0)),
+            ],
+            v1_0,
+        );
+        let new = library(
+            vec![
+                function("init", TypeId::tid_none(), None),
+                function("name", TypeId::tid_bool(), Some(v1_0)),
+                function("reset", TypeId::tid_none(), None),
+                function("run", TypeId::tid_none(), Some(v1_2)),
+            ],
+            v1_2,
+        );
+        let diff = Diff::new(&old, &new);
+
+        let entries = diff
+            .entries
+            .iter()
+            .map(|e| (e.full_name(), e.change.clone(), diff.is_missing_version(e)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    "Test.name".to_owned(),
+                    Change::Changed("return value".into()),
+                    false
+                ),
+                ("Test.quit".to_owned(), Change::Removed, false),
+                ("Test.reset".to_owned(), Change::Added, true),
+                ("Test.run".to_owned(), Change::Added, false),
+            ]
+        );
+        assert_eq!(diff.entries[2].object, "Test.*");
+    }
+}
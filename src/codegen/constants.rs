@@ -19,7 +19,7 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
     let sys_crate_name = env.main_sys_crate_name();
     imports.add("std::ffi::CStr");
 
-    file_saver::save_to_file(path, env.config.make_backup, |w| {
+    file_saver::save_to_file(path, &env.config, |w| {
         general::start_comments(w, &env.config)?;
         general::uses(w, env, &imports, None)?;
         writeln!(w)?;
@@ -91,7 +91,7 @@ impl_function_like_type!(Signal);
 
 pub fn generate(env: &Env) {
     info!("Generating documentation {:?}", env.config.doc_target_path);
-    save_to_file(&env.config.doc_target_path, env.config.make_backup, |w| {
+    save_to_file(&env.config.doc_target_path, &env.config, |w| {
         generate_doc(w, env)
     });
 }
@@ -154,6 +154,26 @@ fn generate_doc(w: &mut dyn Write, env: &Env) -> Result<()> {
         f(w, env)?;
     }
 
+    create_doc_sections_doc(w, env)?;
+
+    Ok(())
+}
+
+fn create_doc_sections_doc(w: &mut dyn Write, env: &Env) -> Result<()> {
+    let symbols = env.symbols.borrow();
+    let module = TypeStruct::new(SType::Mod, "docs");
+
+    for section in &env.library.namespace(MAIN).doc_sections {
+        if let Some(ref doc) = section.doc {
+            let ty = TypeStruct {
+                parent: Some(Box::new(module.clone())),
+                ..TypeStruct::new(SType::Const, &nameutil::doc_section_name(&section.name))
+            };
+            write_item_doc(w, &ty, |w| {
+                writeln!(w, "{}", reformat_doc(doc, &symbols, ""))
+            })?;
+        }
+    }
     Ok(())
 }
 
@@ -285,7 +305,15 @@ fn create_object_doc(w: &mut dyn Write, env: &Env, info: &analysis::object::Info
                 .iter()
                 .find(|f| &f.glib_name == c_identifier)
                 .and_then(|analysed_f| analysed_f.new_name.clone());
-            create_fn_doc(w, env, function, Some(Box::new(ty)), fn_new_name)?;
+            let accessor_note = property_accessor_note(function, properties, functions);
+            create_fn_doc(
+                w,
+                env,
+                function,
+                Some(Box::new(ty)),
+                fn_new_name,
+                accessor_note,
+            )?;
         }
     }
     for signal in signals {
@@ -302,7 +330,14 @@ fn create_object_doc(w: &mut dyn Write, env: &Env, info: &analysis::object::Info
         } else {
             ty.clone()
         };
-        create_fn_doc(w, env, signal, Some(Box::new(ty)), None)?;
+        create_fn_doc(
+            w,
+            env,
+            signal,
+            Some(Box::new(ty)),
+            None,
+            signal_emission_note(signal),
+        )?;
     }
     for property in properties {
         let ty = if has_trait {
@@ -318,7 +353,7 @@ fn create_object_doc(w: &mut dyn Write, env: &Env, info: &analysis::object::Info
         } else {
             ty.clone()
         };
-        create_property_doc(w, env, property, Some(Box::new(ty)))?;
+        create_property_doc(w, env, property, &info.properties, Some(Box::new(ty)))?;
     }
     Ok(())
 }
@@ -351,7 +386,7 @@ fn create_record_doc(w: &mut dyn Write, env: &Env, info: &analysis::record::Info
         ..ty
     };
     for function in &record.functions {
-        create_fn_doc(w, env, function, Some(Box::new(ty.clone())), None)?;
+        create_fn_doc(w, env, function, Some(Box::new(ty.clone())), None, None)?;
     }
     Ok(())
 }
@@ -376,20 +411,13 @@ fn create_enum_doc(w: &mut dyn Write, env: &Env, enum_: &Enumeration) -> Result<
     })?;
 
     for member in &enum_.members {
-        if member.doc.is_some() {
-            let sub_ty = TypeStruct {
-                name: member.name.to_camel(),
-                parent: Some(Box::new(ty.clone())),
-                ty: SType::Variant,
-                args: Vec::new(),
-            };
-            write_item_doc(w, &sub_ty, |w| {
-                if let Some(ref doc) = member.doc {
-                    writeln!(w, "{}", reformat_doc(doc, &symbols, &enum_.name))?;
-                }
-                Ok(())
-            })?;
-        }
+        let sub_ty = TypeStruct {
+            name: member.name.to_camel(),
+            parent: Some(Box::new(ty.clone())),
+            ty: SType::Variant,
+            args: Vec::new(),
+        };
+        create_member_doc(w, &symbols, member, &sub_ty, &enum_.name)?;
     }
 
     Ok(())
@@ -415,25 +443,44 @@ fn create_bitfield_doc(w: &mut dyn Write, env: &Env, bitfield: &Bitfield) -> Res
     })?;
 
     for member in &bitfield.members {
-        if member.doc.is_some() {
-            let sub_ty = TypeStruct {
-                name: nameutil::bitfield_member_name(&member.name),
-                parent: Some(Box::new(ty.clone())),
-                ty: SType::Const,
-                args: Vec::new(),
-            };
-            write_item_doc(w, &sub_ty, |w| {
-                if let Some(ref doc) = member.doc {
-                    writeln!(w, "{}", reformat_doc(doc, &symbols, &bitfield.name))?;
-                }
-                Ok(())
-            })?;
-        }
+        let sub_ty = TypeStruct {
+            name: nameutil::bitfield_member_name(&member.name),
+            parent: Some(Box::new(ty.clone())),
+            ty: SType::Const,
+            args: Vec::new(),
+        };
+        create_member_doc(w, &symbols, member, &sub_ty, &bitfield.name)?;
     }
 
     Ok(())
 }
 
+fn create_member_doc(
+    w: &mut dyn Write,
+    symbols: &analysis::symbols::Info,
+    member: &Member,
+    ty: &TypeStruct,
+    in_type: &str,
+) -> Result<()> {
+    if member.doc.is_none() && member.doc_deprecated.is_none() {
+        return Ok(());
+    }
+    write_item_doc(w, ty, |w| {
+        if let Some(ref doc) = member.doc {
+            writeln!(w, "{}", reformat_doc(doc, symbols, in_type))?;
+        }
+        if let Some(ver) = member.deprecated_version {
+            writeln!(w, "\n# Deprecated since {}\n", ver)?;
+        } else if member.doc_deprecated.is_some() {
+            writeln!(w, "\n# Deprecated\n")?;
+        }
+        if let Some(ref doc) = member.doc_deprecated {
+            writeln!(w, "{}", reformat_doc(doc, symbols, in_type))?;
+        }
+        Ok(())
+    })
+}
+
 static PARAM_NAME: Lazy<Regex> = Lazy::new(|| Regex::new(r"@(\w+)\b").unwrap());
 
 fn fix_param_names<'a>(doc: &'a str, self_name: &Option<String>) -> Cow<'a, str> {
@@ -453,6 +500,7 @@ fn create_fn_doc<T>(
     fn_: &T,
     parent: Option<Box<TypeStruct>>,
     name_override: Option<String>,
+    note: Option<String>,
 ) -> Result<()>
 where
     T: FunctionLikeType + ToStripperType,
@@ -464,6 +512,7 @@ where
         && fn_.doc_deprecated().is_none()
         && fn_.ret().doc.is_none()
         && fn_.parameters().iter().all(|p| p.doc.is_none())
+        && note.is_none()
     {
         return Ok(());
     }
@@ -525,14 +574,54 @@ where
                 reformat_doc(&fix_param_names(doc, &self_name), &symbols, &parent_name)
             )?;
         }
+        if let Some(ref note) = note {
+            writeln!(w, "\n{}", note)?;
+        }
         Ok(())
     })
 }
 
+/// Returns the sentence linking `function` to the property it is declared as accessor of.
+fn property_accessor_note(
+    function: &Function,
+    properties: &[Property],
+    functions: &[Function],
+) -> Option<String> {
+    let is_function = |accessor: Option<&Function>| {
+        accessor.map_or(false, |f| f.c_identifier == function.c_identifier)
+    };
+    properties.iter().find_map(|property| {
+        if is_function(property.getter(functions)) {
+            Some(format!("Getter of the `{}` property.", property.name))
+        } else if is_function(property.setter(functions)) {
+            Some(format!("Setter of the `{}` property.", property.name))
+        } else {
+            None
+        }
+    })
+}
+
+/// Describes the emission flags of `signal` which change what a caller can expect.
+fn signal_emission_note(signal: &Signal) -> Option<String> {
+    let mut notes = Vec::new();
+    if signal.no_recurse {
+        notes.push("A recursive emission restarts the signal instead of recursing.");
+    }
+    if signal.no_hooks {
+        notes.push("Emission hooks are not supported.");
+    }
+    if notes.is_empty() {
+        None
+    } else {
+        Some(format!("# Emission\n\n{}", notes.join(" ")))
+    }
+}
+
 fn create_property_doc(
     w: &mut dyn Write,
     env: &Env,
     property: &Property,
+    analyzed: &[analysis::properties::Property],
     parent: Option<Box<TypeStruct>>,
 ) -> Result<()> {
     if env.is_totally_deprecated(property.deprecated_version) {
@@ -549,13 +638,19 @@ fn create_property_doc(
     let mut v = Vec::with_capacity(2);
 
     let symbols = env.symbols.borrow();
-    if property.readable {
+    // Only the `GValue` based accessors the analysis kept are documented here
+    let generated = |is_get| {
+        analyzed
+            .iter()
+            .any(|p| p.name == property.name && p.is_get == is_get)
+    };
+    if property.readable && generated(true) {
         v.push(TypeStruct {
             parent: parent.clone(),
             ..TypeStruct::new(SType::Fn, &format!("get_property_{}", name_for_func))
         });
     }
-    if property.writable {
+    if property.writable && generated(false) {
         v.push(TypeStruct {
             parent,
             ..TypeStruct::new(SType::Fn, &format!("set_property_{}", name_for_func))
@@ -634,3 +729,54 @@ pub fn get_type_manual_traits_for_implements(
         .map(|name| format!("[`trait@crate::prelude::{}`]", name))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::tests::{env, generated};
+
+    #[test]
+    fn deprecated_member_doc() {
+        let env = env("[options]\nwork_mode = \"doc\"\n");
+        let mode: &Enumeration = env
+            .library
+            .type_(env.library.find_type(MAIN, "Mode").unwrap())
+            .maybe_ref()
+            .unwrap();
+        let doc = generated(|w| create_enum_doc(w, &env, mode));
+        assert!(doc.contains("\n# Deprecated since 1.4\n\nUse fast\n"));
+        // Members without any doc get no entry
+        assert!(!doc.contains("Normal"));
+    }
+
+    #[test]
+    fn signal_emission_notes() {
+        let env = env("[options]\nwork_mode = \"doc\"\n");
+        let widget: &Class = env
+            .library
+            .type_(env.library.find_type(MAIN, "Widget").unwrap())
+            .maybe_ref()
+            .unwrap();
+        let signal = |name| widget.signals.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(
+            signal_emission_note(signal("changed")).as_deref(),
+            Some(
+                "# Emission\n\nA recursive emission restarts the signal instead of recursing. \
+                 Emission hooks are not supported."
+            )
+        );
+        // The emission stage and details don't concern the callers
+        assert_eq!(signal_emission_note(signal("clicked")), None);
+    }
+
+    #[test]
+    fn doc_sections() {
+        let env = env("[options]\nwork_mode = \"doc\"\n");
+        let doc = generated(|w| create_doc_sections_doc(w, &env));
+        assert!(doc.contains(
+            "\nTo use the library, create a [`crate::Widget`] with [`crate::Widget::activate()`].\n"
+        ));
+        // Sections without any doc get no entry
+        assert!(!doc.contains("QUESTION_INDEX"));
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    codegen::general, env::Env, file_saver, library::MAIN_NAMESPACE, nameutil::doc_section_name,
+};
+use std::path::Path;
+
+pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
+    let ns = env.library.namespace(MAIN_NAMESPACE);
+    let doc_sections = ns
+        .doc_sections
+        .iter()
+        .filter(|section| section.doc.is_some())
+        .collect::<Vec<_>>();
+    if doc_sections.is_empty() {
+        return;
+    }
+
+    let path = root_path.join("docs.rs");
+    file_saver::save_to_file(path, &env.config, |w| {
+        general::start_comments(w, &env.config)?;
+
+        // Placeholders carrying the long-form documentation of the library
+        for section in &doc_sections {
+            writeln!(w)?;
+            writeln!(w, "pub const {}: () = ();", doc_section_name(&section.name))?;
+        }
+
+        Ok(())
+    });
+
+    mod_rs.push("\npub mod docs;".into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::tests::env;
+    use std::fs;
+
+    #[test]
+    fn placeholders() {
+        let env = env("[options]\nwork_mode = \"normal\"\n");
+        let dir = std::env::temp_dir().join(format!("gir-doc-sections-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut mod_rs = Vec::new();
+
+        generate(&env, &dir, &mut mod_rs);
+        assert_eq!(mod_rs, ["\npub mod docs;"]);
+        let docs = fs::read_to_string(dir.join("docs.rs")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        // `question-index` has no doc
+        assert!(docs.ends_with("// DO NOT EDIT\n\npub const RUNNING: () = ();\n"));
+    }
+}
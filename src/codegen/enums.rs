@@ -26,7 +26,7 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
     }
 
     let path = root_path.join("enums.rs");
-    file_saver::save_to_file(path, env.config.make_backup, |w| {
+    file_saver::save_to_file(path, &env.config, |w| {
         general::start_comments(w, &env.config)?;
         general::uses(w, env, &env.analysis.enum_imports, None)?;
         writeln!(w)?;
@@ -57,8 +57,10 @@ fn generate_enum(
 ) -> Result<()> {
     struct Member {
         name: String,
+        gir_name: String,
         c_name: String,
         value: String,
+        nick: Option<String>,
         version: Option<Version>,
         deprecated_version: Option<Version>,
     }
@@ -75,17 +77,26 @@ fn generate_enum(
             continue;
         }
         vals.insert(member.value.clone());
-        let deprecated_version = member_config.iter().find_map(|m| m.deprecated_version);
-        let version = member_config.iter().find_map(|m| m.version);
+        let deprecated_version = member_config
+            .iter()
+            .find_map(|m| m.deprecated_version)
+            .or(member.deprecated_version);
+        let version = member_config
+            .iter()
+            .find_map(|m| m.version)
+            .or_else(|| member.version.filter(|&v| Some(v) > enum_.version));
         members.push(Member {
             name: enum_member_name(&member.name),
+            gir_name: member.name.clone(),
             c_name: member.c_identifier.clone(),
             value: member.value.clone(),
+            nick: member.nick.clone(),
             version,
             deprecated_version,
         });
     }
 
+    general::source_position(w, env, enum_.source_position.as_ref(), 0)?;
     cfg_deprecated(w, env, enum_.deprecated_version, false, 0)?;
     version_condition(w, env, enum_.version, false, 0)?;
     if config.must_use {
@@ -156,6 +167,9 @@ fn generate_enum(
 
     writeln!(w)?;
 
+    // Nicks are only used if all members have one, otherwise parsing couldn't round-trip.
+    let has_nicks = !members.is_empty() && members.iter().all(|m| m.nick.is_some());
+
     if config.generate_display_trait && !analysis.specials.has_trait(Type::Display) {
         // Generate Display trait implementation.
         version_condition(w, env, enum_.version, false, 0)?;
@@ -163,19 +177,66 @@ fn generate_enum(
             w,
             "impl fmt::Display for {0} {{\n\
              \tfn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{\n\
-             \t\twrite!(f, \"{0}::{{}}\", match *self {{",
-            enum_.name
+             \t\t{1}match *self {{",
+            enum_.name,
+            if has_nicks {
+                "f.write_str(".to_owned()
+            } else {
+                format!("write!(f, \"{}::{{}}\", ", enum_.name)
+            }
         )?;
         for member in &members {
             version_condition_no_doc(w, env, member.version, false, 3)?;
-            writeln!(w, "\t\t\tSelf::{0} => \"{0}\",", member.name)?;
+            writeln!(
+                w,
+                "\t\t\tSelf::{} => \"{}\",",
+                member.name,
+                match member.nick {
+                    Some(ref nick) if has_nicks => nick,
+                    _ => &member.name,
+                }
+            )?;
         }
         writeln!(
             w,
-            "\t\t\t_ => \"Unknown\",\n\
+            "\t\t\t_ => \"{}\",\n\
              \t\t}})\n\
              \t}}\n\
-             }}\n"
+             }}\n",
+            if has_nicks { "unknown" } else { "Unknown" }
+        )?;
+    }
+
+    if has_nicks {
+        // Generate FromStr trait implementation parsing the GLib nicks.
+        version_condition(w, env, enum_.version, false, 0)?;
+        writeln!(
+            w,
+            "impl std::str::FromStr for {} {{\n\
+             \ttype Err = {};\n\
+             \n\
+             \tfn from_str(s: &str) -> Result<Self, Self::Err> {{\n\
+             \t\tmatch s {{",
+            enum_.name,
+            use_glib_type(env, "BoolError"),
+        )?;
+        for member in &members {
+            version_condition_no_doc(w, env, member.version, false, 3)?;
+            writeln!(
+                w,
+                "\t\t\t\"{}\" => Ok(Self::{}),",
+                member.nick.as_ref().unwrap(),
+                member.name
+            )?;
+        }
+        writeln!(
+            w,
+            "\t\t\t_ => Err({}(\"Invalid value '{{}}' for {}\", s)),\n\
+             \t\t}}\n\
+             \t}}\n\
+             }}\n",
+            use_glib_type(env, "bool_error!"),
+            enum_.name
         )?;
     }
 
@@ -388,5 +449,175 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
         writeln!(w)?;
     }
 
+    if config.generate_serde.unwrap_or(env.config.generate_serde) {
+        let keys = members
+            .iter()
+            .map(|m| match m.nick {
+                Some(ref nick) if has_nicks => nick.as_str(),
+                _ => m.gir_name.as_str(),
+            })
+            .collect::<Vec<_>>();
+
+        // Generate Serialize trait implementation, unknown values are kept as integers.
+        writeln!(w, "#[cfg(feature = \"serde\")]")?;
+        version_condition(w, env, enum_.version, false, 0)?;
+        writeln!(
+            w,
+            "impl serde::Serialize for {} {{\n\
+             \tfn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{\n\
+             \t\tmatch *self {{",
+            enum_.name
+        )?;
+        for (member, key) in members.iter().zip(&keys) {
+            version_condition_no_doc(w, env, member.version, false, 3)?;
+            writeln!(
+                w,
+                "\t\t\tSelf::{} => serializer.serialize_str(\"{}\"),",
+                member.name, key
+            )?;
+        }
+        writeln!(
+            w,
+            "\t\t\tSelf::__Unknown(value) => serializer.serialize_i32(value),\n\
+             \t\t}}\n\
+             \t}}\n\
+             }}\n"
+        )?;
+
+        // Generate Deserialize trait implementation, integers go through `FromGlib`.
+        writeln!(w, "#[cfg(feature = \"serde\")]")?;
+        version_condition(w, env, enum_.version, false, 0)?;
+        writeln!(
+            w,
+            "impl<'de> serde::Deserialize<'de> for {name} {{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {{
+            type Value = {name};
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+                f.write_str(\"a {name} name or value\")
+            }}
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<{name}, E> {{
+                match value {{",
+            name = enum_.name
+        )?;
+        for (member, key) in members.iter().zip(&keys) {
+            version_condition_no_doc(w, env, member.version, false, 5)?;
+            writeln!(
+                w,
+                "\t\t\t\t\t\"{}\" => Ok({}::{}),",
+                key, enum_.name, member.name
+            )?;
+        }
+        writeln!(
+            w,
+            "                    _ => Err(E::invalid_value(serde::de::Unexpected::Str(value), &self)),
+                }}
+            }}
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<{name}, E> {{
+                let value: i32 = std::convert::TryFrom::try_from(value)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(value), &self))?;
+                Ok(unsafe {{ from_glib(value) }})
+            }}
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<{name}, E> {{
+                let value: i32 = std::convert::TryFrom::try_from(value)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(value), &self))?;
+                Ok(unsafe {{ from_glib(value) }})
+            }}
+        }}
+
+        deserializer.deserialize_any(Visitor)
+    }}
+}}
+",
+            name = enum_.name
+        )?;
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::tests::{env, generated};
+
+    const CONFIG: &str = r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+deprecate_by_min_version = true
+generate = ["Test.Mode", "Test.Level"]
+"#;
+
+    fn enum_(env: &Env, name: &str) -> String {
+        let analysis = env
+            .analysis
+            .enumerations
+            .iter()
+            .find(|e| e.full_name == name)
+            .unwrap();
+        let config = &env.config.objects[name];
+        generated(|w| generate_enum(env, w, analysis.type_(&env.library), config, analysis))
+    }
+
+    #[test]
+    fn members_version_and_deprecation() {
+        let env = env(CONFIG);
+        let code = enum_(&env, "Test.Mode");
+        assert!(code.contains(
+            "\t#[cfg(any(feature = \"v1_2\", feature = \"dox\"))]\n\
+             \t#[cfg_attr(feature = \"dox\", doc(cfg(feature = \"v1_2\")))]\n\
+             \t#[doc(alias = \"TEST_MODE_FAST\")]\n\
+             \tFast,\n\
+             \t#[cfg_attr(feature = \"v1_4\", deprecated = \"Since 1.4\")]\n\
+             \t#[doc(alias = \"TEST_MODE_SLOW\")]\n\
+             \tSlow,\n"
+        ));
+        assert!(code.contains(
+            "\t\t\t#[cfg(any(feature = \"v1_2\", feature = \"dox\"))]\n\
+             \t\t\t1 => Self::Fast,\n"
+        ));
+    }
+
+    #[test]
+    fn display_and_from_str_use_nicks() {
+        let env = env(CONFIG);
+        let code = enum_(&env, "Test.Mode");
+        assert!(code.contains(
+            "\t\tf.write_str(match *self {\n\
+             \t\t\tSelf::Normal => \"normal\",\n\
+             \t\t\t#[cfg(any(feature = \"v1_2\", feature = \"dox\"))]\n\
+             \t\t\tSelf::Fast => \"fast\",\n\
+             \t\t\tSelf::Slow => \"slow\",\n\
+             \t\t\t_ => \"unknown\",\n"
+        ));
+        assert!(code.contains(
+            "\t\tmatch s {\n\
+             \t\t\t\"normal\" => Ok(Self::Normal),\n\
+             \t\t\t#[cfg(any(feature = \"v1_2\", feature = \"dox\"))]\n\
+             \t\t\t\"fast\" => Ok(Self::Fast),\n\
+             \t\t\t\"slow\" => Ok(Self::Slow),\n\
+             \t\t\t_ => Err(glib::bool_error!(\"Invalid value '{}' for Mode\", s)),\n"
+        ));
+    }
+
+    #[test]
+    fn display_without_nicks() {
+        let env = env(CONFIG);
+        // `high` has no nick
+        let code = enum_(&env, "Test.Level");
+        assert!(code.contains(
+            "\t\twrite!(f, \"Level::{}\", match *self {\n\
+             \t\t\tSelf::Low => \"Low\",\n\
+             \t\t\tSelf::High => \"High\",\n\
+             \t\t\t_ => \"Unknown\",\n"
+        ));
+        assert!(!code.contains("FromStr"));
+    }
+}
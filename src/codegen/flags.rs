@@ -3,7 +3,8 @@ use crate::{
     analysis::flags::Info,
     analysis::special_functions::Type,
     codegen::general::{
-        self, cfg_deprecated, derives, version_condition, version_condition_string,
+        self, cfg_deprecated, derives, version_condition, version_condition_no_doc,
+        version_condition_string,
     },
     config::gobjects::GObject,
     env::Env,
@@ -11,6 +12,7 @@ use crate::{
     library::*,
     nameutil::{bitfield_member_name, use_glib_type},
     traits::*,
+    version::Version,
 };
 use std::{
     io::{prelude::*, Result},
@@ -23,7 +25,7 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
     }
 
     let path = root_path.join("flags.rs");
-    file_saver::save_to_file(path, env.config.make_backup, |w| {
+    file_saver::save_to_file(path, &env.config, |w| {
         general::start_comments(w, &env.config)?;
         general::uses(w, env, &env.analysis.flags_imports, None)?;
         writeln!(w)?;
@@ -52,7 +54,44 @@ fn generate_flags(
     config: &GObject,
     analysis: &Info,
 ) -> Result<()> {
+    struct Member {
+        name: String,
+        gir_name: String,
+        value: u32,
+        nick: Option<String>,
+        version: Option<Version>,
+        deprecated_version: Option<Version>,
+    }
+
+    let mut members: Vec<Member> = Vec::new();
+    for member in &flags.members {
+        let member_config = config.members.matched(&member.name);
+        let generate = member_config.iter().all(|m| m.status.need_generate());
+        if !generate {
+            continue;
+        }
+
+        let val: i64 = member.value.parse().unwrap();
+        let deprecated_version = member_config
+            .iter()
+            .find_map(|m| m.deprecated_version)
+            .or(member.deprecated_version);
+        let version = member_config
+            .iter()
+            .find_map(|m| m.version)
+            .or_else(|| member.version.filter(|&v| Some(v) > flags.version));
+        members.push(Member {
+            name: bitfield_member_name(&member.name),
+            gir_name: member.name.clone(),
+            value: val as u32,
+            nick: member.nick.clone(),
+            version,
+            deprecated_version,
+        });
+    }
+
     let sys_crate_name = env.main_sys_crate_name();
+    general::source_position(w, env, flags.source_position.as_ref(), 0)?;
     cfg_deprecated(w, env, flags.deprecated_version, false, 0)?;
     version_condition(w, env, flags.version, false, 0)?;
     writeln!(w, "bitflags! {{")?;
@@ -65,20 +104,10 @@ fn generate_flags(
     }
 
     writeln!(w, "    pub struct {}: u32 {{", flags.name)?;
-    for member in &flags.members {
-        let member_config = config.members.matched(&member.name);
-        let generate = member_config.iter().all(|m| m.status.need_generate());
-        if !generate {
-            continue;
-        }
-
-        let name = bitfield_member_name(&member.name);
-        let val: i64 = member.value.parse().unwrap();
-        let deprecated_version = member_config.iter().find_map(|m| m.deprecated_version);
-        let version = member_config.iter().find_map(|m| m.version);
-        cfg_deprecated(w, env, deprecated_version, false, 2)?;
-        version_condition(w, env, version, false, 2)?;
-        writeln!(w, "\t\tconst {} = {};", name, val as u32)?;
+    for member in &members {
+        cfg_deprecated(w, env, member.deprecated_version, false, 2)?;
+        version_condition(w, env, member.version, false, 2)?;
+        writeln!(w, "\t\tconst {} = {};", member.name, member.value)?;
     }
 
     writeln!(
@@ -124,16 +153,85 @@ fn generate_flags(
 
     writeln!(w)?;
 
+    // Nicks are only used if all members have one, otherwise parsing couldn't round-trip.
+    let has_nicks = !members.is_empty() && members.iter().all(|m| m.nick.is_some());
+
     if config.generate_display_trait && !analysis.specials.has_trait(Type::Display) {
         // Generate Display trait implementation.
         version_condition(w, env, flags.version, false, 0)?;
+        if has_nicks {
+            // Join the GLib nicks of the set flags
+            writeln!(
+                w,
+                "impl fmt::Display for {} {{\n\
+                \tfn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{\n\
+                \t\tlet mut nicks = Vec::new();",
+                flags.name
+            )?;
+            // Zero-valued members are contained in any value
+            for member in members.iter().filter(|m| m.value != 0) {
+                version_condition_no_doc(w, env, member.version, false, 2)?;
+                writeln!(
+                    w,
+                    "\t\tif self.contains(Self::{}) {{\n\
+                    \t\t\tnicks.push(\"{}\");\n\
+                    \t\t}}",
+                    member.name,
+                    member.nick.as_ref().unwrap()
+                )?;
+            }
+            writeln!(
+                w,
+                "\t\tf.write_str(&nicks.join(\"|\"))\n\
+                \t}}\n\
+                }}\n"
+            )?;
+        } else {
+            writeln!(
+                w,
+                "impl fmt::Display for {0} {{\n\
+                \tfn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{\n\
+                \t\t<Self as fmt::Debug>::fmt(self, f)\n\
+                \t}}\n\
+                }}\n",
+                flags.name
+            )?;
+        }
+    }
+
+    if has_nicks {
+        // Generate FromStr trait implementation parsing `|`-separated GLib nicks.
+        version_condition(w, env, flags.version, false, 0)?;
+        writeln!(
+            w,
+            "impl std::str::FromStr for {} {{\n\
+            \ttype Err = {};\n\
+            \n\
+            \tfn from_str(s: &str) -> Result<Self, Self::Err> {{\n\
+            \t\tlet mut flags = Self::empty();\n\
+            \t\tfor nick in s.split('|').map(str::trim).filter(|nick| !nick.is_empty()) {{\n\
+            \t\t\tflags |= match nick {{",
+            flags.name,
+            use_glib_type(env, "BoolError"),
+        )?;
+        for member in &members {
+            version_condition_no_doc(w, env, member.version, false, 4)?;
+            writeln!(
+                w,
+                "\t\t\t\t\"{}\" => Self::{},",
+                member.nick.as_ref().unwrap(),
+                member.name
+            )?;
+        }
         writeln!(
             w,
-            "impl fmt::Display for {0} {{\n\
-            \tfn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{\n\
-            \t\t<Self as fmt::Debug>::fmt(self, f)\n\
+            "\t\t\t\t_ => return Err({}(\"Invalid value '{{}}' for {}\", nick)),\n\
+            \t\t\t}};\n\
+            \t\t}}\n\
+            \t\tOk(flags)\n\
             \t}}\n\
             }}\n",
+            use_glib_type(env, "bool_error!"),
             flags.name
         )?;
     }
@@ -251,5 +349,194 @@ impl FromGlib<{sys_crate_name}::{ffi_name}> for {name} {{
         writeln!(w)?;
     }
 
+    if config.generate_serde.unwrap_or(env.config.generate_serde) {
+        let keys = members
+            .iter()
+            .map(|m| match m.nick {
+                Some(ref nick) if has_nicks => nick.as_str(),
+                _ => m.gir_name.as_str(),
+            })
+            .collect::<Vec<_>>();
+
+        // Generate Serialize trait implementation joining the names of the set flags with `|`,
+        // followed by the value of the unknown bits if any.
+        writeln!(w, "#[cfg(feature = \"serde\")]")?;
+        version_condition(w, env, flags.version, false, 0)?;
+        writeln!(
+            w,
+            "impl serde::Serialize for {} {{\n\
+            \tfn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{\n\
+            \t\tlet mut names = Vec::new();",
+            flags.name
+        )?;
+        // Zero-valued members are contained in any value
+        for (member, key) in members.iter().zip(&keys).filter(|(m, _)| m.value != 0) {
+            version_condition_no_doc(w, env, member.version, false, 2)?;
+            writeln!(
+                w,
+                "\t\tif self.contains(Self::{}) {{\n\
+                \t\t\tnames.push(\"{}\");\n\
+                \t\t}}",
+                member.name, key
+            )?;
+        }
+        writeln!(
+            w,
+            "\t\tlet unknown = self.bits() & !Self::all().bits();\n\
+            \t\tlet unknown = unknown.to_string();\n\
+            \t\tif unknown != \"0\" {{\n\
+            \t\t\tnames.push(&unknown);\n\
+            \t\t}}\n\
+            \t\tserializer.serialize_str(&names.join(\"|\"))\n\
+            \t}}\n\
+            }}\n"
+        )?;
+
+        // Generate Deserialize trait implementation, also accepting the integer value.
+        writeln!(w, "#[cfg(feature = \"serde\")]")?;
+        version_condition(w, env, flags.version, false, 0)?;
+        writeln!(
+            w,
+            "impl<'de> serde::Deserialize<'de> for {name} {{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {{
+            type Value = {name};
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+                f.write_str(\"{name} names separated by `|` or a value\")
+            }}
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<{name}, E> {{
+                let mut flags = {name}::empty();
+                for name in value.split('|').map(str::trim).filter(|name| !name.is_empty()) {{
+                    flags |= match name {{",
+            name = flags.name
+        )?;
+        for (member, key) in members.iter().zip(&keys) {
+            version_condition_no_doc(w, env, member.version, false, 6)?;
+            writeln!(
+                w,
+                "\t\t\t\t\t\t\"{}\" => {}::{},",
+                key, flags.name, member.name
+            )?;
+        }
+        writeln!(
+            w,
+            "                        _ => match name.parse::<u32>() {{
+                            Ok(bits) => unsafe {{ {name}::from_bits_unchecked(bits) }},
+                            Err(_) => return Err(E::invalid_value(serde::de::Unexpected::Str(name), &self)),
+                        }},
+                    }};
+                }}
+                Ok(flags)
+            }}
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<{name}, E> {{
+                let value: u32 = std::convert::TryFrom::try_from(value)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(value), &self))?;
+                Ok(unsafe {{ {name}::from_bits_unchecked(value) }})
+            }}
+        }}
+
+        deserializer.deserialize_any(Visitor)
+    }}
+}}
+",
+            name = flags.name
+        )?;
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::tests::{env, generated};
+
+    #[test]
+    fn serde_unknown_bits() {
+        let env = env(r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+generate_serde = true
+
+[[object]]
+name = "Test.Options"
+status = "generate"
+"#);
+        let analysis = &env.analysis.flags[0];
+        let flags = analysis.type_(&env.library);
+        let config = &env.config.objects[&analysis.full_name];
+        let code = generated(|w| generate_flags(&env, w, flags, config, analysis));
+        assert!(code.contains(
+            "\t\tlet unknown = self.bits() & !Self::all().bits();\n\
+             \t\tlet unknown = unknown.to_string();\n\
+             \t\tif unknown != \"0\" {\n\
+             \t\t\tnames.push(&unknown);\n\
+             \t\t}\n\
+             \t\tserializer.serialize_str(&names.join(\"|\"))\n"
+        ));
+        assert!(code.contains(
+            "\t\t\t\t\t\t\"italic\" => Options::ITALIC,\n\
+             \t\t\t\t\t\t_ => match name.parse::<u32>() {\n\
+             \t\t\t\t\t\t\tOk(bits) => unsafe { Options::from_bits_unchecked(bits) },\n"
+        ));
+        assert!(code.contains("\t\t\t\tOk(unsafe { Options::from_bits_unchecked(value) })\n"));
+    }
+
+    #[test]
+    fn display_and_from_str_use_nicks() {
+        let env = env(r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+generate = ["Test.Options", "Test.Hints"]
+"#);
+        let flags = |name| {
+            let analysis = env
+                .analysis
+                .flags
+                .iter()
+                .find(|f| f.full_name == name)
+                .unwrap();
+            let config = &env.config.objects[name];
+            generated(|w| generate_flags(&env, w, analysis.type_(&env.library), config, analysis))
+        };
+
+        let code = flags("Test.Options");
+        assert!(code.contains(
+            "\t\t#[cfg(any(feature = \"v1_2\", feature = \"dox\"))]\n\
+             \t\t#[cfg_attr(feature = \"dox\", doc(cfg(feature = \"v1_2\")))]\n\
+             \t\tconst ITALIC = 2;\n"
+        ));
+        // `NONE` is contained in any value
+        assert!(code.contains(
+            "\t\tlet mut nicks = Vec::new();\n\
+             \t\tif self.contains(Self::BOLD) {\n\
+             \t\t\tnicks.push(\"bold\");\n\
+             \t\t}\n\
+             \t\t#[cfg(any(feature = \"v1_2\", feature = \"dox\"))]\n\
+             \t\tif self.contains(Self::ITALIC) {\n\
+             \t\t\tnicks.push(\"italic\");\n\
+             \t\t}\n\
+             \t\tf.write_str(&nicks.join(\"|\"))\n"
+        ));
+        assert!(code.contains(
+            "\t\t\tflags |= match nick {\n\
+             \t\t\t\t\"none\" => Self::NONE,\n\
+             \t\t\t\t\"bold\" => Self::BOLD,\n\
+             \t\t\t\t#[cfg(any(feature = \"v1_2\", feature = \"dox\"))]\n\
+             \t\t\t\t\"italic\" => Self::ITALIC,\n\
+             \t\t\t\t_ => return Err(glib::bool_error!(\"Invalid value '{}' for Options\", nick)),\n"
+        ));
+
+        // `clip` has no nick
+        let code = flags("Test.Hints");
+        assert!(code.contains("\t\t<Self as fmt::Debug>::fmt(self, f)\n"));
+        assert!(!code.contains("FromStr"));
+    }
+}
@@ -2,7 +2,7 @@ use super::{
     function_body_chunk,
     general::{
         cfg_condition, cfg_deprecated, doc_alias, doc_hidden, not_version_condition,
-        version_condition,
+        source_position, version_condition,
     },
     parameter::ToParameter,
     return_value::{out_parameters_as_return, ToReturnValue},
@@ -12,8 +12,10 @@ use crate::{
     analysis::{
         self,
         bounds::{Bound, Bounds},
+        function_parameters::CParameter,
         functions::Visibility,
         namespaces,
+        trampolines::Trampoline,
         try_from_glib::TryFromGlib,
     },
     chunk::{ffi_function_todo, Chunk},
@@ -81,6 +83,7 @@ pub fn generate(
 
     writeln!(w)?;
     if !in_trait || only_declaration {
+        source_position(w, env, analysis.source_position.as_ref(), indent)?;
         cfg_deprecated(w, env, analysis.deprecated_version, commented, indent)?;
     }
     cfg_condition(w, &analysis.cfg_condition, commented, indent)?;
@@ -184,7 +187,7 @@ pub fn declaration(env: &Env, analysis: &analysis::functions::Info) -> String {
             param_str.push_str(", ")
         }
         let c_par = &analysis.parameters.c_parameters[par.ind_c];
-        let s = c_par.to_parameter(env, &analysis.bounds);
+        let s = parameter_declaration(env, analysis, c_par);
         param_str.push_str(&s);
     }
 
@@ -227,7 +230,7 @@ pub fn declaration_futures(env: &Env, analysis: &analysis::functions::Info) -> S
             param_str.push_str(", ")
         }
 
-        let s = c_par.to_parameter(env, &analysis.bounds);
+        let s = parameter_declaration(env, analysis, c_par);
         param_str.push_str(&s);
     }
 
@@ -239,6 +242,27 @@ pub fn declaration_futures(env: &Env, analysis: &analysis::functions::Info) -> S
     )
 }
 
+fn parameter_declaration(
+    env: &Env,
+    analysis: &analysis::functions::Info,
+    c_par: &CParameter,
+) -> String {
+    match progress_callback(analysis) {
+        // The progress callback of async functions is taken boxed
+        Some(progress) if progress.name == c_par.name => {
+            format!("{}: {}", c_par.name, progress.bound_name)
+        }
+        _ => c_par.to_parameter(env, &analysis.bounds),
+    }
+}
+
+fn progress_callback(analysis: &analysis::functions::Info) -> Option<&Trampoline> {
+    analysis
+        .trampoline
+        .as_ref()
+        .and_then(|trampoline| trampoline.progress_callback.as_ref())
+}
+
 pub fn bound_to_string(bound: &Bound, r#async: bool) -> String {
     use crate::analysis::bounds::BoundType::*;
 
@@ -404,9 +428,14 @@ pub fn body_chunk_futures(
     }
     let skip = if async_future.is_method { 1 } else { 0 };
 
+    let progress_name = progress_callback(analysis).map(|progress| progress.name.as_str());
+
     // Skip the instance parameter
     for par in analysis.parameters.rust_parameters.iter().skip(skip) {
-        if par.name == "cancellable" || par.name == "callback" {
+        if par.name == "cancellable"
+            || par.name == "callback"
+            || Some(par.name.as_str()) == progress_name
+        {
             continue;
         }
 
@@ -464,6 +493,9 @@ pub fn body_chunk_futures(
             writeln!(body, "\t\tSome(&cancellable),")?;
         } else if par.name == "callback" {
             continue;
+        } else if Some(par.name.as_str()) == progress_name {
+            // The boxed closure is moved into the async call
+            writeln!(body, "\t\t{},", par.name)?;
         } else {
             let c_par = &analysis.parameters.c_parameters[par.ind_c];
 
@@ -491,3 +523,65 @@ pub fn body_chunk_futures(
 
     Ok(body)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::tests::{env, generated};
+
+    #[test]
+    fn async_progress_callback() {
+        let env = env(r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+manual = ["GLib.Error", "Gio.AsyncResult", "Gio.Cancellable"]
+
+[[object]]
+name = "Test.Widget"
+status = "generate"
+    [[object.function]]
+    name = "start_copy"
+    finish_function = "test_widget_copy_finish"
+"#);
+        let analysis = env.analysis.objects["Test.Widget"]
+            .functions
+            .iter()
+            .find(|f| f.name == "start_copy")
+            .unwrap();
+        let code = generated(|w| generate(w, &env, analysis, None, None, true, false, 1));
+        assert_eq!(
+            &code[..code.find("\n\n\t\n").unwrap()],
+            "\n\
+             \tfn start_copy<P: FnOnce(Result<(), glib::Error>) + Send + 'static>(&self, destination: &str, count: i32, \
+             cancellable: Option<&gio::Cancellable>, progress_callback: Option<Box_<dyn FnMut(i64, i64) + Send + 'static>>, callback: P) {\n\
+             \t\tlet user_data: Box_<(P, RefCell<Option<Box_<dyn FnMut(i64, i64) + Send + 'static>>>)> = \
+             Box_::new((callback, RefCell::new(progress_callback)));\n\
+             \t\tunsafe extern \"C\" fn start_copy_trampoline<P: FnOnce(Result<(), glib::Error>) + Send + 'static>(\
+             _source_object: *mut glib::gobject_ffi::GObject, res: *mut gio::ffi::GAsyncResult, user_data: glib::ffi::gpointer) {\n\
+             \t\t\tlet mut error = ptr::null_mut();\n\
+             \t\t\tlet _ = ffi::test_widget_copy_finish(_source_object as *mut _, res, &mut error);\n\
+             \t\t\tlet result = if error.is_null() { Ok(()) } else { Err(from_glib_full(error)) };\n\
+             \t\t\tlet callback: Box_<(P, RefCell<Option<Box_<dyn FnMut(i64, i64) + Send + 'static>>>)> = \
+             Box_::from_raw(user_data as *mut _);\n\
+             \t\t\tlet callback = callback.0;\n\
+             \t\t\tcallback(result);\n\
+             \t\t}\n\
+             \t\tlet callback = start_copy_trampoline::<P>;\n\
+             \t\tunsafe extern \"C\" fn progress_callback_func<P: FnOnce(Result<(), glib::Error>) + Send + 'static>(\
+             current_num_bytes: i64, total_num_bytes: i64, user_data: glib::ffi::gpointer) {\n\
+             \t\t\tlet callback: &(P, RefCell<Option<Box_<dyn FnMut(i64, i64) + Send + 'static>>>) = &*(user_data as *mut _);\n\
+             \t\t\tlet mut progress_callback = callback.1.borrow_mut();\n\
+             \t\t\tlet progress_callback = progress_callback.as_mut().expect(\"cannot get closure...\");\n\
+             \t\t\t(*progress_callback)(current_num_bytes, total_num_bytes);\n\
+             \t\t}\n\
+             \t\tlet progress_callback = if user_data.1.borrow().is_some() { Some(progress_callback_func::<P> as _) } else { None };\n\
+             \t\tlet user_data = Box_::into_raw(user_data) as glib::ffi::gpointer;\n\
+             \t\tunsafe {\n\
+             \t\t\tffi::test_widget_start_copy(self.as_ref().to_glib_none().0, destination.to_glib_none().0, count, \
+             cancellable.to_glib_none().0, progress_callback, user_data, Some(callback), user_data);\n\
+             \t\t}\n\
+             \t}"
+        );
+    }
+}
@@ -405,39 +405,7 @@ impl Builder {
         }
 
         let mut body = Vec::new();
-        let mut arguments = Vec::new();
-
-        for par in trampoline.parameters.transformations.iter() {
-            if par.name == "this"
-                || trampoline.parameters.c_parameters[par.ind_c].is_real_gpointer(env)
-            {
-                continue;
-            }
-            let ty_name = match RustType::try_new(env, par.typ) {
-                Ok(x) => x.into_string(),
-                _ => String::new(),
-            };
-            let nullable = trampoline.parameters.rust_parameters[par.ind_rust].nullable;
-            let is_fundamental =
-                add_chunk_for_type(env, par.typ, par, &mut body, &ty_name, nullable);
-            if is_gstring(&ty_name) {
-                if *nullable {
-                    arguments.push(Chunk::Name(format!("{}.as_ref().as_deref()", par.name)));
-                } else {
-                    arguments.push(Chunk::Name(format!("{}.as_str()", par.name)));
-                }
-                continue;
-            }
-            if *nullable && !is_fundamental {
-                arguments.push(Chunk::Name(format!("{}.as_ref().as_ref()", par.name)));
-                continue;
-            }
-            arguments.push(Chunk::Name(format!(
-                "{}{}",
-                if is_fundamental { "" } else { "&" },
-                par.name
-            )));
-        }
+        let arguments = trampoline_arguments(env, trampoline, &mut body);
 
         let func = trampoline
             .parameters
@@ -649,38 +617,9 @@ impl Builder {
 
         let extern_func = Chunk::ExternCFunc {
             name: format!("{}_func", trampoline.name),
-            parameters: trampoline
-                .parameters
-                .c_parameters
-                .iter()
-                .skip(1) // to skip the generated this
-                .map(|p| {
-                    if p.is_real_gpointer(env) {
-                        Param {
-                            name: p.name.clone(),
-                            typ: use_glib_if_needed(env, "ffi::gpointer"),
-                        }
-                    } else {
-                        Param {
-                            name: p.name.clone(),
-                            typ: crate::analysis::ffi_type::ffi_type(env, p.typ, &p.c_type)
-                                .expect("failed to write c_type")
-                                .into_string(),
-                        }
-                    }
-                })
-                .collect::<Vec<_>>(),
+            parameters: trampoline_c_parameters(env, trampoline),
             body: Box::new(Chunk::Chunks(body)),
-            return_value: if trampoline.ret.c_type != "void" {
-                let p = &trampoline.ret;
-                Some(
-                    crate::analysis::ffi_type::ffi_type(env, p.typ, &p.c_type)
-                        .expect("failed to write c_type")
-                        .into_string(),
-                )
-            } else {
-                None
-            },
+            return_value: trampoline_return_value(env, trampoline),
             bounds: bounds.to_owned(),
         };
 
@@ -716,14 +655,25 @@ impl Builder {
         chunks: &mut Vec<Chunk>,
         trampoline: &AsyncTrampoline,
     ) {
+        // The progress callback lives in the user data of the async callback, so that it's
+        // dropped once the operation is finished.
+        let user_data_type = match trampoline.progress_callback {
+            Some(ref progress) => format!(
+                "({}, RefCell<{}>)",
+                trampoline.bound_name, progress.bound_name
+            ),
+            None => trampoline.bound_name.to_string(),
+        };
         chunks.push(Chunk::Let {
             name: "user_data".to_string(),
             is_mut: false,
-            value: Box::new(Chunk::Custom("Box_::new(callback)".into())),
-            type_: Some(Box::new(Chunk::Custom(format!(
-                "Box_<{}>",
-                trampoline.bound_name
-            )))),
+            value: Box::new(Chunk::Custom(match trampoline.progress_callback {
+                Some(ref progress) => {
+                    format!("Box_::new((callback, RefCell::new({})))", progress.name)
+                }
+                None => "Box_::new(callback)".into(),
+            })),
+            type_: Some(Box::new(Chunk::Custom(format!("Box_<{}>", user_data_type)))),
         });
 
         let mut finish_args = vec![];
@@ -872,11 +822,11 @@ impl Builder {
             name: "callback".to_string(),
             is_mut: false,
             value: Box::new(Chunk::Custom("Box_::from_raw(user_data as *mut _)".into())),
-            type_: Some(Box::new(Chunk::Custom(format!(
-                "Box_<{}>",
-                trampoline.bound_name
-            )))),
+            type_: Some(Box::new(Chunk::Custom(format!("Box_<{}>", user_data_type)))),
         });
+        if trampoline.progress_callback.is_some() {
+            body.push(Chunk::Custom("let callback = callback.0;".into()));
+        }
         body.push(Chunk::Call {
             func_name: "callback".to_string(),
             arguments: vec![Chunk::Name("result".to_string())],
@@ -917,6 +867,96 @@ impl Builder {
             type_: None,
         };
         chunks.push(chunk);
+
+        if let Some(ref progress) = trampoline.progress_callback {
+            self.add_async_progress_trampoline(env, chunks, trampoline, progress, &user_data_type);
+            chunks.push(Chunk::Custom(format!(
+                "let user_data = Box_::into_raw(user_data) as {};",
+                use_glib_if_needed(env, "ffi::gpointer")
+            )));
+        }
+    }
+
+    fn add_async_progress_trampoline(
+        &self,
+        env: &Env,
+        chunks: &mut Vec<Chunk>,
+        trampoline: &AsyncTrampoline,
+        progress: &Trampoline,
+        user_data_type: &str,
+    ) {
+        let mut body = Vec::new();
+        let arguments = trampoline_arguments(env, progress, &mut body);
+        let user_data = progress
+            .parameters
+            .c_parameters
+            .last()
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| "Unknown".to_owned());
+
+        body.push(Chunk::Let {
+            name: "callback".to_owned(),
+            is_mut: false,
+            value: Box::new(Chunk::Custom(format!("&*({} as *mut _)", user_data))),
+            type_: Some(Box::new(Chunk::Custom(format!("&{}", user_data_type)))),
+        });
+        body.push(Chunk::Custom(format!(
+            "let mut {} = callback.1.borrow_mut();",
+            progress.name
+        )));
+        if *progress.nullable {
+            body.push(Chunk::Custom(format!(
+                "let {0} = {0}.as_mut().expect(\"cannot get closure...\");",
+                progress.name
+            )));
+        }
+        {
+            use crate::writer::to_code::ToCode;
+            body.push(Chunk::Custom(format!(
+                "{}(*{})({});",
+                if progress.ret.c_type != "void" {
+                    "let res = "
+                } else {
+                    ""
+                },
+                progress.name,
+                arguments
+                    .iter()
+                    .flat_map(|arg| arg.to_code(env))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )));
+        }
+        if progress.ret.c_type != "void" {
+            use crate::codegen::trampoline_to_glib::TrampolineToGlib;
+
+            body.push(Chunk::Custom(format!(
+                "res{}",
+                progress.ret.trampoline_to_glib(env)
+            )));
+        }
+
+        chunks.push(Chunk::ExternCFunc {
+            name: format!(
+                "{}_func<{}: {}>",
+                progress.name, trampoline.bound_name, trampoline.callback_type
+            ),
+            parameters: trampoline_c_parameters(env, progress),
+            body: Box::new(Chunk::Chunks(body)),
+            return_value: trampoline_return_value(env, progress),
+            bounds: String::new(),
+        });
+        chunks.push(Chunk::Custom(if *progress.nullable {
+            format!(
+                "let {0} = if user_data.1.borrow().is_some() {{ Some({0}_func::<{1}> as _) }} else {{ None }};",
+                progress.name, trampoline.bound_name
+            )
+        } else {
+            format!(
+                "let {0} = Some({0}_func::<{1}> as _);",
+                progress.name, trampoline.bound_name
+            )
+        }));
     }
 
     fn array_length(&self, param: &analysis::Parameter) -> Option<&String> {
@@ -1321,6 +1361,79 @@ fn type_mem_mode(env: &Env, parameter: &library::Parameter) -> Chunk {
     }
 }
 
+/// Converts the parameters of a callback trampoline into the arguments of the Rust closure.
+fn trampoline_arguments(env: &Env, trampoline: &Trampoline, body: &mut Vec<Chunk>) -> Vec<Chunk> {
+    let mut arguments = Vec::new();
+
+    for par in trampoline.parameters.transformations.iter() {
+        if par.name == "this" || trampoline.parameters.c_parameters[par.ind_c].is_real_gpointer(env)
+        {
+            continue;
+        }
+        let ty_name = match RustType::try_new(env, par.typ) {
+            Ok(x) => x.into_string(),
+            _ => String::new(),
+        };
+        let nullable = trampoline.parameters.rust_parameters[par.ind_rust].nullable;
+        let is_fundamental = add_chunk_for_type(env, par.typ, par, body, &ty_name, nullable);
+        if is_gstring(&ty_name) {
+            if *nullable {
+                arguments.push(Chunk::Name(format!("{}.as_ref().as_deref()", par.name)));
+            } else {
+                arguments.push(Chunk::Name(format!("{}.as_str()", par.name)));
+            }
+            continue;
+        }
+        if *nullable && !is_fundamental {
+            arguments.push(Chunk::Name(format!("{}.as_ref().as_ref()", par.name)));
+            continue;
+        }
+        arguments.push(Chunk::Name(format!(
+            "{}{}",
+            if is_fundamental { "" } else { "&" },
+            par.name
+        )));
+    }
+    arguments
+}
+
+fn trampoline_c_parameters(env: &Env, trampoline: &Trampoline) -> Vec<Param> {
+    trampoline
+        .parameters
+        .c_parameters
+        .iter()
+        .skip(1) // to skip the generated this
+        .map(|p| {
+            if p.is_real_gpointer(env) {
+                Param {
+                    name: p.name.clone(),
+                    typ: use_glib_if_needed(env, "ffi::gpointer"),
+                }
+            } else {
+                Param {
+                    name: p.name.clone(),
+                    typ: crate::analysis::ffi_type::ffi_type(env, p.typ, &p.c_type)
+                        .expect("failed to write c_type")
+                        .into_string(),
+                }
+            }
+        })
+        .collect()
+}
+
+fn trampoline_return_value(env: &Env, trampoline: &Trampoline) -> Option<String> {
+    if trampoline.ret.c_type != "void" {
+        let p = &trampoline.ret;
+        Some(
+            crate::analysis::ffi_type::ffi_type(env, p.typ, &p.c_type)
+                .expect("failed to write c_type")
+                .into_string(),
+        )
+    } else {
+        None
+    }
+}
+
 fn add_chunk_for_type(
     env: &Env,
     typ_: library::TypeId,
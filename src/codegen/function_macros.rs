@@ -0,0 +1,61 @@
+use crate::{
+    analysis::function_macros,
+    codegen::general::{
+        self, cfg_deprecated, cfg_deprecated_string, doc_alias, version_condition,
+        version_condition_string,
+    },
+    env::Env,
+    file_saver,
+};
+use std::path::Path;
+
+pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>) {
+    let function_macros = function_macros::analyze(env);
+    if function_macros.is_empty() {
+        return;
+    }
+
+    let path = root_path.join("function_macros.rs");
+    file_saver::save_to_file(path, &env.config, |w| {
+        general::start_comments(w, &env.config)?;
+
+        for func_macro in &function_macros {
+            let params = func_macro
+                .parameters
+                .iter()
+                .map(|(name, typ)| format!("{}: {}", name, typ))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let ret = func_macro
+                .ret
+                .as_ref()
+                .map(|ret| format!(" -> {}", ret))
+                .unwrap_or_default();
+
+            writeln!(w)?;
+            cfg_deprecated(w, env, func_macro.deprecated_version, false, 0)?;
+            version_condition(w, env, func_macro.version, false, 0)?;
+            doc_alias(w, &func_macro.c_identifier, "", 0)?;
+            writeln!(w, "#[inline]")?;
+            writeln!(w, "pub fn {}({}){} {{", func_macro.name, params, ret)?;
+            writeln!(w, "\t{}", func_macro.body)?;
+            writeln!(w, "}}")?;
+        }
+
+        Ok(())
+    });
+
+    mod_rs.push("\nmod function_macros;".into());
+    for func_macro in &function_macros {
+        if let Some(cfg) = version_condition_string(env, func_macro.version, false, 0) {
+            mod_rs.push(cfg);
+        }
+        if let Some(cfg) = cfg_deprecated_string(env, func_macro.deprecated_version, false, 0) {
+            mod_rs.push(cfg);
+        }
+        mod_rs.push(format!(
+            "pub use self::function_macros::{};",
+            func_macro.name
+        ));
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     config::{derives::Derive, Config},
     env::Env,
     gir_version::VERSION,
+    library,
     nameutil::use_glib_type,
     version::Version,
     writer::primitives::tabs,
@@ -120,6 +121,7 @@ pub fn define_object_type(
     glib_func_name: &str,
     is_interface: bool,
     parents: &[StatusedTypeId],
+    position: Option<&library::SourcePosition>,
 ) -> Result<()> {
     let sys_crate_name = env.main_sys_crate_name();
     let class_name = {
@@ -139,6 +141,7 @@ pub fn define_object_type(
         .collect();
 
     writeln!(w)?;
+    source_position(w, env, position, 0)?;
     writeln!(w, "{} {{", use_glib_type(env, "wrapper!"))?;
     if parents.is_empty() {
         writeln!(
@@ -279,8 +282,10 @@ pub fn define_boxed_type(
     clear_function_expression: &Option<String>,
     get_type_fn: Option<(String, Option<Version>)>,
     derive: &[Derive],
+    position: Option<&library::SourcePosition>,
 ) -> Result<()> {
     writeln!(w)?;
+    source_position(w, env, position, 0)?;
 
     if let Some((ref get_type_fn, get_type_version)) = get_type_fn {
         if get_type_version.is_some() {
@@ -353,9 +358,11 @@ pub fn define_auto_boxed_type(
     clear_function_expression: &Option<String>,
     get_type_fn: &str,
     derive: &[Derive],
+    position: Option<&library::SourcePosition>,
 ) -> Result<()> {
     let sys_crate_name = env.main_sys_crate_name();
     writeln!(w)?;
+    source_position(w, env, position, 0)?;
     writeln!(w, "{} {{", use_glib_type(env, "wrapper!"))?;
     derives(w, derive, 1)?;
     writeln!(
@@ -440,8 +447,10 @@ pub fn define_shared_type(
     unref_fn: &str,
     get_type_fn: Option<(String, Option<Version>)>,
     derive: &[Derive],
+    position: Option<&library::SourcePosition>,
 ) -> Result<()> {
     writeln!(w)?;
+    source_position(w, env, position, 0)?;
 
     if let Some((ref get_type_fn, get_type_version)) = get_type_fn {
         if get_type_version.is_some() {
@@ -671,6 +680,20 @@ pub fn doc_alias(w: &mut dyn Write, name: &str, comment_prefix: &str, indent: us
     )
 }
 
+pub fn source_position(
+    w: &mut dyn Write,
+    env: &Env,
+    position: Option<&library::SourcePosition>,
+    indent: usize,
+) -> Result<()> {
+    match position {
+        Some(position) if env.config.generate_source_positions => {
+            writeln!(w, "{}// C: {}", tabs(indent), position)
+        }
+        _ => Ok(()),
+    }
+}
+
 pub fn doc_hidden(
     w: &mut dyn Write,
     doc_hidden: bool,
@@ -738,4 +761,28 @@ mod tests {
         assert_eq!(escape_string("no escaping here"), "no escaping here");
         assert_eq!(escape_string(r#"'"\"#), r#"'\"\\"#);
     }
+    #[test]
+    fn source_position_above_wrapper() {
+        let env = crate::env::tests::env(
+            "[options]\nwork_mode = \"normal\"\ngenerate_source_positions = true\n",
+        );
+        let position = library::SourcePosition {
+            filename: "test/widget.h".into(),
+            line: 42,
+        };
+        let code = crate::env::tests::generated(|w| {
+            define_object_type(
+                w,
+                &env,
+                "Widget",
+                "TestWidget",
+                Some("TestWidgetClass"),
+                "test_widget_get_type",
+                false,
+                &[],
+                Some(&position),
+            )
+        });
+        assert!(code.starts_with("\n// C: test/widget.h:42\nglib::wrapper! {\n"));
+    }
 }
@@ -5,10 +5,12 @@ mod alias;
 mod child_properties;
 mod constants;
 mod doc;
+mod doc_sections;
 mod enums;
 mod flags;
 pub mod function;
 mod function_body_chunk;
+mod function_macros;
 mod functions;
 mod general;
 mod object;
@@ -22,7 +24,9 @@ mod return_value;
 mod signal;
 mod signal_body;
 mod special_functions;
-mod sys;
+mod subclass;
+mod subclasses;
+pub mod sys;
 mod trait_impls;
 mod trampoline;
 mod trampoline_from_glib;
@@ -36,6 +40,9 @@ pub fn generate(env: &Env) {
         WorkMode::Sys => sys::generate(env),
         WorkMode::Doc => doc::generate(env),
         WorkMode::DisplayNotBound => {}
+        WorkMode::Subclass => {
+            subclasses::generate(env, &env.config.auto_path);
+        }
     }
 }
 
@@ -52,13 +59,20 @@ fn normal_generate(env: &Env) {
     alias::generate(env, root_path, &mut mod_rs);
     functions::generate(env, root_path, &mut mod_rs);
     constants::generate(env, root_path, &mut mod_rs);
+    function_macros::generate(env, root_path, &mut mod_rs);
+    doc_sections::generate(env, root_path, &mut mod_rs);
+
+    if subclasses::generate(env, &root_path.join("subclass")) {
+        mod_rs.push("".to_owned());
+        mod_rs.push("pub mod subclass;".to_owned());
+    }
 
     generate_mod_rs(env, root_path, &mod_rs, &traits);
 }
 
 pub fn generate_mod_rs(env: &Env, root_path: &Path, mod_rs: &[String], traits: &[String]) {
     let path = root_path.join("mod.rs");
-    save_to_file(path, env.config.make_backup, |w| {
+    save_to_file(path, &env.config, |w| {
         general::start_comments(w, &env.config)?;
         general::write_vec(w, mod_rs)?;
         writeln!(w)?;
@@ -71,8 +85,41 @@ pub fn generate_mod_rs(env: &Env, root_path: &Path, mod_rs: &[String], traits: &
 
 pub fn generate_single_version_file(env: &Env) {
     if let Some(ref path) = env.config.single_version_file {
-        save_to_file(path, env.config.make_backup, |w| {
+        save_to_file(path, &env.config, |w| {
             general::single_version_file(w, &env.config, "")
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn subclass_mode_generates_only_subclasses() {
+        let mut env = crate::env::tests::env(
+            r#"
+[options]
+work_mode = "subclass"
+min_cfg_version = "1.0"
+generate = ["Test.Widget", "Test.Label", "Test.Mode"]
+"#,
+        );
+        let dir = std::env::temp_dir().join(format!("gir-subclass-mode-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        env.config.auto_path = dir.clone();
+
+        generate(&env);
+        let mut files: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        // `Label` has no virtual methods and `Mode` isn't a class
+        assert_eq!(files, ["mod.rs", "widget.rs"]);
+        let mod_rs = fs::read_to_string(dir.join("mod.rs")).unwrap();
+        assert!(mod_rs.contains("pub use self::widget::{WidgetImpl, WidgetImplExt};"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
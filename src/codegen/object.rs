@@ -28,6 +28,7 @@ pub fn generate(
         &analysis.get_type,
         analysis.is_interface,
         &analysis.supertypes,
+        env.library.type_(analysis.type_id).source_position(),
     )?;
 
     if need_generate_inherent(analysis) {
@@ -159,6 +160,10 @@ pub fn generate(
         generate_trait(w, env, analysis)?;
     }
 
+    if analysis.signals.iter().any(|s| s.stream.is_some()) {
+        signal::generate_stream_type(w, env)?;
+    }
+
     if generate_display_trait && !analysis.specials.has_trait(Type::Display) {
         writeln!(w, "\nimpl fmt::Display for {} {{", analysis.name,)?;
         // Generate Display trait implementation.
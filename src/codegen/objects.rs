@@ -20,7 +20,7 @@ pub fn generate(env: &Env, root_path: &Path, mod_rs: &mut Vec<String>, traits: &
         path.set_extension("rs");
         info!("Generating file {:?}", path);
 
-        save_to_file(path, env.config.make_backup, |ref mut w| {
+        save_to_file(path, &env.config, |ref mut w| {
             super::object::generate(w, env, class_analysis, generate_display_trait)
         });
 
@@ -1,8 +1,9 @@
 use super::{function, general, trait_impls};
 use crate::{
-    analysis::{self, special_functions::Type},
+    analysis::{self, conversion_type::ConversionType, special_functions::Type},
     env::Env,
     library,
+    nameutil::use_glib_type,
 };
 use std::io::{Result, Write};
 
@@ -12,7 +13,15 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
     general::start_comments(w, &env.config)?;
     general::uses(w, env, &analysis.imports, type_.version)?;
 
-    if analysis.is_boxed {
+    if analysis.is_direct {
+        define_direct_type(
+            w,
+            env,
+            analysis,
+            &type_.c_type,
+            type_.source_position.as_ref(),
+        )?;
+    } else if analysis.is_boxed {
         if let Some((ref glib_get_type, _)) = analysis.glib_get_type {
             general::define_auto_boxed_type(
                 w,
@@ -23,6 +32,7 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
                 &analysis.clear_function_expression,
                 glib_get_type,
                 &analysis.derives,
+                type_.source_position.as_ref(),
             )?;
         } else {
             panic!(
@@ -49,6 +59,7 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
                 }
             }),
             &analysis.derives,
+            type_.source_position.as_ref(),
         )?;
     } else if let (Some(copy_fn), Some(free_fn)) = (
         analysis.specials.traits().get(&Type::Copy),
@@ -71,6 +82,7 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
                 }
             }),
             &analysis.derives,
+            type_.source_position.as_ref(),
         )?;
     } else {
         panic!(
@@ -79,14 +91,16 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
         );
     }
 
-    if analysis
-        .functions
-        .iter()
-        .any(|f| f.status.need_generate() && !f.visibility.hidden())
+    if !analysis.fields.is_empty()
+        || analysis
+            .functions
+            .iter()
+            .any(|f| f.status.need_generate() && !f.visibility.hidden())
     {
         writeln!(w)?;
         write!(w, "impl {} {{", analysis.name)?;
 
+        generate_field_accessors(w, analysis)?;
         for func_analysis in &analysis.functions {
             function::generate(
                 w,
@@ -115,6 +129,14 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
         analysis.version,
     )?;
 
+    if analysis.is_direct {
+        direct_type_impls(w, env, analysis, &type_.c_type)?;
+        let config = &env.config.objects[&analysis.full_name];
+        if config.generate_serde.unwrap_or(env.config.generate_serde) {
+            direct_serde_impls(w, analysis)?;
+        }
+    }
+
     if analysis.concurrency != library::Concurrency::None {
         writeln!(w)?;
     }
@@ -136,6 +158,407 @@ pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::record::Info)
     Ok(())
 }
 
+fn define_direct_type(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::record::Info,
+    glib_name: &str,
+    position: Option<&library::SourcePosition>,
+) -> Result<()> {
+    writeln!(w)?;
+    general::source_position(w, env, position, 0)?;
+    general::derives(w, &analysis.derives, 0)?;
+    writeln!(w, "#[derive(Clone, Copy)]")?;
+    writeln!(w, "#[repr(transparent)]")?;
+    general::doc_alias(w, glib_name, "", 0)?;
+    writeln!(
+        w,
+        "pub struct {}({}::{});",
+        analysis.name,
+        env.main_sys_crate_name(),
+        glib_name
+    )
+}
+
+fn generate_field_accessors(w: &mut dyn Write, analysis: &analysis::record::Info) -> Result<()> {
+    for field in &analysis.fields {
+        let (get, set) = match field.conversion_type {
+            ConversionType::Scalar => (
+                format!("unsafe {{ from_glib(self.0.{}) }}", field.name),
+                format!("{}.into_glib()", field.getter_name),
+            ),
+            _ => (format!("self.0.{}", field.name), field.getter_name.clone()),
+        };
+        writeln!(w)?;
+        writeln!(
+            w,
+            "\tpub fn {}(&self) -> {} {{",
+            field.getter_name, field.typ
+        )?;
+        writeln!(w, "\t\t{}", get)?;
+        writeln!(w, "\t}}")?;
+        writeln!(w)?;
+        writeln!(
+            w,
+            "\tpub fn {}(&mut self, {}: {}) {{",
+            field.setter_name, field.getter_name, field.typ
+        )?;
+        writeln!(w, "\t\tself.0.{} = {};", field.name, set)?;
+        writeln!(w, "\t}}")?;
+    }
+    Ok(())
+}
+
+/// Translation traits of a direct record, passed to C as a pointer to the wrapped struct.
+fn direct_type_impls(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::record::Info,
+    glib_name: &str,
+) -> Result<()> {
+    let name = &analysis.name;
+    let ffi_name = format!("{}::{}", env.main_sys_crate_name(), glib_name);
+
+    if !analysis
+        .derives
+        .iter()
+        .any(|d| d.names.iter().any(|n| n == "Debug"))
+    {
+        writeln!(w)?;
+        writeln!(w, "impl fmt::Debug for {} {{", name)?;
+        writeln!(
+            w,
+            "\tfn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {{"
+        )?;
+        write!(w, "\t\tf.debug_struct(\"{}\")", name)?;
+        for field in &analysis.fields {
+            write!(
+                w,
+                "\n\t\t\t.field(\"{}\", &self.{}())",
+                field.name, field.getter_name
+            )?;
+        }
+        writeln!(w, "\n\t\t\t.finish()")?;
+        writeln!(w, "\t}}")?;
+        writeln!(w, "}}")?;
+    }
+
+    // Boxed records opting in keep using the boxed functions of their GType.
+    let free_full = if let Some((ref glib_get_type, _)) = analysis.glib_get_type {
+        writeln!(
+            w,
+            "
+impl StaticType for {name} {{
+\tfn static_type() -> {gtype} {{
+\t\tunsafe {{ from_glib({sys_crate_name}::{get_type}()) }}
+\t}}
+}}
+
+impl {valuetype} for {name} {{
+\ttype Type = Self;
+}}
+
+unsafe impl<'a> FromValue<'a> for {name} {{
+\ttype Checker = {checker}<Self>;
+
+\tunsafe fn from_value(value: &'a {gvalue}) -> Self {{
+\t\tlet ptr = {get_boxed}(value.to_glib_none().0);
+\t\tassert!(!ptr.is_null());
+\t\tfrom_glib_none(ptr as *const {ffi})
+\t}}
+}}
+
+impl ToValue for {name} {{
+\tfn to_value(&self) -> {gvalue} {{
+\t\tlet mut value = {gvalue}::for_value_type::<Self>();
+\t\tunsafe {{
+\t\t\t{set_boxed}(
+\t\t\t\tvalue.to_glib_none_mut().0,
+\t\t\t\tToGlibPtr::<*const {ffi}>::to_glib_none(self).0 as *const _,
+\t\t\t);
+\t\t}}
+\t\tvalue
+\t}}
+
+\tfn value_type(&self) -> {gtype} {{
+\t\tSelf::static_type()
+\t}}
+}}",
+            name = name,
+            ffi = ffi_name,
+            sys_crate_name = env.main_sys_crate_name(),
+            get_type = glib_get_type,
+            gtype = use_glib_type(env, "Type"),
+            gvalue = use_glib_type(env, "Value"),
+            valuetype = use_glib_type(env, "value::ValueType"),
+            checker = use_glib_type(env, "value::GenericValueTypeOrNoneChecker"),
+            get_boxed = use_glib_type(env, "gobject_ffi::g_value_get_boxed"),
+            set_boxed = use_glib_type(env, "gobject_ffi::g_value_set_boxed"),
+        )?;
+        format!(
+            "{}({}::{}(), ptr as *mut _)",
+            use_glib_type(env, "gobject_ffi::g_boxed_free"),
+            env.main_sys_crate_name(),
+            glib_get_type
+        )
+    } else {
+        format!("{}(ptr as *mut _)", use_glib_type(env, "ffi::g_free"))
+    };
+
+    writeln!(
+        w,
+        "
+#[doc(hidden)]
+impl Uninitialized for {name} {{
+\t#[inline]
+\tunsafe fn uninitialized() -> Self {{
+\t\tmem::zeroed()
+\t}}
+}}
+
+#[doc(hidden)]
+impl<'a> ToGlibPtr<'a, *const {ffi}> for {name} {{
+\ttype Storage = &'a Self;
+
+\t#[inline]
+\tfn to_glib_none(&'a self) -> Stash<'a, *const {ffi}, Self> {{
+\t\tStash(&self.0 as *const _, self)
+\t}}
+}}
+
+#[doc(hidden)]
+impl<'a> ToGlibPtrMut<'a, *mut {ffi}> for {name} {{
+\ttype Storage = &'a mut Self;
+
+\t#[inline]
+\tfn to_glib_none_mut(&'a mut self) -> StashMut<'a, *mut {ffi}, Self> {{
+\t\tlet ptr = &mut self.0 as *mut _;
+\t\tStashMut(ptr, self)
+\t}}
+}}
+
+#[doc(hidden)]
+impl FromGlibPtrNone<*const {ffi}> for {name} {{
+\tunsafe fn from_glib_none(ptr: *const {ffi}) -> Self {{
+\t\tSelf(*ptr)
+\t}}
+}}
+
+#[doc(hidden)]
+impl FromGlibPtrNone<*mut {ffi}> for {name} {{
+\tunsafe fn from_glib_none(ptr: *mut {ffi}) -> Self {{
+\t\tSelf(*ptr)
+\t}}
+}}
+
+#[doc(hidden)]
+impl FromGlibPtrBorrow<*const {ffi}> for {name} {{
+\tunsafe fn from_glib_borrow(ptr: *const {ffi}) -> Borrowed<Self> {{
+\t\tBorrowed::new(Self(*ptr))
+\t}}
+}}
+
+#[doc(hidden)]
+impl FromGlibPtrBorrow<*mut {ffi}> for {name} {{
+\tunsafe fn from_glib_borrow(ptr: *mut {ffi}) -> Borrowed<Self> {{
+\t\tBorrowed::new(Self(*ptr))
+\t}}
+}}
+
+#[doc(hidden)]
+impl FromGlibPtrFull<*mut {ffi}> for {name} {{
+\tunsafe fn from_glib_full(ptr: *mut {ffi}) -> Self {{
+\t\tlet res = Self(*ptr);
+\t\t{free_full};
+\t\tres
+\t}}
+}}
+
+#[doc(hidden)]
+impl FromGlibContainerAsVec<{ffi}, *mut {ffi}> for {name} {{
+\tunsafe fn from_glib_none_num_as_vec(ptr: *mut {ffi}, num: usize) -> Vec<Self> {{
+\t\tif num == 0 || ptr.is_null() {{
+\t\t\treturn Vec::new();
+\t\t}}
+\t\t(0..num).map(|i| Self(*ptr.add(i))).collect()
+\t}}
+
+\tunsafe fn from_glib_container_num_as_vec(ptr: *mut {ffi}, num: usize) -> Vec<Self> {{
+\t\tlet res = FromGlibContainerAsVec::from_glib_none_num_as_vec(ptr, num);
+\t\t{g_free}(ptr as *mut _);
+\t\tres
+\t}}
+
+\tunsafe fn from_glib_full_num_as_vec(ptr: *mut {ffi}, num: usize) -> Vec<Self> {{
+\t\tFromGlibContainerAsVec::from_glib_container_num_as_vec(ptr, num)
+\t}}
+}}
+
+#[doc(hidden)]
+impl FromGlibContainerAsVec<*mut {ffi}, *mut *mut {ffi}> for {name} {{
+\tunsafe fn from_glib_none_num_as_vec(ptr: *mut *mut {ffi}, num: usize) -> Vec<Self> {{
+\t\tif num == 0 || ptr.is_null() {{
+\t\t\treturn Vec::new();
+\t\t}}
+\t\t(0..num).map(|i| from_glib_none(*ptr.add(i))).collect()
+\t}}
+
+\tunsafe fn from_glib_container_num_as_vec(ptr: *mut *mut {ffi}, num: usize) -> Vec<Self> {{
+\t\tlet res = FromGlibContainerAsVec::from_glib_none_num_as_vec(ptr, num);
+\t\t{g_free}(ptr as *mut _);
+\t\tres
+\t}}
+
+\tunsafe fn from_glib_full_num_as_vec(ptr: *mut *mut {ffi}, num: usize) -> Vec<Self> {{
+\t\tif num == 0 || ptr.is_null() {{
+\t\t\treturn Vec::new();
+\t\t}}
+\t\tlet res = (0..num).map(|i| from_glib_full(*ptr.add(i))).collect();
+\t\t{g_free}(ptr as *mut _);
+\t\tres
+\t}}
+}}
+
+#[doc(hidden)]
+impl FromGlibPtrArrayContainerAsVec<*mut {ffi}, *mut *mut {ffi}> for {name} {{
+\tunsafe fn from_glib_none_as_vec(ptr: *mut *mut {ffi}) -> Vec<Self> {{
+\t\tFromGlibContainerAsVec::from_glib_none_num_as_vec(ptr, c_ptr_array_len(ptr))
+\t}}
+
+\tunsafe fn from_glib_container_as_vec(ptr: *mut *mut {ffi}) -> Vec<Self> {{
+\t\tFromGlibContainerAsVec::from_glib_container_num_as_vec(ptr, c_ptr_array_len(ptr))
+\t}}
+
+\tunsafe fn from_glib_full_as_vec(ptr: *mut *mut {ffi}) -> Vec<Self> {{
+\t\tFromGlibContainerAsVec::from_glib_full_num_as_vec(ptr, c_ptr_array_len(ptr))
+\t}}
+}}
+
+#[doc(hidden)]
+impl<'a> ToGlibContainerFromSlice<'a, *mut {ffi}> for {name} {{
+\ttype Storage = &'a [Self];
+
+\tfn to_glib_none_from_slice(t: &'a [Self]) -> (*mut {ffi}, &'a [Self]) {{
+\t\t(t.as_ptr() as *mut {ffi}, t)
+\t}}
+
+\tfn to_glib_container_from_slice(t: &'a [Self]) -> (*mut {ffi}, &'a [Self]) {{
+\t\t(ToGlibContainerFromSlice::to_glib_full_from_slice(t), t)
+\t}}
+
+\tfn to_glib_full_from_slice(t: &[Self]) -> *mut {ffi} {{
+\t\tunsafe {{
+\t\t\tlet res = {g_malloc0_n}(t.len() + 1, mem::size_of::<{ffi}>()) as *mut {ffi};
+\t\t\tstd::ptr::copy_nonoverlapping(t.as_ptr() as *const {ffi}, res, t.len());
+\t\t\tres
+\t\t}}
+\t}}
+}}",
+        name = name,
+        ffi = ffi_name,
+        free_full = free_full,
+        g_free = use_glib_type(env, "ffi::g_free"),
+        g_malloc0_n = use_glib_type(env, "ffi::g_malloc0_n"),
+    )
+}
+
+/// Serde implementations of a direct record, as a struct of its accessible fields.
+/// Missing fields are deserialized zeroed like the inaccessible ones.
+fn direct_serde_impls(w: &mut dyn Write, analysis: &analysis::record::Info) -> Result<()> {
+    let name = &analysis.name;
+    let keys = analysis
+        .fields
+        .iter()
+        .map(|f| f.getter_name.trim_end_matches('_'))
+        .collect::<Vec<_>>();
+
+    writeln!(w)?;
+    writeln!(w, "#[cfg(feature = \"serde\")]")?;
+    writeln!(
+        w,
+        "impl serde::Serialize for {name} {{\n\
+        \tfn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{\n\
+        \t\tuse serde::ser::SerializeStruct;\n\
+        \t\tlet mut state = serializer.serialize_struct(\"{name}\", {len})?;",
+        name = name,
+        len = keys.len(),
+    )?;
+    for (field, key) in analysis.fields.iter().zip(&keys) {
+        writeln!(
+            w,
+            "\t\tstate.serialize_field(\"{}\", &self.{}())?;",
+            key, field.getter_name
+        )?;
+    }
+    writeln!(w, "\t\tstate.end()\n\t}}\n}}")?;
+
+    writeln!(w)?;
+    writeln!(w, "#[cfg(feature = \"serde\")]")?;
+    writeln!(
+        w,
+        "impl<'de> serde::Deserialize<'de> for {name} {{
+\tfn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{
+\t\tconst FIELDS: &[&str] = &[{fields}];
+
+\t\tstruct Visitor;
+
+\t\timpl<'de> serde::de::Visitor<'de> for Visitor {{
+\t\t\ttype Value = {name};
+
+\t\t\tfn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+\t\t\t\tf.write_str(\"struct {name}\")
+\t\t\t}}
+
+\t\t\tfn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<{name}, A::Error> {{
+\t\t\t\tlet mut res = unsafe {{ {name}::uninitialized() }};
+{seq_fields}\t\t\t\tOk(res)
+\t\t\t}}
+
+\t\t\tfn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<{name}, A::Error> {{
+\t\t\t\tlet mut res = unsafe {{ {name}::uninitialized() }};
+\t\t\t\twhile let Some(key) = map.next_key::<String>()? {{
+\t\t\t\t\tmatch key.as_str() {{",
+        name = name,
+        fields = keys
+            .iter()
+            .map(|key| format!("\"{}\"", key))
+            .collect::<Vec<_>>()
+            .join(", "),
+        seq_fields = analysis
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| format!(
+                "\t\t\t\tres.{}(seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length({}, &self))?);\n",
+                field.setter_name, i
+            ))
+            .collect::<String>(),
+    )?;
+    for (field, key) in analysis.fields.iter().zip(&keys) {
+        writeln!(
+            w,
+            "\t\t\t\t\t\t\"{}\" => res.{}(map.next_value()?),",
+            key, field.setter_name
+        )?;
+    }
+    writeln!(
+        w,
+        "\t\t\t\t\t\t_ => {{
+\t\t\t\t\t\t\tmap.next_value::<serde::de::IgnoredAny>()?;
+\t\t\t\t\t\t}}
+\t\t\t\t\t}}
+\t\t\t\t}}
+\t\t\t\tOk(res)
+\t\t\t}}
+\t\t}}
+
+\t\tdeserializer.deserialize_struct(\"{}\", FIELDS, Visitor)
+\t}}
+}}",
+        name
+    )
+}
+
 pub fn generate_reexports(
     env: &Env,
     analysis: &analysis::record::Info,
@@ -160,3 +583,94 @@ pub fn generate_reexports(
         cfg, module_name, analysis.name
     ));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::tests::{env, generated};
+
+    #[test]
+    fn direct_boxed_record() {
+        let env = env(r#"
+[options]
+work_mode = "normal"
+
+[[object]]
+name = "Test.Point"
+status = "generate"
+direct = true
+"#);
+        let analysis = &env.analysis.records["Test.Point"];
+        let code = generated(|w| generate(w, &env, analysis));
+        assert!(code.contains("pub struct Point(ffi::TestPoint);"));
+        assert!(code.contains(
+            "\tpub fn type_(&self) -> f64 {\n\
+             \t\tself.0.type_\n\
+             \t}\n\
+             \n\
+             \tpub fn set_type(&mut self, type_: f64) {\n\
+             \t\tself.0.type_ = type_;\n\
+             \t}\n"
+        ));
+        assert!(code.contains(
+            "\tpub fn visible(&self) -> bool {\n\
+             \t\tunsafe { from_glib(self.0.visible) }\n\
+             \t}\n"
+        ));
+        assert!(code.contains(".field(\"type_\", &self.type_())"));
+        assert!(code.contains("impl glib::value::ValueType for Point {\n"));
+        assert!(code.contains("unsafe impl<'a> FromValue<'a> for Point {\n"));
+        assert!(code.contains("impl ToValue for Point {\n"));
+        assert!(code.contains("impl FromGlibPtrBorrow<*const ffi::TestPoint> for Point {\n"));
+        assert!(code.contains(
+            "\t\tlet res = Self(*ptr);\n\
+             \t\tglib::gobject_ffi::g_boxed_free(ffi::test_point_get_type(), ptr as *mut _);\n"
+        ));
+        assert!(code.contains(
+            "impl FromGlibPtrArrayContainerAsVec<*mut ffi::TestPoint, *mut *mut ffi::TestPoint> for Point {\n"
+        ));
+        assert!(code
+            .contains("impl<'a> ToGlibContainerFromSlice<'a, *mut ffi::TestPoint> for Point {\n"));
+    }
+
+    #[test]
+    fn direct_record_serde() {
+        let env = env(r#"
+[options]
+work_mode = "normal"
+generate_serde = true
+
+[[object]]
+name = "Test.Point"
+status = "generate"
+direct = true
+"#);
+        let analysis = &env.analysis.records["Test.Point"];
+        let code = generated(|w| direct_serde_impls(w, analysis));
+        assert!(code.contains(
+            "\t\tlet mut state = serializer.serialize_struct(\"Point\", 4)?;\n\
+             \t\tstate.serialize_field(\"x\", &self.x())?;\n\
+             \t\tstate.serialize_field(\"y\", &self.y())?;\n\
+             \t\tstate.serialize_field(\"type\", &self.type_())?;\n\
+             \t\tstate.serialize_field(\"visible\", &self.visible())?;\n\
+             \t\tstate.end()\n"
+        ));
+        assert!(
+            code.contains("\t\tconst FIELDS: &[&str] = &[\"x\", \"y\", \"type\", \"visible\"];\n")
+        );
+        assert!(code.contains(
+            "\t\t\t\tres.set_type(seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(2, &self))?);\n"
+        ));
+        assert!(code.contains(
+            "\t\t\t\tlet mut res = unsafe { Point::uninitialized() };\n\
+             \t\t\t\twhile let Some(key) = map.next_key::<String>()? {\n\
+             \t\t\t\t\tmatch key.as_str() {\n\
+             \t\t\t\t\t\t\"x\" => res.set_x(map.next_value()?),\n\
+             \t\t\t\t\t\t\"y\" => res.set_y(map.next_value()?),\n\
+             \t\t\t\t\t\t\"type\" => res.set_type(map.next_value()?),\n\
+             \t\t\t\t\t\t\"visible\" => res.set_visible(map.next_value()?),\n\
+             \t\t\t\t\t\t_ => {\n\
+             \t\t\t\t\t\t\tmap.next_value::<serde::de::IgnoredAny>()?;\n"
+        ));
+    }
+}
@@ -2,15 +2,22 @@ use super::{
     general::{cfg_deprecated, doc_alias, doc_hidden, version_condition},
     signal_body,
     trampoline::{self, func_string},
+    trampoline_from_glib::from_glib_xxx,
+    trampoline_to_glib::TrampolineToGlib,
 };
 use crate::{
-    analysis,
+    analysis::{
+        self, conversion_type::ConversionType, ffi_type::ffi_type, trampolines::Trampoline,
+    },
     chunk::Chunk,
     consts::TYPE_PARAMETERS_START,
     env::Env,
-    nameutil::use_glib_type,
+    library,
+    nameutil::{signal_to_snake, use_glib_type},
+    traits::IntoString,
     writer::{primitives::tabs, ToCode},
 };
+use log::error;
 use std::io::{Result, Write};
 
 pub fn generate(
@@ -89,6 +96,22 @@ pub fn generate(
         return Ok(());
     }
 
+    if analysis.is_detailed {
+        generate_detailed(
+            w,
+            env,
+            analysis,
+            &function_type,
+            in_trait,
+            only_declaration,
+            indent,
+        )?;
+    }
+
+    if let Some(ref stream) = analysis.stream {
+        generate_stream(w, env, analysis, stream, in_trait, only_declaration, indent)?;
+    }
+
     if let Some(ref emit_name) = analysis.action_emit_name {
         writeln!(w)?;
         if !in_trait || only_declaration {
@@ -116,6 +139,7 @@ pub fn generate(
                     analysis.signal_name,
                 )
             });
+            let has_ret = trampoline.ret.typ != Default::default();
             let mut args = String::with_capacity(100);
 
             for (pos, par) in trampoline.parameters.rust_parameters.iter().enumerate() {
@@ -123,36 +147,114 @@ pub fn generate(
                 if pos == 0 {
                     continue;
                 }
+                let transformation = match trampoline.parameters.get(pos) {
+                    Some(transformation) => transformation,
+                    None => {
+                        error!("No transformation for {}", par.name);
+                        continue;
+                    }
+                };
 
-                if pos > 1 {
-                    args.push_str(", ");
-                }
-                args.push('&');
+                args.push_str(", ");
                 args.push_str(&par.name);
+                args.push_str(&transformation.trampoline_to_glib(env));
+                args.push_str(vararg_promotion(env, par.typ));
+            }
+            if has_ret {
+                args.push_str(", ret.as_mut_ptr()");
             }
 
+            writeln!(w, "{}unsafe {{", tabs(indent + 1))?;
+            if has_ret {
+                writeln!(
+                    w,
+                    "{}let mut ret = mem::MaybeUninit::<{}>::uninit();",
+                    tabs(indent + 2),
+                    ffi_type(env, trampoline.ret.typ, &trampoline.ret.c_type).into_string(),
+                )?;
+            }
             writeln!(
                 w,
-                "{}let {} = unsafe {{ glib::Object::from_glib_borrow(self.as_ptr() as *mut {}).emit_by_name(\"{}\", &[{}]).unwrap() }};",
-                tabs(indent + 1),
-                if trampoline.ret.typ != Default::default() {
-                    "res"
-                } else {
-                    "_"
-                },
-                use_glib_type(env, "gobject_ffi::GObject"),
+                "{}{}(self.as_ptr() as *mut _, b\"{}\\0\".as_ptr() as *const _{});",
+                tabs(indent + 2),
+                use_glib_type(env, "gobject_ffi::g_signal_emit_by_name"),
                 analysis.signal_name,
                 args,
             )?;
-
-            if trampoline.ret.typ != Default::default() {
+            if has_ret {
                 writeln!(
                     w,
-                    "{}res.unwrap().get().expect(\"Return Value for `{}`\")",
-                    tabs(indent + 1),
-                    emit_name,
+                    "{}{}",
+                    tabs(indent + 2),
+                    emit_return_value(env, trampoline),
                 )?;
             }
+            writeln!(w, "{}}}", tabs(indent + 1))?;
+            writeln!(w, "{}}}", tabs(indent))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Generates `connect_*_detailed` and the `connect_*` functions for each configured detail,
+/// forwarding to the generic `connect_*` function.
+fn generate_detailed(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::signals::Info,
+    function_type: &Option<String>,
+    in_trait: bool,
+    only_declaration: bool,
+    indent: usize,
+) -> Result<()> {
+    let pub_prefix = if in_trait { "" } else { "pub " };
+    let suffix = if only_declaration { ";" } else { " {" };
+    let bounds = bounds(function_type);
+
+    let mut connects = vec![(
+        format!("{}_detailed", analysis.connect_name),
+        "&self, detail: &str, f: F",
+        "detail".to_owned(),
+    )];
+    for detail in &analysis.details {
+        connects.push((
+            format!(
+                "connect_{}_{}",
+                signal_to_snake(detail),
+                signal_to_snake(&analysis.signal_name)
+            ),
+            "&self, f: F",
+            format!("\"{}\"", detail),
+        ));
+    }
+
+    for (name, params, detail) in connects {
+        writeln!(w)?;
+        if !in_trait || only_declaration {
+            cfg_deprecated(w, env, analysis.deprecated_version, false, indent)?;
+        }
+        version_condition(w, env, analysis.version, false, indent)?;
+        doc_hidden(w, analysis.doc_hidden, "", indent)?;
+        doc_alias(w, &analysis.signal_name, "", indent)?;
+        writeln!(
+            w,
+            "{}{}fn {}<{}>({}) -> SignalHandlerId{}",
+            tabs(indent),
+            pub_prefix,
+            name,
+            bounds,
+            params,
+            suffix
+        )?;
+        if !only_declaration {
+            writeln!(
+                w,
+                "{}\tself.{}(Some({}), f)",
+                tabs(indent),
+                analysis.connect_name,
+                detail
+            )?;
             writeln!(w, "{}}}", tabs(indent))?;
         }
     }
@@ -160,6 +262,202 @@ pub fn generate(
     Ok(())
 }
 
+/// Generates the `*_stream` function, connecting to the signal for the lifetime of the stream.
+fn generate_stream(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::signals::Info,
+    stream: &analysis::signals::StreamInfo,
+    in_trait: bool,
+    only_declaration: bool,
+    indent: usize,
+) -> Result<()> {
+    let pub_prefix = if in_trait { "" } else { "pub " };
+    let suffix = if only_declaration { ";" } else { " {" };
+    let trampoline = analysis.trampoline.as_ref().unwrap();
+
+    let item = match stream.items.len() {
+        1 => stream.items[0].0.clone(),
+        _ => format!(
+            "({})",
+            stream
+                .items
+                .iter()
+                .map(|(typ, _)| typ.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    writeln!(w)?;
+    if !in_trait || only_declaration {
+        cfg_deprecated(w, env, analysis.deprecated_version, false, indent)?;
+    }
+    version_condition(w, env, analysis.version, false, indent)?;
+    doc_hidden(w, analysis.doc_hidden, "", indent)?;
+    doc_alias(w, &analysis.signal_name, "", indent)?;
+    writeln!(
+        w,
+        "{}{}fn {}(&self) -> Pin<Box_<dyn Stream<Item = {}> + 'static>>{}",
+        tabs(indent),
+        pub_prefix,
+        stream.name,
+        item,
+        suffix
+    )?;
+    if only_declaration {
+        return Ok(());
+    }
+
+    let mut args = vec!["_".to_owned()];
+    args.extend(
+        trampoline
+            .parameters
+            .rust_parameters
+            .iter()
+            .skip(1)
+            .map(|par| par.name.clone()),
+    );
+    let value = match stream.items.len() {
+        1 => stream.items[0].1.clone(),
+        _ => format!(
+            "({})",
+            stream
+                .items
+                .iter()
+                .map(|(_, expr)| expr.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    writeln!(
+        w,
+        "{}\tlet (sender, receiver) = futures_channel::mpsc::unbounded();",
+        tabs(indent)
+    )?;
+    writeln!(
+        w,
+        "{}\tlet handler = self.{}({}move |{}| {{",
+        tabs(indent),
+        analysis.connect_name,
+        if analysis.is_detailed { "None, " } else { "" },
+        args.join(", ")
+    )?;
+    writeln!(
+        w,
+        "{}\t\tlet _ = sender.unbounded_send({});",
+        tabs(indent),
+        value
+    )?;
+    writeln!(w, "{}\t}});", tabs(indent))?;
+    writeln!(
+        w,
+        "{}\tBox_::pin(SignalStream {{ object: {}(self), handler: Some(handler), receiver }})",
+        tabs(indent),
+        use_glib_type(env, "ObjectExt::downgrade"),
+    )?;
+    writeln!(w, "{}}}", tabs(indent))?;
+
+    Ok(())
+}
+
+/// Generates the stream type returned by the `*_stream` functions of an object,
+/// which disconnects its signal handler when dropped.
+pub fn generate_stream_type(w: &mut dyn Write, env: &Env) -> Result<()> {
+    let object_type = use_glib_type(env, "ObjectType");
+
+    writeln!(w)?;
+    writeln!(w, "struct SignalStream<O: {}, T> {{", object_type)?;
+    writeln!(w, "\tobject: {}<O>,", use_glib_type(env, "WeakRef"))?;
+    writeln!(
+        w,
+        "\thandler: Option<{}>,",
+        use_glib_type(env, "SignalHandlerId")
+    )?;
+    writeln!(
+        w,
+        "\treceiver: futures_channel::mpsc::UnboundedReceiver<T>,"
+    )?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "impl<O: {}, T> Unpin for SignalStream<O, T> {{}}",
+        object_type
+    )?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "impl<O: {}, T> Stream for SignalStream<O, T> {{",
+        object_type
+    )?;
+    writeln!(w, "\ttype Item = T;")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "\tfn poll_next(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Option<T>> {{"
+    )?;
+    writeln!(w, "\t\tPin::new(&mut self.receiver).poll_next(cx)")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "impl<O: {}, T> Drop for SignalStream<O, T> {{",
+        object_type
+    )?;
+    writeln!(w, "\tfn drop(&mut self) {{")?;
+    writeln!(
+        w,
+        "\t\tif let (Some(object), Some(handler)) = (self.object.upgrade(), self.handler.take()) {{"
+    )?;
+    writeln!(
+        w,
+        "\t\t\t{}(&object, handler);",
+        use_glib_type(env, "ObjectExt::disconnect")
+    )?;
+    writeln!(w, "\t\t}}")?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")?;
+
+    Ok(())
+}
+
+/// C variadic arguments undergo default argument promotion, which Rust doesn't do implicitly.
+fn vararg_promotion(env: &Env, typ: library::TypeId) -> &'static str {
+    use crate::library::Fundamental::*;
+    match env.library.type_(typ) {
+        library::Type::Fundamental(Int8) | library::Type::Fundamental(Char) => " as i32",
+        library::Type::Fundamental(Int16) | library::Type::Fundamental(Short) => " as i32",
+        library::Type::Fundamental(UInt8) | library::Type::Fundamental(UChar) => " as u32",
+        library::Type::Fundamental(UInt16) | library::Type::Fundamental(UShort) => " as u32",
+        library::Type::Fundamental(Float) => " as f64",
+        _ => "",
+    }
+}
+
+/// Converts the value collected by `g_signal_emit_by_name`, which is owned by the caller.
+fn emit_return_value(env: &Env, trampoline: &Trampoline) -> String {
+    let ret = "ret.assume_init()";
+    let value = match ConversionType::of(env, trampoline.ret.typ) {
+        ConversionType::Direct => ret.to_owned(),
+        ConversionType::Scalar | ConversionType::Option | ConversionType::Result { .. } => {
+            format!("from_glib({})", ret)
+        }
+        ConversionType::Borrow | ConversionType::Pointer => {
+            let (left, right) = from_glib_xxx(library::Transfer::Full, false);
+            format!("{}{}{}", left, ret, right)
+        }
+        ConversionType::Unknown => format!("/*Unknown conversion*/{}", ret),
+    };
+    if trampoline.inhibit {
+        format!("{}({})", use_glib_type(env, "signal::Inhibit"), value)
+    } else {
+        value
+    }
+}
+
 fn function_type_string(
     env: &Env,
     analysis: &analysis::signals::Info,
@@ -221,3 +519,175 @@ fn body(analysis: &analysis::signals::Info, in_trait: bool) -> Chunk {
 
     builder.generate()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::tests::{env, generated};
+
+    /// The generated trait functions of a signal of `Test.Widget`.
+    fn signal(env: &Env, signal_name: &str) -> String {
+        let analysis = env.analysis.objects["Test.Widget"]
+            .signals
+            .iter()
+            .find(|s| s.signal_name == signal_name)
+            .unwrap();
+        generated(|w| generate(w, env, analysis, true, false, 1))
+    }
+
+    /// The generated `emit_*` function of a signal of `Test.Widget`.
+    fn emit(env: &Env, signal_name: &str) -> String {
+        let code = signal(env, signal_name);
+        code[code.find("\tfn emit_").unwrap()..].to_owned()
+    }
+
+    #[test]
+    fn typed_emit() {
+        let env = env(r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+
+[[object]]
+name = "Test.Widget"
+status = "generate"
+    [[object.signal]]
+    name = "select-item"
+    inhibit = true
+"#);
+        assert_eq!(
+            emit(&env, "clicked"),
+            "\tfn emit_clicked(&self) {\n\
+             \t\tunsafe {\n\
+             \t\t\tglib::gobject_ffi::g_signal_emit_by_name(self.as_ptr() as *mut _, b\"clicked\\0\".as_ptr() as *const _);\n\
+             \t\t}\n\
+             \t}\n"
+        );
+        assert_eq!(
+            emit(&env, "select-item"),
+            "\tfn emit_select_item(&self, index: i8, name: Option<&str>, other: &Widget, scale: f32) -> glib::signal::Inhibit {\n\
+             \t\tunsafe {\n\
+             \t\t\tlet mut ret = mem::MaybeUninit::<glib::ffi::gboolean>::uninit();\n\
+             \t\t\tglib::gobject_ffi::g_signal_emit_by_name(self.as_ptr() as *mut _, b\"select-item\\0\".as_ptr() as *const _, \
+             index as i32, name.to_glib_none().0, other.to_glib_none().0, scale as f64, ret.as_mut_ptr());\n\
+             \t\t\tglib::signal::Inhibit(from_glib(ret.assume_init()))\n\
+             \t\t}\n\
+             \t}\n"
+        );
+        assert_eq!(
+            emit(&env, "fetch-label"),
+            "\tfn emit_fetch_label(&self) -> Option<glib::GString> {\n\
+             \t\tunsafe {\n\
+             \t\t\tlet mut ret = mem::MaybeUninit::<*mut libc::c_char>::uninit();\n\
+             \t\t\tglib::gobject_ffi::g_signal_emit_by_name(self.as_ptr() as *mut _, b\"fetch-label\\0\".as_ptr() as *const _, ret.as_mut_ptr());\n\
+             \t\t\tfrom_glib_full(ret.assume_init())\n\
+             \t\t}\n\
+             \t}\n"
+        );
+    }
+
+    #[test]
+    fn detailed_connects() {
+        let env = env(r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+
+[[object]]
+name = "Test.Widget"
+status = "generate"
+    [[object.signal]]
+    name = "changed"
+    details = ["font-name", "scale"]
+"#);
+        let code = signal(&env, "changed");
+        let detailed = code.find("\n\t#[doc(alias = \"changed\")]\n\tfn connect_changed_detailed");
+        assert_eq!(
+            &code[detailed.unwrap()..],
+            "\n\
+             \t#[doc(alias = \"changed\")]\n\
+             \tfn connect_changed_detailed<F: Fn(&Self, i32) + 'static>(&self, detail: &str, f: F) -> SignalHandlerId {\n\
+             \t\tself.connect_changed(Some(detail), f)\n\
+             \t}\n\
+             \n\
+             \t#[doc(alias = \"changed\")]\n\
+             \tfn connect_font_name_changed<F: Fn(&Self, i32) + 'static>(&self, f: F) -> SignalHandlerId {\n\
+             \t\tself.connect_changed(Some(\"font-name\"), f)\n\
+             \t}\n\
+             \n\
+             \t#[doc(alias = \"changed\")]\n\
+             \tfn connect_scale_changed<F: Fn(&Self, i32) + 'static>(&self, f: F) -> SignalHandlerId {\n\
+             \t\tself.connect_changed(Some(\"scale\"), f)\n\
+             \t}\n"
+        );
+    }
+
+    #[test]
+    fn signal_stream() {
+        let env = env(r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+
+[[object]]
+name = "Test.Widget"
+status = "generate"
+generate_signal_streams = true
+"#);
+        let code = signal(&env, "moved");
+        assert_eq!(
+            &code[code.find("\n\t#[doc(alias = \"moved\")]\n\tfn moved_stream").unwrap()..],
+            "\n\
+             \t#[doc(alias = \"moved\")]\n\
+             \tfn moved_stream(&self) -> Pin<Box_<dyn Stream<Item = (i32, Option<glib::GString>, Widget)> + 'static>> {\n\
+             \t\tlet (sender, receiver) = futures_channel::mpsc::unbounded();\n\
+             \t\tlet handler = self.connect_moved(move |_, index, name, other| {\n\
+             \t\t\tlet _ = sender.unbounded_send((index, name.map(Into::into), other.clone()));\n\
+             \t\t});\n\
+             \t\tBox_::pin(SignalStream { object: glib::ObjectExt::downgrade(self), handler: Some(handler), receiver })\n\
+             \t}\n"
+        );
+        // Streams of detailed signals receive every detail
+        assert!(signal(&env, "changed").contains(
+            "\tfn changed_stream(&self) -> Pin<Box_<dyn Stream<Item = i32> + 'static>> {\n\
+             \t\tlet (sender, receiver) = futures_channel::mpsc::unbounded();\n\
+             \t\tlet handler = self.connect_changed(None, move |_, value| {\n\
+             \t\t\tlet _ = sender.unbounded_send(value);\n"
+        ));
+    }
+
+    #[test]
+    fn signal_stream_type() {
+        let env = env(r#"
+[options]
+work_mode = "normal"
+"#);
+        assert_eq!(
+            generated(|w| generate_stream_type(w, &env)),
+            "\n\
+             struct SignalStream<O: glib::ObjectType, T> {\n\
+             \tobject: glib::WeakRef<O>,\n\
+             \thandler: Option<glib::SignalHandlerId>,\n\
+             \treceiver: futures_channel::mpsc::UnboundedReceiver<T>,\n\
+             }\n\
+             \n\
+             impl<O: glib::ObjectType, T> Unpin for SignalStream<O, T> {}\n\
+             \n\
+             impl<O: glib::ObjectType, T> Stream for SignalStream<O, T> {\n\
+             \ttype Item = T;\n\
+             \n\
+             \tfn poll_next(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Option<T>> {\n\
+             \t\tPin::new(&mut self.receiver).poll_next(cx)\n\
+             \t}\n\
+             }\n\
+             \n\
+             impl<O: glib::ObjectType, T> Drop for SignalStream<O, T> {\n\
+             \tfn drop(&mut self) {\n\
+             \t\tif let (Some(object), Some(handler)) = (self.object.upgrade(), self.handler.take()) {\n\
+             \t\t\tglib::ObjectExt::disconnect(&object, handler);\n\
+             \t\t}\n\
+             \t}\n\
+             }\n"
+        );
+    }
+}
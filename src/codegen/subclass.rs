@@ -0,0 +1,463 @@
+use super::{
+    general::{self, cfg_deprecated, version_condition},
+    trampoline::{func_parameter, func_returns},
+    trampoline_from_glib::TrampolineFromGlib,
+    trampoline_to_glib::TrampolineToGlib,
+    translate_from_glib::TranslateFromGlib,
+};
+use crate::{
+    analysis::{
+        self, conversion_type::ConversionType, ffi_type::ffi_type,
+        trampoline_parameters::Transformation, trampolines::Trampoline, try_from_glib::TryFromGlib,
+        virtual_methods,
+    },
+    chunk::conversion_from_glib::Mode,
+    env::Env,
+    nameutil::use_glib_if_needed,
+    traits::IntoString,
+};
+use std::io::{Result, Write};
+
+pub fn generate(w: &mut dyn Write, env: &Env, analysis: &analysis::subclass::Info) -> Result<()> {
+    general::start_comments(w, &env.config)?;
+    general::uses(w, env, &analysis.imports, None)?;
+
+    generate_impl_trait(w, env, analysis)?;
+    generate_impl_ext_trait(w, env, analysis)?;
+    generate_impl_ext(w, env, analysis)?;
+    match analysis.parent_type_name {
+        Some(ref parent_type_name) => generate_is_subclassable(w, env, analysis, parent_type_name)?,
+        None => generate_is_implementable(w, env, analysis)?,
+    }
+
+    for vfn in &analysis.virtual_methods {
+        if let Ok(ref trampoline) = vfn.trampoline {
+            generate_trampoline(w, env, analysis, vfn, trampoline)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn generate_impl_trait(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::subclass::Info,
+) -> Result<()> {
+    writeln!(w)?;
+    writeln!(
+        w,
+        "pub trait {}: {} + {} {{",
+        analysis.impl_trait_name, analysis.ext_trait_name, analysis.parent_impl_trait_name
+    )?;
+    for (pos, vfn) in analysis.virtual_methods.iter().enumerate() {
+        if pos > 0 {
+            writeln!(w)?;
+        }
+        match vfn.trampoline {
+            Ok(ref trampoline) => {
+                cfg_deprecated(w, env, vfn.deprecated_version, false, 1)?;
+                version_condition(w, env, vfn.version, false, 1)?;
+                writeln!(
+                    w,
+                    "\tfn {}({}){} {{",
+                    vfn.name,
+                    declaration_parameters(env, analysis, trampoline),
+                    func_returns(env, trampoline)
+                )?;
+                writeln!(
+                    w,
+                    "\t\tself.{}({})",
+                    vfn.parent_name,
+                    call_parameters(analysis, trampoline)
+                )?;
+                writeln!(w, "\t}}")?;
+            }
+            Err(ref errors) => {
+                cfg_deprecated(w, env, vfn.deprecated_version, true, 1)?;
+                version_condition(w, env, vfn.version, true, 1)?;
+                let params = std::iter::once(format!("{}: &Self::Type", analysis.instance_name))
+                    .chain(vfn.parameters.iter().cloned())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let ret = vfn
+                    .ret
+                    .as_ref()
+                    .map_or_else(String::new, |ret| format!(" -> {}", ret));
+                writeln!(w, "\t//fn {}(&self, {}){} {{", vfn.name, params, ret)?;
+                for error in errors {
+                    writeln!(w, "\t//\t{}", error)?;
+                }
+                writeln!(w, "\t//}}")?;
+            }
+        }
+    }
+    writeln!(w, "}}")
+}
+
+fn generate_impl_ext_trait(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::subclass::Info,
+) -> Result<()> {
+    writeln!(w)?;
+    writeln!(
+        w,
+        "pub trait {}: ObjectSubclass {{",
+        analysis.ext_trait_name
+    )?;
+    for (vfn, trampoline) in supported(&analysis.virtual_methods) {
+        cfg_deprecated(w, env, vfn.deprecated_version, false, 1)?;
+        version_condition(w, env, vfn.version, false, 1)?;
+        writeln!(
+            w,
+            "\tfn {}({}){};",
+            vfn.parent_name,
+            declaration_parameters(env, analysis, trampoline),
+            func_returns(env, trampoline)
+        )?;
+    }
+    writeln!(w, "}}")
+}
+
+fn generate_impl_ext(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::subclass::Info,
+) -> Result<()> {
+    writeln!(w)?;
+    writeln!(
+        w,
+        "impl<T: {}> {} for T {{",
+        analysis.impl_trait_name, analysis.ext_trait_name
+    )?;
+    for (vfn, trampoline) in supported(&analysis.virtual_methods) {
+        version_condition(w, env, vfn.version, false, 1)?;
+        writeln!(
+            w,
+            "\tfn {}({}){} {{",
+            vfn.parent_name,
+            declaration_parameters(env, analysis, trampoline),
+            func_returns(env, trampoline)
+        )?;
+        writeln!(w, "\t\tunsafe {{")?;
+        writeln!(w, "\t\t\tlet data = T::type_data();")?;
+        match analysis.parent_type_name {
+            Some(_) => writeln!(
+                w,
+                "\t\t\tlet parent_class = data.as_ref().parent_class() as *mut {};",
+                analysis.class_struct
+            )?,
+            None => writeln!(
+                w,
+                "\t\t\tlet parent_class = data.as_ref().parent_interface::<{}>() as *const {};",
+                analysis.name, analysis.class_struct
+            )?,
+        }
+
+        let mut args = vec![format!(
+            "{}.unsafe_cast_ref::<{}>().to_glib_none().0",
+            analysis.instance_name, analysis.name
+        )];
+        args.extend(
+            trampoline
+                .parameters
+                .transformations
+                .iter()
+                .filter(|tr| tr.ind_rust > 0)
+                .map(parent_call_parameter),
+        );
+        let call = format!("f({})", args.join(", "));
+
+        if trampoline.ret.typ == Default::default() {
+            writeln!(w, "\t\t\tif let Some(f) = (*parent_class).{} {{", vfn.name)?;
+            writeln!(w, "\t\t\t\t{}", call)?;
+            writeln!(w, "\t\t\t}}")?;
+        } else {
+            writeln!(
+                w,
+                "\t\t\tlet f = (*parent_class).{}.expect(\"No parent class implementation for \\\"{}\\\"\");",
+                vfn.name, vfn.name
+            )?;
+            let mode = Mode {
+                typ: trampoline.ret.typ,
+                transfer: trampoline.ret.transfer,
+                is_uninitialized: false,
+                try_from_glib: TryFromGlib::from_type_defaults(env, trampoline.ret.typ),
+            };
+            let (pre, post) = mode.translate_from_glib_as_function(env, None);
+            writeln!(w, "\t\t\t{}{}{}", pre, call, post)?;
+        }
+        writeln!(w, "\t\t}}")?;
+        writeln!(w, "\t}}")?;
+    }
+    writeln!(w, "}}")
+}
+
+fn generate_is_subclassable(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::subclass::Info,
+    parent_type_name: &str,
+) -> Result<()> {
+    writeln!(w)?;
+    writeln!(
+        w,
+        "unsafe impl<T: {}> IsSubclassable<T> for {} {{",
+        analysis.impl_trait_name, analysis.name
+    )?;
+    writeln!(
+        w,
+        "\tfn class_init(class: &mut {}) {{",
+        use_glib_if_needed(env, "Class<Self>")
+    )?;
+    writeln!(
+        w,
+        "\t\t<{} as IsSubclassable<T>>::class_init(class);",
+        parent_type_name
+    )?;
+    writeln!(w)?;
+    writeln!(w, "\t\tlet klass = class.as_mut();")?;
+    for (vfn, trampoline) in supported(&analysis.virtual_methods) {
+        version_condition(w, env, vfn.version, false, 2)?;
+        writeln!(
+            w,
+            "\t\tklass.{} = Some({}::<T>);",
+            vfn.name, trampoline.name
+        )?;
+    }
+    writeln!(w, "\t}}")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "\tfn instance_init(instance: &mut {}<T>) {{",
+        use_glib_if_needed(env, "subclass::InitializingObject")
+    )?;
+    writeln!(
+        w,
+        "\t\t<{} as IsSubclassable<T>>::instance_init(instance);",
+        parent_type_name
+    )?;
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")
+}
+
+fn generate_is_implementable(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::subclass::Info,
+) -> Result<()> {
+    writeln!(w)?;
+    writeln!(
+        w,
+        "unsafe impl<T: {}> IsImplementable<T> for {} {{",
+        analysis.impl_trait_name, analysis.name
+    )?;
+    writeln!(
+        w,
+        "\tfn interface_init(iface: &mut {}) {{",
+        use_glib_if_needed(env, "Interface<Self>")
+    )?;
+    writeln!(w, "\t\tlet iface = iface.as_mut();")?;
+    for (vfn, trampoline) in supported(&analysis.virtual_methods) {
+        version_condition(w, env, vfn.version, false, 2)?;
+        writeln!(
+            w,
+            "\t\tiface.{} = Some({}::<T>);",
+            vfn.name, trampoline.name
+        )?;
+    }
+    writeln!(w, "\t}}")?;
+    writeln!(w, "}}")
+}
+
+fn generate_trampoline(
+    w: &mut dyn Write,
+    env: &Env,
+    analysis: &analysis::subclass::Info,
+    vfn: &virtual_methods::Info,
+    trampoline: &Trampoline,
+) -> Result<()> {
+    let params = trampoline
+        .parameters
+        .c_parameters
+        .iter()
+        .map(|par| {
+            format!(
+                "{}: {}",
+                par.name,
+                ffi_type(env, par.typ, &par.c_type).into_string()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let ret = if trampoline.ret.typ == Default::default() {
+        String::new()
+    } else {
+        format!(
+            " -> {}",
+            ffi_type(env, trampoline.ret.typ, &trampoline.ret.c_type).into_string()
+        )
+    };
+
+    writeln!(w)?;
+    version_condition(w, env, vfn.version, false, 0)?;
+    writeln!(
+        w,
+        "unsafe extern \"C\" fn {}<T: {}>({}){} {{",
+        trampoline.name, analysis.impl_trait_name, params, ret
+    )?;
+    writeln!(w, "\tlet instance = &*(this as *mut T::Instance);")?;
+    writeln!(w, "\tlet imp = instance.impl_();")?;
+    writeln!(
+        w,
+        "\tlet wrap: Borrowed<{}> = from_glib_borrow(this);",
+        analysis.name
+    )?;
+
+    let mut args = vec!["wrap.unsafe_cast_ref()".to_owned()];
+    for (ind, par) in trampoline
+        .parameters
+        .rust_parameters
+        .iter()
+        .enumerate()
+        .skip(1)
+    {
+        if let Some(transformation) = trampoline.parameters.get(ind) {
+            args.push(transformation.trampoline_from_glib(env, false, *par.nullable));
+        }
+    }
+    let ret = if trampoline.ret.typ == Default::default() {
+        String::new()
+    } else {
+        trampoline.ret.trampoline_to_glib(env)
+    };
+    writeln!(w, "\timp.{}({}){}", vfn.name, args.join(", "), ret)?;
+    writeln!(w, "}}")
+}
+
+fn supported(
+    virtual_methods: &[virtual_methods::Info],
+) -> impl Iterator<Item = (&virtual_methods::Info, &Trampoline)> {
+    virtual_methods
+        .iter()
+        .filter_map(|vfn| vfn.trampoline.as_ref().ok().map(|t| (vfn, t)))
+}
+
+fn declaration_parameters(
+    env: &Env,
+    analysis: &analysis::subclass::Info,
+    trampoline: &Trampoline,
+) -> String {
+    let mut params = vec![
+        "&self".to_owned(),
+        format!("{}: &Self::Type", analysis.instance_name),
+    ];
+    for par in trampoline.parameters.rust_parameters.iter().skip(1) {
+        params.push(format!(
+            "{}: {}",
+            par.name,
+            func_parameter(env, par, &trampoline.bounds, None)
+        ));
+    }
+    params.join(", ")
+}
+
+fn call_parameters(analysis: &analysis::subclass::Info, trampoline: &Trampoline) -> String {
+    std::iter::once(analysis.instance_name.as_str())
+        .chain(
+            trampoline
+                .parameters
+                .rust_parameters
+                .iter()
+                .skip(1)
+                .map(|par| par.name.as_str()),
+        )
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parent_call_parameter(transformation: &Transformation) -> String {
+    match transformation.conversion_type {
+        ConversionType::Direct => transformation.name.clone(),
+        ConversionType::Scalar | ConversionType::Option | ConversionType::Result { .. } => {
+            format!("{}.into_glib()", transformation.name)
+        }
+        ConversionType::Borrow | ConversionType::Pointer => {
+            format!("{}.to_glib_none().0", transformation.name)
+        }
+        ConversionType::Unknown => format!("/*Unknown conversion*/{}", transformation.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::tests::{env, generated};
+
+    const CONFIG: &str = r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+
+[[object]]
+name = "Test.Widget"
+status = "generate"
+generate_subclass = true
+    [[object.virtual_method]]
+    name = "get_name"
+    ignore = true
+
+[[object]]
+name = "Test.Sizable"
+status = "generate"
+generate_subclass = true
+"#;
+
+    fn subclass(env: &Env, name: &str) -> String {
+        let info = env.analysis.objects[name].subclass.as_ref().unwrap();
+        generated(|w| generate(w, env, info))
+    }
+
+    #[test]
+    fn class_virtual_methods() {
+        let env = env(CONFIG);
+        let code = subclass(&env, "Test.Widget");
+        assert!(!code.contains("get_name"));
+        assert!(code.contains(
+            "\t#[cfg(any(feature = \"v1_2\", feature = \"dox\"))]\n\
+             \t#[cfg_attr(feature = \"dox\", doc(cfg(feature = \"v1_2\")))]\n\
+             \tfn set_label(&self, widget: &Self::Type, label: Option<&str>, size: i32, other: &Widget) -> bool {\n"
+        ));
+        assert!(code.contains(
+            "\t//fn get_data(&self, widget: &Self::Type) -> glib::GString {\n\
+             \t//\tTransfer none return value *.Utf8\n"
+        ));
+        assert!(code.contains(
+            "\t#[cfg_attr(feature = \"v1_4\", deprecated = \"Since 1.4\")]\n\
+             \tfn parent_reset(&self, widget: &Self::Type);\n"
+        ));
+        assert!(code.contains(
+            "unsafe impl<T: WidgetImpl> IsSubclassable<T> for Widget {\n\
+             \tfn class_init(class: &mut glib::Class<Self>) {\n\
+             \t\t<glib::Object as IsSubclassable<T>>::class_init(class);\n"
+        ));
+    }
+
+    #[test]
+    fn interface_virtual_methods() {
+        let env = env(CONFIG);
+        let code = subclass(&env, "Test.Sizable");
+        assert!(code.contains("pub trait SizableImpl: SizableImplExt + ObjectImpl {\n"));
+        assert!(code.contains(
+            "\t\t\tlet parent_class = data.as_ref().parent_interface::<Sizable>() as *const ffi::TestSizableInterface;\n"
+        ));
+        assert!(code.contains(
+            "unsafe impl<T: SizableImpl> IsImplementable<T> for Sizable {\n\
+             \tfn interface_init(iface: &mut glib::Interface<Self>) {\n\
+             \t\tlet iface = iface.as_mut();\n\
+             \t\tiface.get_size = Some(sizable_get_size::<T>);\n"
+        ));
+        assert!(!code.contains("IsSubclassable"));
+    }
+}
@@ -0,0 +1,67 @@
+use super::general;
+use crate::{env::Env, file_saver::*, nameutil::*};
+use log::info;
+use std::path::Path;
+
+/// Generates the `subclass` module tree, returns `false` if there is nothing to subclass.
+pub fn generate(env: &Env, subclass_path: &Path) -> bool {
+    info!("Generate subclasses");
+    let mut mod_rs: Vec<String> = Vec::new();
+    let mut traits: Vec<String> = Vec::new();
+
+    for class_analysis in env.analysis.objects.values() {
+        let obj = &env.config.objects[&class_analysis.full_name];
+        if !obj.status.need_generate() {
+            continue;
+        }
+        let subclass_analysis = match class_analysis.subclass {
+            Some(ref subclass_analysis) => subclass_analysis,
+            None => continue,
+        };
+
+        let mod_name = obj
+            .module_name
+            .clone()
+            .unwrap_or_else(|| module_name(split_namespace_name(&class_analysis.full_name).1));
+
+        let mut path = subclass_path.join(&mod_name);
+        path.set_extension("rs");
+        info!("Generating file {:?}", path);
+
+        save_to_file(path, &env.config, |ref mut w| {
+            super::subclass::generate(w, env, subclass_analysis)
+        });
+
+        let mut cfgs: Vec<String> = Vec::new();
+        if let Some(cfg) = general::cfg_condition_string(&class_analysis.cfg_condition, false, 0) {
+            cfgs.push(cfg);
+        }
+        if let Some(cfg) = general::version_condition_string(env, class_analysis.version, false, 0)
+        {
+            cfgs.push(cfg);
+        }
+
+        mod_rs.push("".to_owned());
+        mod_rs.extend_from_slice(&cfgs);
+        mod_rs.push(format!("mod {};", mod_name));
+        mod_rs.extend_from_slice(&cfgs);
+        mod_rs.push(format!(
+            "pub use self::{}::{{{}, {}}};",
+            mod_name, subclass_analysis.impl_trait_name, subclass_analysis.ext_trait_name
+        ));
+        for cfg in &cfgs {
+            traits.push(format!("\t{}", cfg));
+        }
+        traits.push(format!(
+            "\tpub use super::{}::{{{}, {}}};",
+            mod_name, subclass_analysis.impl_trait_name, subclass_analysis.ext_trait_name
+        ));
+    }
+
+    if mod_rs.is_empty() {
+        return false;
+    }
+
+    super::generate_mod_rs(env, subclass_path, &mod_rs, &traits);
+    true
+}
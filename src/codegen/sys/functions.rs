@@ -1,6 +1,7 @@
 use super::ffi_type::*;
 use crate::{
-    codegen::general::{cfg_condition, version_condition},
+    analysis::function_macros,
+    codegen::general::{cfg_condition, source_position, version_condition},
     config::{functions::Function, gobjects::GObject},
     env::Env,
     library, nameutil,
@@ -173,6 +174,37 @@ pub fn generate_other_funcs(
     generate_object_funcs(w, env, obj, None, "Other functions", INTERN, functions)
 }
 
+pub fn generate_function_macros(
+    w: &mut dyn Write,
+    env: &Env,
+    function_macros: &[function_macros::Info],
+) -> Result<()> {
+    if !function_macros.is_empty() {
+        writeln!(w, "\n// Function macros")?;
+    }
+    for func_macro in function_macros {
+        let params = func_macro
+            .parameters
+            .iter()
+            .map(|(name, typ)| format!("{}: {}", name, typ))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let ret = func_macro
+            .ret
+            .as_ref()
+            .map(|ret| format!(" -> {}", ret))
+            .unwrap_or_default();
+        writeln!(w)?;
+        version_condition(w, env, func_macro.version, false, 0)?;
+        writeln!(w, "#[inline]")?;
+        writeln!(w, "pub unsafe fn {}({}){} {{", func_macro.name, params, ret)?;
+        writeln!(w, "    {}", func_macro.body)?;
+        writeln!(w, "}}")?;
+    }
+
+    Ok(())
+}
+
 fn generate_cfg_configure(
     w: &mut dyn Write,
     configured_functions: &[&Function],
@@ -248,6 +280,7 @@ fn generate_object_funcs(
             .or(func.version)
             .or(version);
 
+        source_position(w, env, func.source_position.as_ref(), 1)?;
         version_condition(w, env, version, commented, 1)?;
         let name = func.c_identifier.as_ref().unwrap();
         // since we work with gir-files from Linux, some function names need to be adjusted
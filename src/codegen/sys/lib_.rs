@@ -1,5 +1,6 @@
 use super::{ffi_type::ffi_type, fields, functions, statics};
 use crate::{
+    analysis::function_macros,
     codegen::general::{self, cfg_condition, version_condition},
     config::{constants, ExternalLibrary},
     env::Env,
@@ -14,13 +15,15 @@ use std::{
     io::{Result, Write},
 };
 
-pub fn generate(env: &Env) {
+pub fn generate(env: &Env, function_macros: &[function_macros::Info]) {
     info!("Generating sys for {}", env.config.library_name);
 
     let path = env.config.auto_path.join(file_name_sys("lib"));
 
     info!("Generating file {:?}", path);
-    save_to_file(&path, env.config.make_backup, |w| generate_lib(w, env));
+    save_to_file(&path, &env.config, |w| {
+        generate_lib(w, env, function_macros)
+    });
 }
 
 fn write_link_attr(w: &mut dyn Write, shared_libs: &[String]) -> Result<()> {
@@ -35,7 +38,11 @@ fn write_link_attr(w: &mut dyn Write, shared_libs: &[String]) -> Result<()> {
     Ok(())
 }
 
-fn generate_lib(w: &mut dyn Write, env: &Env) -> Result<()> {
+fn generate_lib(
+    w: &mut dyn Write,
+    env: &Env,
+    function_macros: &[function_macros::Info],
+) -> Result<()> {
     general::start_comments(w, &env.config)?;
     statics::begin(w)?;
 
@@ -84,6 +91,8 @@ fn generate_lib(w: &mut dyn Write, env: &Env) -> Result<()> {
 
     writeln!(w, "\n}}")?;
 
+    functions::generate_function_macros(w, env, function_macros)?;
+
     Ok(())
 }
 
@@ -1,21 +1,24 @@
-use crate::{codegen::generate_single_version_file, env::Env, version::Version};
+use crate::{
+    analysis::function_macros, codegen::generate_single_version_file, env::Env, version::Version,
+};
 use std::collections::BTreeMap;
 
 mod build;
 mod cargo_toml;
 pub mod ffi_type;
 mod fields;
-mod functions;
+pub mod functions;
 mod lib_;
 mod statics;
 mod tests;
 
 pub fn generate(env: &Env) {
+    let function_macros = function_macros::analyze(env);
     generate_single_version_file(env);
-    lib_::generate(env);
+    lib_::generate(env, &function_macros);
     build::generate(env);
     let crate_name = cargo_toml::generate(env);
-    tests::generate(env, &crate_name);
+    tests::generate(env, &crate_name, &function_macros);
 }
 
 pub fn collect_versions(env: &Env) -> BTreeMap<Version, Version> {
@@ -1,5 +1,5 @@
 use crate::{
-    analysis::types::IsIncomplete,
+    analysis::{function_macros, types::IsIncomplete},
     codegen::general,
     env::Env,
     file_saver::save_to_file,
@@ -27,11 +27,20 @@ struct CConstant {
     value: String,
 }
 
-pub fn generate(env: &Env, crate_name: &str) {
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct CFunctionMacro {
+    /// Invocation of the macro in C.
+    call: String,
+    /// Invocation of the corresponding function in Rust.
+    rust_call: String,
+}
+
+pub fn generate(env: &Env, crate_name: &str, function_macros: &[function_macros::Info]) {
     let ctypes = prepare_ctypes(env);
     let cconsts = prepare_cconsts(env);
+    let cmacros = prepare_cfunction_macros(env, function_macros);
 
-    if ctypes.is_empty() && cconsts.is_empty() {
+    if ctypes.is_empty() && cconsts.is_empty() && cmacros.is_empty() {
         return;
     }
 
@@ -39,24 +48,31 @@ pub fn generate(env: &Env, crate_name: &str) {
 
     let manual_h = tests.join("manual.h");
     if !manual_h.exists() {
-        save_to_file(&manual_h, env.config.make_backup, |w| {
+        save_to_file(&manual_h, &env.config, |w| {
             generate_manual_h(env, &manual_h, w)
         });
     }
 
     let layout_c = tests.join("layout.c");
-    save_to_file(&layout_c, env.config.make_backup, |w| {
+    save_to_file(&layout_c, &env.config, |w| {
         generate_layout_c(env, &layout_c, w, &ctypes)
     });
 
     let constant_c = tests.join("constant.c");
-    save_to_file(&constant_c, env.config.make_backup, |w| {
+    save_to_file(&constant_c, &env.config, |w| {
         generate_constant_c(env, &constant_c, w, &cconsts)
     });
 
+    if !cmacros.is_empty() {
+        let function_macro_c = tests.join("function_macro.c");
+        save_to_file(&function_macro_c, &env.config, |w| {
+            generate_function_macro_c(env, &function_macro_c, w, &cmacros)
+        });
+    }
+
     let abi_rs = tests.join("abi.rs");
-    save_to_file(&abi_rs, env.config.make_backup, |w| {
-        generate_abi_rs(env, &abi_rs, w, crate_name, &ctypes, &cconsts)
+    save_to_file(&abi_rs, &env.config, |w| {
+        generate_abi_rs(env, &abi_rs, w, crate_name, &ctypes, &cconsts, &cmacros)
     });
 }
 
@@ -179,6 +195,27 @@ fn prepare_cconsts(env: &Env) -> Vec<CConstant> {
     constants
 }
 
+fn prepare_cfunction_macros(
+    env: &Env,
+    function_macros: &[function_macros::Info],
+) -> Vec<CFunctionMacro> {
+    let mut macros = Vec::new();
+    for func_macro in function_macros {
+        // Versioned macros would need the tests to be conditionally compiled too
+        if env.config.filter_version(func_macro.version).is_some() {
+            continue;
+        }
+        for args in &func_macro.tests {
+            let args = args.join(", ");
+            macros.push(CFunctionMacro {
+                call: format!("{}({})", func_macro.c_identifier, args),
+                rust_call: format!("{}({})", func_macro.name, args),
+            });
+        }
+    }
+    macros
+}
+
 /// Checks if type name is unlikely to correspond to a real C type name.
 fn is_name_made_up(name: &str) -> bool {
     // Unnamed types are assigned name during parsing, those names contain an underscore.
@@ -241,10 +278,43 @@ fn generate_constant_c(
     writeln!(w)?;
     writeln!(w, "#include \"manual.h\"")?;
     writeln!(w, "#include <stdio.h>")?;
-    writeln!(
-        w,
-        "{}",
-        r####"
+    writeln!(w, "{}", PRINT_CONSTANT)?;
+
+    writeln!(w, "{}", r"int main() {")?;
+
+    for cconst in cconsts {
+        writeln!(w, "    PRINT_CONSTANT({name});", name = cconst.name,)?;
+    }
+
+    writeln!(w, "    return 0;")?;
+    writeln!(w, "{}", r"}")
+}
+
+#[allow(clippy::write_literal)]
+fn generate_function_macro_c(
+    env: &Env,
+    path: &Path,
+    w: &mut dyn Write,
+    cmacros: &[CFunctionMacro],
+) -> io::Result<()> {
+    info!("Generating file {:?}", path);
+    general::start_comments(w, &env.config)?;
+    writeln!(w)?;
+    writeln!(w, "#include \"manual.h\"")?;
+    writeln!(w, "#include <stdio.h>")?;
+    writeln!(w, "{}", PRINT_CONSTANT)?;
+
+    writeln!(w, "{}", r"int main() {")?;
+
+    for cmacro in cmacros {
+        writeln!(w, "    PRINT_CONSTANT({});", cmacro.call)?;
+    }
+
+    writeln!(w, "    return 0;")?;
+    writeln!(w, "{}", r"}")
+}
+
+const PRINT_CONSTANT: &str = r####"
 #define PRINT_CONSTANT(CONSTANT_NAME) \
     printf("%s;", #CONSTANT_NAME); \
     printf(_Generic((CONSTANT_NAME), \
@@ -265,18 +335,7 @@ fn generate_constant_c(
                     long double: "%ld"), \
            CONSTANT_NAME); \
     printf("\n");
-"####
-    )?;
-
-    writeln!(w, "{}", r"int main() {")?;
-
-    for cconst in cconsts {
-        writeln!(w, "    PRINT_CONSTANT({name});", name = cconst.name,)?;
-    }
-
-    writeln!(w, "    return 0;")?;
-    writeln!(w, "{}", r"}")
-}
+"####;
 
 #[allow(clippy::write_literal)]
 fn generate_abi_rs(
@@ -286,6 +345,7 @@ fn generate_abi_rs(
     crate_name: &str,
     ctypes: &[CType],
     cconsts: &[CConstant],
+    cmacros: &[CFunctionMacro],
 ) -> io::Result<()> {
     let ns = env.library.namespace(MAIN_NAMESPACE);
     let package_name = ns.package_name.as_ref().expect("Missing package name");
@@ -294,8 +354,10 @@ fn generate_abi_rs(
     general::start_comments(w, &env.config)?;
     writeln!(w)?;
 
-    if !ctypes.is_empty() {
+    if !ctypes.is_empty() || !cmacros.is_empty() {
         writeln!(w, "use {}::*;", crate_name)?;
+    }
+    if !ctypes.is_empty() {
         writeln!(w, "use std::mem::{{align_of, size_of}};")?;
     }
 
@@ -539,5 +601,68 @@ const RUST_CONSTANTS: &[(&str, &str)] = &["##
         r##"];
 
 "##
+    )?;
+
+    if !cmacros.is_empty() {
+        generate_function_macros_test(w, cmacros)?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::write_literal)]
+fn generate_function_macros_test(w: &mut dyn Write, cmacros: &[CFunctionMacro]) -> io::Result<()> {
+    writeln!(
+        w,
+        "{}",
+        r####"#[test]
+fn cross_validate_function_macros_with_c() {
+    let mut c_results: Vec<(String, String)> = Vec::new();
+
+    for l in get_c_output("function_macro").unwrap().lines() {
+        let mut words = l.trim().split(';');
+        let call = words.next().expect("Failed to parse call").to_owned();
+        let value = words.next().expect("Failed to parse value").to_owned();
+        c_results.push((call, value));
+    }
+
+    let rust_results: Vec<(&str, String)> = unsafe {
+        vec!["####
+    )?;
+    for cmacro in cmacros {
+        writeln!(
+            w,
+            "            (\"{}\", {}.to_string()),",
+            cmacro.call, cmacro.rust_call
+        )?;
+    }
+    writeln!(
+        w,
+        "{}",
+        r####"        ]
+    };
+
+    let mut results = Results::default();
+
+    for ((rust_call, rust_value), (c_call, c_value)) in rust_results.iter().zip(c_results.iter()) {
+        if rust_call != c_call {
+            results.record_failed();
+            eprintln!("Call mismatch:\nRust: {:?}\nC:    {:?}", rust_call, c_call,);
+            continue;
+        }
+
+        if rust_value != c_value {
+            results.record_failed();
+            eprintln!(
+                "Function macro result mismatch for {}\nRust: {:?}\nC:    {:?}",
+                rust_call, rust_value, &c_value
+            );
+            continue;
+        }
+
+        results.record_passed();
+    }
+
+    results.expect_total_success();
+}"####
     )
 }
@@ -127,7 +127,7 @@ fn func_parameters(
     param_str
 }
 
-fn func_parameter(
+pub fn func_parameter(
     env: &Env,
     par: &RustParameter,
     bounds: &Bounds,
@@ -169,7 +169,7 @@ fn func_parameter(
     }
 }
 
-fn func_returns(env: &Env, analysis: &Trampoline) -> String {
+pub fn func_returns(env: &Env, analysis: &Trampoline) -> String {
     if analysis.ret.typ == Default::default() {
         String::new()
     } else if analysis.inhibit {
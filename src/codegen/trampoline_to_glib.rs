@@ -1,4 +1,7 @@
-use crate::{analysis::conversion_type::ConversionType, env, library};
+use crate::{
+    analysis::{conversion_type::ConversionType, trampoline_parameters::Transformation},
+    env, library,
+};
 
 pub trait TrampolineToGlib {
     fn trampoline_to_glib(&self, env: &env::Env) -> String;
@@ -17,6 +20,19 @@ impl TrampolineToGlib for library::Parameter {
     }
 }
 
+impl TrampolineToGlib for Transformation {
+    fn trampoline_to_glib(&self, _env: &env::Env) -> String {
+        use crate::analysis::conversion_type::ConversionType::*;
+        match self.conversion_type {
+            Direct => String::new(),
+            Scalar | Option | Result { .. } => ".into_glib()".to_owned(),
+            // Borrowed by the callee for the duration of the call
+            Borrow | Pointer => ".to_glib_none().0".to_owned(),
+            Unknown => "/*Unknown conversion*/".to_owned(),
+        }
+    }
+}
+
 fn to_glib_xxx(transfer: library::Transfer) -> &'static str {
     use crate::library::Transfer::*;
     match transfer {
@@ -1,4 +1,7 @@
-use super::{error::TomlHelper, parsable::Parse};
+use super::{
+    error::{TomlHelper, UnknownKeys},
+    parsable::Parse,
+};
 use log::error;
 use toml::Value;
 
@@ -11,7 +14,7 @@ pub struct ChildProperty {
 }
 
 impl Parse for ChildProperty {
-    fn parse(toml: &Value, object_name: &str) -> Option<ChildProperty> {
+    fn parse(toml: &Value, object_name: &str, unknown_keys: &UnknownKeys) -> Option<ChildProperty> {
         let name = toml
             .lookup("name")
             .and_then(Value::as_str)
@@ -26,6 +29,7 @@ impl Parse for ChildProperty {
         toml.check_unwanted(
             &["name", "type", "doc_hidden", "rename_getter"],
             &format!("child property {}", object_name),
+            unknown_keys,
         );
 
         let type_name = toml
@@ -67,7 +71,11 @@ pub struct ChildProperties {
 }
 
 impl Parse for ChildProperties {
-    fn parse(toml_object: &Value, object_name: &str) -> Option<ChildProperties> {
+    fn parse(
+        toml_object: &Value,
+        object_name: &str,
+        unknown_keys: &UnknownKeys,
+    ) -> Option<ChildProperties> {
         let child_name = toml_object
             .lookup("child_name")
             .and_then(Value::as_str)
@@ -79,7 +87,7 @@ impl Parse for ChildProperties {
         let mut properties: Vec<ChildProperty> = Vec::new();
         if let Some(configs) = toml_object.lookup("child_prop").and_then(Value::as_array) {
             for config in configs {
-                if let Some(item) = ChildProperty::parse(config, object_name) {
+                if let Some(item) = ChildProperty::parse(config, object_name, unknown_keys) {
                     properties.push(item);
                 }
             }
@@ -121,7 +129,7 @@ name = "prop"
 type = "prop_type"
 "#,
         );
-        let child = ChildProperty::parse(&toml, "a").unwrap();
+        let child = ChildProperty::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!("prop", child.name);
         assert_eq!("prop_type", child.type_name);
     }
@@ -133,14 +141,14 @@ type = "prop_type"
 name = "prop"
 "#,
         );
-        assert!(ChildProperty::parse(&tml, "a").is_none());
+        assert!(ChildProperty::parse(&tml, "a", &Default::default()).is_none());
 
         let tml = toml(
             r#"
 type_name = "prop_type"
 "#,
         );
-        assert!(ChildProperty::parse(&tml, "a").is_none());
+        assert!(ChildProperty::parse(&tml, "a", &Default::default()).is_none());
     }
 
     #[test]
@@ -157,7 +165,7 @@ name = "prop2"
 type = "prop_type2"
 "#,
         );
-        let props = ChildProperties::parse(&toml, "a").unwrap();
+        let props = ChildProperties::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(Some("child_name".into()), props.child_name);
         assert_eq!(Some("child_type".into()), props.child_type);
         assert_eq!(2, props.properties.len());
@@ -175,7 +183,7 @@ child_name = "child_name"
 child_type = "child_type"
 "#,
         );
-        let props = ChildProperties::parse(&toml, "a");
+        let props = ChildProperties::parse(&toml, "a", &Default::default());
         assert!(props.is_none());
     }
 
@@ -188,7 +196,7 @@ name = "prop"
 type = "prop_type"
 "#,
         );
-        let props = ChildProperties::parse(&toml, "a").unwrap();
+        let props = ChildProperties::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(None, props.child_name);
         assert_eq!(None, props.child_type);
         assert_eq!(1, props.properties.len());
@@ -204,7 +212,7 @@ name = "prop"
 type = "prop_type"
 "#,
         );
-        let props = ChildProperties::parse(&toml, "a").unwrap();
+        let props = ChildProperties::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(Some("child_name".into()), props.child_name);
         assert_eq!(None, props.child_type);
         assert_eq!(1, props.properties.len());
@@ -220,7 +228,7 @@ name = "prop"
 type = "prop_type"
 "#,
         );
-        let props = ChildProperties::parse(&toml, "a").unwrap();
+        let props = ChildProperties::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(None, props.child_name);
         assert_eq!(Some("child_type".into()), props.child_type);
         assert_eq!(1, props.properties.len());
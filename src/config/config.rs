@@ -1,9 +1,11 @@
 use super::{
     external_libraries::{read_external_libraries, ExternalLibrary},
-    gobjects, WorkMode,
+    function_macros::{read_function_macros, FunctionMacro},
+    gobjects, validation, OutputFormat, WorkMode,
 };
 use crate::{
-    config::error::TomlHelper,
+    config::error::{TomlHelper, UnknownKeys},
+    file_saver::GeneratedFiles,
     git::{repo_hash, repo_remote_url, toplevel},
     library::{self, Library},
     nameutil::set_crate_name_overrides,
@@ -103,22 +105,32 @@ pub struct Config {
     pub doc_target_path: PathBuf,
     pub external_libraries: Vec<ExternalLibrary>,
     pub objects: gobjects::GObjects,
+    pub function_macros: Vec<FunctionMacro>,
     pub min_cfg_version: Version,
     pub make_backup: bool,
+    /// Only compare the generated code with the existing files, without writing them.
+    pub check: bool,
+    /// Fail on unknown config keys and on config entries matching nothing.
+    pub strict: bool,
+    pub output_format: OutputFormat,
     pub generate_safety_asserts: bool,
     pub deprecate_by_min_version: bool,
     pub show_statistics: bool,
     pub concurrency: library::Concurrency,
     pub single_version_file: Option<PathBuf>,
     pub generate_display_trait: bool,
+    pub generate_serde: bool,
     pub trust_return_value_nullability: bool,
     pub docs_rs_features: Vec<String>,
     pub disable_format: bool,
     pub split_build_rs: bool,
+    pub generate_source_positions: bool,
     pub extra_versions: Vec<Version>,
     pub lib_version_overrides: HashMap<Version, Version>,
     pub feature_dependencies: HashMap<Version, Vec<String>>,
     pub dox_feature_dependencies: Vec<String>,
+    /// Files saved by the run, checked against the generated directory in check mode.
+    pub generated_files: GeneratedFiles,
 }
 
 impl Config {
@@ -133,6 +145,9 @@ impl Config {
         make_backup: bool,
         show_statistics: bool,
         disable_format: bool,
+        check: bool,
+        strict: bool,
+        output_format: OutputFormat,
     ) -> Result<Config, String>
     where
         S: Into<Option<&'a str>>,
@@ -160,6 +175,43 @@ impl Config {
             }
         };
 
+        // Reported while parsing, the config is rejected at the end in strict mode.
+        let unknown_keys = &UnknownKeys::default();
+        if let Some(options) = toml.lookup("options") {
+            options.check_unwanted(
+                &[
+                    "auto_path",
+                    "concurrency",
+                    "deprecate_by_min_version",
+                    "disable_format",
+                    "doc_target_path",
+                    "docs_rs_features",
+                    "dox_feature_dependencies",
+                    "external_libraries",
+                    "extra_versions",
+                    "generate",
+                    "generate_builder",
+                    "generate_display_trait",
+                    "generate_safety_asserts",
+                    "generate_serde",
+                    "generate_source_positions",
+                    "girs_directories",
+                    "ignore",
+                    "library",
+                    "manual",
+                    "min_cfg_version",
+                    "single_version_file",
+                    "split_build_rs",
+                    "target_path",
+                    "trust_return_value_nullability",
+                    "version",
+                    "work_mode",
+                ],
+                "options",
+                unknown_keys,
+            );
+        }
+
         let overrides = read_crate_name_overrides(&toml);
         if !overrides.is_empty() {
             set_crate_name_overrides(overrides);
@@ -229,6 +281,7 @@ impl Config {
         let auto_path = match toml.lookup("options.auto_path") {
             Some(p) => target_path.join(p.as_result_str("options.auto_path")?),
             None if work_mode == WorkMode::Normal => target_path.join("src").join("auto"),
+            None if work_mode == WorkMode::Subclass => target_path.join("src").join("subclass"),
             None => target_path.join("src"),
         };
 
@@ -250,6 +303,11 @@ impl Config {
             None => true,
         };
 
+        let generate_serde = match toml.lookup("options.generate_serde") {
+            Some(v) => v.as_result_bool("options.generate_serde")?,
+            None => false,
+        };
+
         let trust_return_value_nullability =
             match toml.lookup("options.trust_return_value_nullability") {
                 Some(v) => v.as_result_bool("options.trust_return_value_nullability")?,
@@ -278,6 +336,7 @@ impl Config {
                     generate_display_trait,
                     generate_builder,
                     trust_return_value_nullability,
+                    unknown_keys,
                 )
             })
             .unwrap_or_default();
@@ -291,6 +350,7 @@ impl Config {
         );
 
         let external_libraries = read_external_libraries(&toml)?;
+        let function_macros = read_function_macros(&toml, unknown_keys)?;
 
         let min_cfg_version = match toml.lookup("options.min_cfg_version") {
             Some(v) => v.as_result_str("options.min_cfg_version")?.parse()?,
@@ -333,11 +393,24 @@ impl Config {
             None => false,
         };
 
+        let generate_source_positions = match toml.lookup("options.generate_source_positions") {
+            Some(v) => v.as_result_bool("options.generate_source_positions")?,
+            None => false,
+        };
+
         let extra_versions = read_extra_versions(&toml)?;
         let lib_version_overrides = read_lib_version_overrides(&toml)?;
         let feature_dependencies = read_feature_dependencies(&toml)?;
         let dox_feature_dependencies = read_dox_feature_dependencies(&toml)?;
 
+        if strict && unknown_keys.count() > 0 {
+            return Err(format!(
+                "{} unknown key(s) in \"{}\"",
+                unknown_keys.count(),
+                config_file.display()
+            ));
+        }
+
         Ok(Config {
             work_mode,
             girs_dirs,
@@ -349,22 +422,29 @@ impl Config {
             doc_target_path,
             external_libraries,
             objects,
+            function_macros,
             min_cfg_version,
             make_backup,
+            check,
+            strict,
+            output_format,
             generate_safety_asserts,
             deprecate_by_min_version,
             show_statistics,
             concurrency,
             single_version_file,
             generate_display_trait,
+            generate_serde,
             trust_return_value_nullability,
             docs_rs_features,
             disable_format,
             split_build_rs,
+            generate_source_positions,
             extra_versions,
             lib_version_overrides,
             feature_dependencies,
             dox_feature_dependencies,
+            generated_files: GeneratedFiles::default(),
         })
     }
 
@@ -386,6 +466,22 @@ impl Config {
         gobjects::resolve_type_ids(&mut self.objects, library)
     }
 
+    /// Warns about the config entries matching nothing in the library, failing on them
+    /// in strict mode.
+    pub fn check_unmatched(&self, library: &Library) -> Result<(), String> {
+        let unmatched = validation::unmatched_entries(&self.objects, library);
+        for entry in &unmatched {
+            warn!("{}", entry);
+        }
+        if self.strict && !unmatched.is_empty() {
+            return Err(format!(
+                "{} config entries matching nothing in the library",
+                unmatched.len()
+            ));
+        }
+        Ok(())
+    }
+
     pub fn check_disable_format(&mut self) {
         if !self.disable_format && !crate::fmt::check_fmt() {
             warn!("Formatter not found, options.disable_format set to true");
@@ -553,4 +649,60 @@ mod tests {
             PathBuf::from("/tmp/glib/_vers.dat")
         );
     }
+
+    #[test]
+    fn subclass_work_mode() {
+        let config = crate::env::tests::env("[options]\nwork_mode = \"subclass\"\n").config;
+        assert_eq!(config.work_mode, WorkMode::Subclass);
+        assert_eq!(
+            config.auto_path,
+            std::env::temp_dir().join("src").join("subclass")
+        );
+
+        let config = crate::env::tests::env(
+            "[options]\nwork_mode = \"subclass\"\nauto_path = \"src/imp\"\n",
+        )
+        .config;
+        assert_eq!(
+            config.auto_path,
+            std::env::temp_dir().join("src").join("imp")
+        );
+    }
+
+    #[test]
+    fn unknown_options_fail_strict() {
+        let config_path =
+            std::env::temp_dir().join(format!("gir-unknown-options-{}.toml", std::process::id()));
+        std::fs::write(
+            &config_path,
+            "[options]\nwork_mode = \"normal\"\ngenerate_serd = true\n",
+        )
+        .unwrap();
+        let new = |strict| {
+            Config::new(
+                config_path.to_str(),
+                None::<WorkMode>,
+                &[crate::env::tests::girs_dir().to_str().unwrap().to_owned()],
+                Some("Test"),
+                Some("1.0"),
+                std::env::temp_dir().to_str(),
+                None,
+                false,
+                false,
+                true,
+                false,
+                strict,
+                OutputFormat::default(),
+            )
+        };
+        let lenient = new(false);
+        let strict = new(true);
+        std::fs::remove_file(&config_path).unwrap();
+
+        assert!(lenient.is_ok());
+        assert_eq!(
+            strict.err(),
+            Some(format!("1 unknown key(s) in \"{}\"", config_path.display()))
+        );
+    }
 }
@@ -1,4 +1,9 @@
-use super::{error::TomlHelper, gobjects::GStatus, ident::Ident, parsable::Parse};
+use super::{
+    error::{TomlHelper, UnknownKeys},
+    gobjects::GStatus,
+    ident::Ident,
+    parsable::Parse,
+};
 use crate::version::Version;
 use log::error;
 use toml::Value;
@@ -12,7 +17,7 @@ pub struct Constant {
 }
 
 impl Parse for Constant {
-    fn parse(toml: &Value, object_name: &str) -> Option<Constant> {
+    fn parse(toml: &Value, object_name: &str, unknown_keys: &UnknownKeys) -> Option<Constant> {
         let ident = match Ident::parse(toml, object_name, "function") {
             Some(ident) => ident,
             None => {
@@ -33,6 +38,7 @@ impl Parse for Constant {
                 "pattern",
             ],
             &format!("function {}", object_name),
+            unknown_keys,
         );
 
         let version = toml
@@ -1,4 +1,7 @@
-use super::{error::TomlHelper, parsable::Parse};
+use super::{
+    error::{TomlHelper, UnknownKeys},
+    parsable::Parse,
+};
 use log::error;
 use toml::Value;
 
@@ -9,7 +12,7 @@ pub struct Derive {
 }
 
 impl Parse for Derive {
-    fn parse(toml: &Value, object_name: &str) -> Option<Derive> {
+    fn parse(toml: &Value, object_name: &str, unknown_keys: &UnknownKeys) -> Option<Derive> {
         let names = match toml.lookup("name").and_then(Value::as_str) {
             Some(names) => names,
             None => {
@@ -20,6 +23,7 @@ impl Parse for Derive {
         toml.check_unwanted(
             &["name", "cfg_condition"],
             &format!("derive {}", object_name),
+            unknown_keys,
         );
 
         let cfg_condition = toml
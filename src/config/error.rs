@@ -1,10 +1,22 @@
 use log::error;
+use std::cell::Cell;
+
+/// Counts the unknown keys reported by `check_unwanted` while parsing a config, which fail
+/// it in strict mode.
+#[derive(Debug, Default)]
+pub struct UnknownKeys(Cell<usize>);
+
+impl UnknownKeys {
+    pub fn count(&self) -> usize {
+        self.0.get()
+    }
+}
 
 pub trait TomlHelper
 where
     Self: Sized,
 {
-    fn check_unwanted(&self, options: &[&str], err_msg: &str);
+    fn check_unwanted(&self, options: &[&str], err_msg: &str, unknown_keys: &UnknownKeys);
     fn lookup<'a>(&'a self, option: &str) -> Option<&'a toml::Value>;
     fn lookup_str<'a>(&'a self, option: &'a str, err: &str) -> Result<&'a str, String>;
     fn lookup_vec<'a>(&'a self, option: &'a str, err: &str) -> Result<&'a Vec<Self>, String>;
@@ -14,7 +26,7 @@ where
 }
 
 impl TomlHelper for toml::Value {
-    fn check_unwanted(&self, options: &[&str], err_msg: &str) {
+    fn check_unwanted(&self, options: &[&str], err_msg: &str, unknown_keys: &UnknownKeys) {
         let mut ret = Vec::new();
         let table = match self.as_table() {
             Some(table) => table,
@@ -26,6 +38,7 @@ impl TomlHelper for toml::Value {
             }
         }
         if !ret.is_empty() {
+            unknown_keys.0.set(unknown_keys.count() + ret.len());
             error!(
                 "\"{}\": Unknown key{}: {:?}",
                 err_msg,
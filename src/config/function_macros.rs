@@ -0,0 +1,162 @@
+use super::error::{TomlHelper, UnknownKeys};
+use crate::version::Version;
+use toml::Value;
+
+/// Function-like C macro to bind as an `#[inline]` Rust function.
+///
+/// Without `body` the expansion is inferred when possible, e.g. for `IS_FOO` type checks
+/// in sys mode.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FunctionMacro {
+    /// Name of the macro in the GIR, without the namespace prefix.
+    pub name: String,
+    pub rename: Option<String>,
+    /// Rust types of the parameters, in the order of the macro arguments.
+    pub parameters: Vec<String>,
+    pub return_type: Option<String>,
+    pub body: Option<String>,
+    pub version: Option<Version>,
+    /// Arguments with which the Rust function is checked against the C macro.
+    pub tests: Vec<Vec<String>>,
+}
+
+pub fn read_function_macros(
+    toml: &Value,
+    unknown_keys: &UnknownKeys,
+) -> Result<Vec<FunctionMacro>, String> {
+    let entries = match toml.lookup("function_macro") {
+        Some(entries) => entries.as_result_vec("function_macro")?,
+        None => return Ok(Vec::new()),
+    };
+    entries
+        .iter()
+        .map(|entry| parse(entry, unknown_keys))
+        .collect()
+}
+
+fn parse(toml: &Value, unknown_keys: &UnknownKeys) -> Result<FunctionMacro, String> {
+    let name = toml
+        .lookup_str("name", "No `name` given for function_macro")?
+        .to_owned();
+    let err_msg = format!("function_macro {}", name);
+    toml.check_unwanted(
+        &[
+            "name",
+            "rename",
+            "parameters",
+            "return",
+            "body",
+            "version",
+            "tests",
+        ],
+        &err_msg,
+        unknown_keys,
+    );
+
+    let str_option = |option: &str| -> Result<Option<String>, String> {
+        match toml.lookup(option) {
+            Some(v) => Ok(Some(v.as_result_str(option)?.to_owned())),
+            None => Ok(None),
+        }
+    };
+
+    let parameters = match toml.lookup("parameters") {
+        Some(v) => v
+            .as_result_vec("parameters")?
+            .iter()
+            .map(|p| p.as_result_str("parameters").map(ToOwned::to_owned))
+            .collect::<Result<_, _>>()?,
+        None => Vec::new(),
+    };
+    let version = match str_option("version")? {
+        Some(v) => Some(v.parse()?),
+        None => None,
+    };
+    let mut tests = Vec::new();
+    if let Some(v) = toml.lookup("tests") {
+        for args in v.as_result_vec("tests")? {
+            let args = args
+                .as_result_vec("tests")?
+                .iter()
+                .map(|arg| match arg {
+                    Value::String(s) => Ok(s.clone()),
+                    Value::Integer(_) | Value::Boolean(_) => Ok(arg.to_string()),
+                    _ => Err(format!(
+                        "{}: test arguments expected to be strings or integers, found {}",
+                        err_msg,
+                        arg.type_str()
+                    )),
+                })
+                .collect::<Result<_, _>>()?;
+            tests.push(args);
+        }
+    }
+
+    Ok(FunctionMacro {
+        name,
+        rename: str_option("rename")?,
+        parameters,
+        return_type: str_option("return")?,
+        body: str_option("body")?,
+        version,
+        tests,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toml(input: &str) -> ::toml::Value {
+        let value = ::toml::from_str(input);
+        assert!(value.is_ok());
+        value.unwrap()
+    }
+
+    #[test]
+    fn function_macros() {
+        let toml = toml(
+            r#"
+[[function_macro]]
+name = "IS_WIDGET"
+
+[[function_macro]]
+name = "CHECK_VERSION"
+rename = "check_version_macro"
+parameters = ["c_uint", "c_uint", "c_uint"]
+return = "gboolean"
+body = "(MAJOR_VERSION > major) as gboolean"
+version = "3.2"
+tests = [[3, 0, 0], ["4", "0", "0"]]
+"#,
+        );
+        let macros = read_function_macros(&toml, &Default::default()).unwrap();
+
+        assert_eq!(
+            macros[0],
+            FunctionMacro {
+                name: "IS_WIDGET".to_owned(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(macros[1].rename.as_deref(), Some("check_version_macro"));
+        assert_eq!(macros[1].parameters, vec!["c_uint", "c_uint", "c_uint"]);
+        assert_eq!(macros[1].return_type.as_deref(), Some("gboolean"));
+        assert_eq!(macros[1].version, Some(Version::Full(3, 2, 0)));
+        assert_eq!(
+            macros[1].tests,
+            vec![vec!["3", "0", "0"], vec!["4", "0", "0"]]
+        );
+    }
+
+    #[test]
+    fn function_macros_without_name() {
+        let toml = toml(
+            r#"
+[[function_macro]]
+body = "0"
+"#,
+        );
+        assert!(read_function_macros(&toml, &Default::default()).is_err());
+    }
+}
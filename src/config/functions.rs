@@ -1,5 +1,5 @@
 use super::{
-    error::TomlHelper,
+    error::{TomlHelper, UnknownKeys},
     gobjects::GStatus,
     ident::Ident,
     parameter_matchable::Functionlike,
@@ -29,7 +29,7 @@ pub struct Parameter {
 }
 
 impl Parse for Parameter {
-    fn parse(toml: &Value, object_name: &str) -> Option<Parameter> {
+    fn parse(toml: &Value, object_name: &str, unknown_keys: &UnknownKeys) -> Option<Parameter> {
         let ident = match Ident::parse(toml, object_name, "function parameter") {
             Some(ident) => ident,
             None => {
@@ -52,6 +52,7 @@ impl Parse for Parameter {
                 "string_type",
             ],
             &format!("function parameter {}", object_name),
+            unknown_keys,
         );
 
         let constant = toml
@@ -123,7 +124,7 @@ pub struct Return {
 }
 
 impl Return {
-    pub fn parse(toml: Option<&Value>, object_name: &str) -> Return {
+    pub fn parse(toml: Option<&Value>, object_name: &str, unknown_keys: &UnknownKeys) -> Return {
         if toml.is_none() {
             return Return {
                 nullable: None,
@@ -150,6 +151,7 @@ impl Return {
                 "type",
             ],
             "return",
+            unknown_keys,
         );
 
         let nullable = v.lookup("nullable").and_then(Value::as_bool).map(Nullable);
@@ -239,10 +241,12 @@ pub struct Function {
     pub bypass_auto_rename: bool,
     pub is_constructor: Option<bool>,
     pub assertion: Option<SafetyAssertionMode>,
+    /// C identifier of the function finishing this async function.
+    pub finish_function: Option<String>,
 }
 
 impl Parse for Function {
-    fn parse(toml: &Value, object_name: &str) -> Option<Function> {
+    fn parse(toml: &Value, object_name: &str, unknown_keys: &UnknownKeys) -> Option<Function> {
         let ident = match Ident::parse(toml, object_name, "function") {
             Some(ident) => ident,
             None => {
@@ -273,8 +277,10 @@ impl Parse for Function {
                 "bypass_auto_rename",
                 "constructor",
                 "assertion",
+                "finish_function",
             ],
             &format!("function {}", object_name),
+            unknown_keys,
         );
 
         let status = {
@@ -302,8 +308,8 @@ impl Parse for Function {
             .lookup("cfg_condition")
             .and_then(Value::as_str)
             .map(ToOwned::to_owned);
-        let parameters = Parameters::parse(toml.lookup("parameter"), object_name);
-        let ret = Return::parse(toml.lookup("return"), object_name);
+        let parameters = Parameters::parse(toml.lookup("parameter"), object_name, unknown_keys);
+        let ret = Return::parse(toml.lookup("return"), object_name, unknown_keys);
         let doc_hidden = toml
             .lookup("doc_hidden")
             .and_then(Value::as_bool)
@@ -349,6 +355,10 @@ impl Parse for Function {
             error!("{}", err);
         }
         let assertion = assertion.ok().flatten();
+        let finish_function = toml
+            .lookup("finish_function")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned);
 
         Some(Function {
             ident,
@@ -367,6 +377,7 @@ impl Parse for Function {
             bypass_auto_rename,
             is_constructor,
             assertion,
+            finish_function,
         })
     }
 }
@@ -419,7 +430,7 @@ name = "func1"
 ignore = true
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.ident, Ident::Name("func1".into()));
         assert!(f.status.ignored());
     }
@@ -432,7 +443,7 @@ name = "func1"
 manual = true
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.ident, Ident::Name("func1".into()));
         assert!(f.status.manual());
     }
@@ -444,7 +455,7 @@ manual = true
 name = "func1"
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.version, None);
         assert!(f.status.need_generate());
     }
@@ -457,7 +468,7 @@ name = "func1"
 version = "3.20"
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.version, Some(Version::Full(3, 20, 0)));
     }
 
@@ -468,7 +479,7 @@ version = "3.20"
 name = "func1"
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.cfg_condition, None);
     }
 
@@ -480,7 +491,7 @@ name = "func1"
 cfg_condition = 'unix'
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.cfg_condition, Some("unix".to_string()));
     }
 
@@ -491,7 +502,7 @@ cfg_condition = 'unix'
 name = "func1"
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.ret.nullable, None);
     }
 
@@ -503,7 +514,7 @@ name = "func1"
 [return]
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.ret.nullable, None);
     }
 
@@ -527,7 +538,7 @@ pattern = "par4"
 const = true
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         let pars = f.parameters;
         assert_eq!(pars.len(), 4);
         assert_eq!(pars[0].ident, Ident::Name("par1".into()));
@@ -553,7 +564,7 @@ name = "func1"
 nullable = false
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.ret.nullable, Some(Nullable(false)));
     }
 
@@ -566,13 +577,13 @@ name = "func1"
 nullable = true
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.ret.nullable, Some(Nullable(true)));
     }
 
     #[test]
     fn functions_parse_empty_for_none() {
-        let fns = Functions::parse(None, "a");
+        let fns = Functions::parse(None, "a", &Default::default());
         assert!(fns.is_empty());
     }
 
@@ -592,7 +603,7 @@ pattern = 'func3\w+'
 pattern = 'bad_func4[\w+'
 "#,
         );
-        let fns = Functions::parse(Some(&toml), "a");
+        let fns = Functions::parse(Some(&toml), "a", &Default::default());
         assert_eq!(fns.len(), 3);
         assert_eq!(fns[0].ident, Ident::Name("func1".into()));
         assert_eq!(fns[1].ident, Ident::Name("func2".into()));
@@ -613,7 +624,7 @@ name = "func2"
 pattern = 'func\d+'
 "#,
         );
-        let fns = Functions::parse(Some(&toml), "a");
+        let fns = Functions::parse(Some(&toml), "a", &Default::default());
         assert_eq!(fns.len(), 3);
 
         assert_eq!(fns.matched("func1").len(), 2);
@@ -646,7 +657,7 @@ name="par3"
 pattern='par\d+'
 "#,
         );
-        let fns = Functions::parse(Some(&toml), "a");
+        let fns = Functions::parse(Some(&toml), "a", &Default::default());
         assert_eq!(fns.len(), 2);
         let m = fns.matched("func");
         assert_eq!(m.len(), 2);
@@ -666,7 +677,7 @@ name = "func1"
 rename = "another"
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.rename, Some("another".to_owned()));
     }
 
@@ -678,7 +689,7 @@ name = "func1"
 rename = "anoth er"
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         assert!(f.is_none());
     }
 
@@ -690,11 +701,26 @@ name = "func1"
 bypass_auto_rename = true
 "#,
         );
-        let f = Function::parse(&toml, "a").unwrap();
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.ident, Ident::Name("func1".into()));
         assert!(f.bypass_auto_rename);
     }
 
+    #[test]
+    fn function_parse_finish_function() {
+        let toml = toml(
+            r#"
+name = "replace_contents_bytes_async"
+finish_function = "g_file_replace_contents_finish"
+"#,
+        );
+        let f = Function::parse(&toml, "a", &Default::default()).unwrap();
+        assert_eq!(
+            f.finish_function.as_deref(),
+            Some("g_file_replace_contents_finish")
+        );
+    }
+
     #[test]
     fn parse_return_mandatory_default() {
         let toml = toml(
@@ -702,7 +728,7 @@ bypass_auto_rename = true
 name = "func1"
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         assert!(f.ret.mandatory.is_none());
     }
@@ -716,7 +742,7 @@ name = "func1"
     mandatory = true
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         assert_eq!(f.ret.mandatory, Some(Mandatory(true)));
     }
@@ -730,7 +756,7 @@ name = "func1"
     mandatory = false
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         assert_eq!(f.ret.mandatory, Some(Mandatory(false)));
     }
@@ -744,7 +770,7 @@ name = "func1"
     name = "param1"
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         let param1 = &f.parameters[0];
         assert!(param1.mandatory.is_none());
@@ -760,7 +786,7 @@ name = "func1"
     mandatory = true
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         let param1 = &f.parameters[0];
         assert_eq!(param1.mandatory, Some(Mandatory(true)));
@@ -776,7 +802,7 @@ name = "func1"
     mandatory = false
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         let param1 = &f.parameters[0];
         assert_eq!(param1.mandatory, Some(Mandatory(false)));
@@ -789,7 +815,7 @@ name = "func1"
 name = "func1"
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         assert!(f.ret.infallible.is_none());
     }
@@ -803,7 +829,7 @@ name = "func1"
     infallible = true
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         assert_eq!(f.ret.infallible, Some(Infallible(true)));
     }
@@ -817,7 +843,7 @@ name = "func1"
     infallible = false
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         assert_eq!(f.ret.infallible, Some(Infallible(false)));
     }
@@ -831,7 +857,7 @@ name = "func1"
     name = "param1"
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         let param1 = &f.parameters[0];
         assert!(param1.infallible.is_none());
@@ -847,7 +873,7 @@ name = "func1"
     infallible = true
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         let param1 = &f.parameters[0];
         assert_eq!(param1.infallible, Some(Infallible(true)));
@@ -863,7 +889,7 @@ name = "func1"
     infallible = false
 "#,
         );
-        let f = Function::parse(&toml, "a");
+        let f = Function::parse(&toml, "a", &Default::default());
         let f = f.unwrap();
         let param1 = &f.parameters[0];
         assert_eq!(param1.infallible, Some(Infallible(false)));
@@ -10,10 +10,10 @@ use super::{
 use crate::{
     analysis::{conversion_type::ConversionType, ref_mode},
     config::{
-        error::TomlHelper,
+        error::{TomlHelper, UnknownKeys},
         parsable::{Parsable, Parse},
     },
-    library::{self, Library, TypeId, MAIN_NAMESPACE},
+    library::{self, Library, TypeId},
     version::Version,
 };
 use log::{error, warn};
@@ -62,6 +62,8 @@ impl FromStr for GStatus {
 pub struct GObject {
     pub name: String,
     pub functions: Functions,
+    /// Virtual methods, only their `ignore` and `version` are used.
+    pub virtual_methods: Functions,
     pub constants: Constants,
     pub signals: Signals,
     pub members: Members,
@@ -85,8 +87,15 @@ pub struct GObject {
     pub align: Option<u32>,
     pub generate_builder: bool,
     pub builder_postprocess: Option<String>,
+    pub generate_subclass: bool,
     pub init_function_expression: Option<String>,
     pub clear_function_expression: Option<String>,
+    /// Forces (or prevents) generating a record as a plain value over its sys struct.
+    pub direct: Option<bool>,
+    /// Generates `*_stream` functions for the signals supporting them.
+    pub generate_signal_streams: bool,
+    /// Overrides `options.generate_serde` for enums, flags and direct records.
+    pub generate_serde: Option<bool>,
 }
 
 impl Default for GObject {
@@ -94,6 +103,7 @@ impl Default for GObject {
         GObject {
             name: "Default".into(),
             functions: Functions::new(),
+            virtual_methods: Functions::new(),
             constants: Constants::new(),
             signals: Signals::new(),
             members: Members::new(),
@@ -117,8 +127,12 @@ impl Default for GObject {
             align: None,
             generate_builder: false,
             builder_postprocess: None,
+            generate_subclass: false,
             init_function_expression: None,
             clear_function_expression: None,
+            direct: None,
+            generate_signal_streams: false,
+            generate_serde: None,
         }
     }
 }
@@ -132,6 +146,7 @@ pub fn parse_toml(
     generate_display_trait: bool,
     generate_builder: bool,
     trust_return_value_nullability: bool,
+    unknown_keys: &UnknownKeys,
 ) -> GObjects {
     let mut objects = GObjects::new();
     for toml_object in toml_objects.as_array().unwrap() {
@@ -141,6 +156,7 @@ pub fn parse_toml(
             generate_display_trait,
             generate_builder,
             trust_return_value_nullability,
+            unknown_keys,
         );
         objects.insert(gobject.name.clone(), gobject);
     }
@@ -160,9 +176,17 @@ fn ref_mode_from_str(ref_mode: &str) -> Option<ref_mode::RefMode> {
     }
 }
 
-pub fn parse_conversion_type(toml: Option<&Value>, object_name: &str) -> Option<ConversionType> {
+pub fn parse_conversion_type(
+    toml: Option<&Value>,
+    object_name: &str,
+    unknown_keys: &UnknownKeys,
+) -> Option<ConversionType> {
     let v = toml?;
-    v.check_unwanted(&["variant", "ok_type", "err_type"], "conversion_type");
+    v.check_unwanted(
+        &["variant", "ok_type", "err_type"],
+        "conversion_type",
+        unknown_keys,
+    );
 
     let (conversion_type, ok_type, err_type) = match &v {
         Value::Table(table) => {
@@ -228,6 +252,7 @@ fn parse_object(
     default_generate_display_trait: bool,
     generate_builder: bool,
     trust_return_value_nullability: bool,
+    unknown_keys: &UnknownKeys,
 ) -> GObject {
     let name: String = toml_object
         .lookup("name")
@@ -241,6 +266,7 @@ fn parse_object(
             "name",
             "status",
             "function",
+            "virtual_method",
             "constant",
             "signal",
             "member",
@@ -265,10 +291,15 @@ fn parse_object(
             "align",
             "generate_builder",
             "builder_postprocess",
+            "generate_subclass",
             "init_function_expression",
             "clear_function_expression",
+            "direct",
+            "generate_signal_streams",
+            "generate_serde",
         ],
         &format!("object {}", name),
+        unknown_keys,
     );
 
     let status = match toml_object.lookup("status") {
@@ -278,13 +309,15 @@ fn parse_object(
         None => Default::default(),
     };
 
-    let constants = Constants::parse(toml_object.lookup("constant"), &name);
-    let functions = Functions::parse(toml_object.lookup("function"), &name);
+    let constants = Constants::parse(toml_object.lookup("constant"), &name, unknown_keys);
+    let functions = Functions::parse(toml_object.lookup("function"), &name, unknown_keys);
+    let virtual_methods =
+        Functions::parse(toml_object.lookup("virtual_method"), &name, unknown_keys);
     let signals = {
         let mut v = Vec::new();
         if let Some(configs) = toml_object.lookup("signal").and_then(Value::as_array) {
             for config in configs {
-                if let Some(item) = Signal::parse(config, &name, concurrency) {
+                if let Some(item) = Signal::parse(config, &name, concurrency, unknown_keys) {
                     v.push(item);
                 }
             }
@@ -292,11 +325,11 @@ fn parse_object(
 
         v
     };
-    let members = Members::parse(toml_object.lookup("member"), &name);
-    let properties = Properties::parse(toml_object.lookup("property"), &name);
+    let members = Members::parse(toml_object.lookup("member"), &name, unknown_keys);
+    let properties = Properties::parse(toml_object.lookup("property"), &name, unknown_keys);
     let derives = toml_object
         .lookup("derive")
-        .map(|derives| Derives::parse(Some(derives), &name));
+        .map(|derives| Derives::parse(Some(derives), &name, unknown_keys));
     let module_name = toml_object
         .lookup("module_name")
         .and_then(Value::as_str)
@@ -327,8 +360,9 @@ fn parse_object(
         .lookup("ref_mode")
         .and_then(Value::as_str)
         .and_then(ref_mode_from_str);
-    let conversion_type = parse_conversion_type(toml_object.lookup("conversion_type"), &name);
-    let child_properties = ChildProperties::parse(toml_object, &name);
+    let conversion_type =
+        parse_conversion_type(toml_object.lookup("conversion_type"), &name, unknown_keys);
+    let child_properties = ChildProperties::parse(toml_object, &name, unknown_keys);
     let must_use = toml_object
         .lookup("must_use")
         .and_then(Value::as_bool)
@@ -372,6 +406,10 @@ fn parse_object(
         .lookup("builder_postprocess")
         .and_then(Value::as_str)
         .map(String::from);
+    let generate_subclass = toml_object
+        .lookup("generate_subclass")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
     let init_function_expression = toml_object
         .lookup("init_function_expression")
         .and_then(Value::as_str)
@@ -381,6 +419,15 @@ fn parse_object(
         .and_then(Value::as_str)
         .map(ToOwned::to_owned);
 
+    let direct = toml_object.lookup("direct").and_then(Value::as_bool);
+    let generate_signal_streams = toml_object
+        .lookup("generate_signal_streams")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let generate_serde = toml_object
+        .lookup("generate_serde")
+        .and_then(Value::as_bool);
+
     if (init_function_expression.is_some() && clear_function_expression.is_none())
         || (init_function_expression.is_none() && clear_function_expression.is_some())
     {
@@ -414,6 +461,7 @@ fn parse_object(
     GObject {
         name,
         functions,
+        virtual_methods,
         constants,
         signals,
         members,
@@ -437,8 +485,12 @@ fn parse_object(
         align,
         generate_builder,
         builder_postprocess,
+        generate_subclass,
         init_function_expression,
         clear_function_expression,
+        direct,
+        generate_signal_streams,
+        generate_serde,
     }
 }
 
@@ -497,15 +549,11 @@ fn parse_status_shorthand(
     }
 }
 
+/// Objects missing from the library are left without type id, see `Config::check_unmatched`.
 pub fn resolve_type_ids(objects: &mut GObjects, library: &Library) {
-    let ns = library.namespace(MAIN_NAMESPACE);
-    let global_functions_name = format!("{}.*", ns.name);
-
     for (name, object) in objects.iter_mut() {
         let type_id = library.find_type(0, name);
-        if type_id.is_none() && name != &global_functions_name {
-            warn!("Configured object `{}` missing from the library", name);
-        } else if object.generate_builder {
+        if object.generate_builder {
             if let Some(ref type_id) = type_id {
                 if library.type_(*type_id).is_abstract() {
                     warn!(
@@ -543,7 +591,14 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(object.conversion_type, None);
     }
 
@@ -557,7 +612,14 @@ conversion_type = "Option"
 "#,
         );
 
-        let object = parse_object(&toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            &toml,
+            Concurrency::default(),
+            false,
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(object.conversion_type, Some(ConversionType::Option));
     }
 
@@ -572,7 +634,14 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(object.conversion_type, Some(ConversionType::Option));
     }
 
@@ -587,7 +656,14 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(
             object.conversion_type,
             Some(ConversionType::Result {
@@ -609,7 +685,14 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(
             object.conversion_type,
             Some(ConversionType::Result {
@@ -632,7 +715,14 @@ status = "generate"
 "#,
         );
 
-        let object = parse_object(toml, Concurrency::default(), false, false, false);
+        let object = parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            false,
+            &Default::default(),
+        );
         assert_eq!(
             object.conversion_type,
             Some(ConversionType::Result {
@@ -641,4 +731,63 @@ status = "generate"
             }),
         );
     }
+
+    #[test]
+    fn generate_switches() {
+        type Switch = fn(&GObject) -> bool;
+        let switches: [(&str, Switch); 2] = [
+            ("generate_subclass", |o| o.generate_subclass),
+            ("generate_signal_streams", |o| o.generate_signal_streams),
+        ];
+        for &(key, get) in &switches {
+            for value in &[None, Some(false), Some(true)] {
+                let mut config = "name = \"Test\"\nstatus = \"generate\"\n".to_owned();
+                if let Some(value) = value {
+                    config.push_str(&format!("{} = {}\n", key, value));
+                }
+                let object = parse_object(
+                    &toml(&config),
+                    Concurrency::default(),
+                    false,
+                    false,
+                    false,
+                    &Default::default(),
+                );
+                assert_eq!(
+                    get(&object),
+                    value.unwrap_or(false),
+                    "{} = {:?}",
+                    key,
+                    value
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_keys_counted() {
+        let toml = &toml(
+            r#"
+name = "Test"
+status = "generate"
+colour = "red"
+    [[function]]
+    name = "reset"
+    versoin = "1.2"
+        [[function.parameter]]
+        name = "mode"
+        nulable = true
+"#,
+        );
+        let unknown_keys = UnknownKeys::default();
+        parse_object(
+            toml,
+            Concurrency::default(),
+            false,
+            false,
+            false,
+            &unknown_keys,
+        );
+        assert_eq!(unknown_keys.count(), 3);
+    }
 }
@@ -1,4 +1,9 @@
-use super::{error::TomlHelper, gobjects::GStatus, ident::Ident, parsable::Parse};
+use super::{
+    error::{TomlHelper, UnknownKeys},
+    gobjects::GStatus,
+    ident::Ident,
+    parsable::Parse,
+};
 use crate::version::Version;
 use log::error;
 use toml::Value;
@@ -14,7 +19,7 @@ pub struct Member {
 }
 
 impl Parse for Member {
-    fn parse(toml: &Value, object_name: &str) -> Option<Member> {
+    fn parse(toml: &Value, object_name: &str, unknown_keys: &UnknownKeys) -> Option<Member> {
         let ident = match Ident::parse(toml, object_name, "member") {
             Some(ident) => ident,
             None => {
@@ -27,8 +32,17 @@ impl Parse for Member {
         };
 
         toml.check_unwanted(
-            &["alias", "version", "name", "pattern", "ignore", "manual"],
+            &[
+                "alias",
+                "version",
+                "deprecated_version",
+                "name",
+                "pattern",
+                "ignore",
+                "manual",
+            ],
             &format!("member {}", object_name),
+            unknown_keys,
         );
 
         let alias = toml
@@ -102,7 +116,7 @@ name = "name1"
 alias = true
 "#,
         );
-        let f = Member::parse(&toml, "a").unwrap();
+        let f = Member::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.ident, Ident::Name("name1".into()));
         assert_eq!(f.alias, true);
     }
@@ -114,7 +128,7 @@ alias = true
 name = "name1"
 "#,
         );
-        let f = Member::parse(&toml, "a").unwrap();
+        let f = Member::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.version, None);
     }
 
@@ -126,7 +140,7 @@ name = "name1"
 version = "3.20"
 "#,
         );
-        let f = Member::parse(&toml, "a").unwrap();
+        let f = Member::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.version, Some(Version::Full(3, 20, 0)));
     }
 }
@@ -5,17 +5,20 @@ pub mod constants;
 pub mod derives;
 pub mod error;
 mod external_libraries;
+pub mod function_macros;
 pub mod functions;
 pub mod gobjects;
 pub mod ident;
 pub mod matchable;
 pub mod members;
+pub mod output_format;
 pub mod parameter_matchable;
 pub mod parsable;
 pub mod properties;
 pub mod property_generate_flags;
 pub mod signals;
 pub mod string_type;
+mod validation;
 pub mod work_mode;
 
 pub use self::{
@@ -23,6 +26,7 @@ pub use self::{
     config::Config,
     external_libraries::ExternalLibrary,
     gobjects::GObject,
+    output_format::OutputFormat,
     property_generate_flags::PropertyGenerateFlags,
     string_type::StringType,
     work_mode::WorkMode,
@@ -1,5 +1,8 @@
 use super::{
-    error::TomlHelper, gobjects::GStatus, ident::Ident, parsable::Parse,
+    error::{TomlHelper, UnknownKeys},
+    gobjects::GStatus,
+    ident::Ident,
+    parsable::Parse,
     property_generate_flags::PropertyGenerateFlags,
 };
 use crate::version::Version;
@@ -17,7 +20,7 @@ pub struct Property {
 }
 
 impl Parse for Property {
-    fn parse(toml: &Value, object_name: &str) -> Option<Property> {
+    fn parse(toml: &Value, object_name: &str, unknown_keys: &UnknownKeys) -> Option<Property> {
         let ident = match Ident::parse(toml, object_name, "property") {
             Some(ident) => ident,
             None => {
@@ -41,6 +44,7 @@ impl Parse for Property {
                 "doc_trait_name",
             ],
             &format!("property {}", object_name),
+            unknown_keys,
         );
 
         let status = {
@@ -128,7 +132,7 @@ name = "prop1"
 ignore = true
 "#,
         );
-        let p = Property::parse(&toml, "a").unwrap();
+        let p = Property::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(p.ident, Ident::Name("prop1".into()));
         assert!(p.status.ignored());
     }
@@ -141,7 +145,7 @@ name = "prop1"
 manual = true
 "#,
         );
-        let p = Property::parse(&toml, "a").unwrap();
+        let p = Property::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(p.ident, Ident::Name("prop1".into()));
         assert!(p.status.manual());
     }
@@ -154,7 +158,7 @@ name = "prop1"
 bypass_auto_rename = true
 "#,
         );
-        let f = Property::parse(&toml, "a").unwrap();
+        let f = Property::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(f.ident, Ident::Name("prop1".into()));
         assert!(f.bypass_auto_rename);
     }
@@ -166,7 +170,7 @@ bypass_auto_rename = true
 name = "prop1"
 "#,
         );
-        let p = Property::parse(&toml, "a").unwrap();
+        let p = Property::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(p.version, None);
         assert!(p.status.need_generate());
     }
@@ -179,13 +183,13 @@ name = "prop1"
 version = "3.20"
 "#,
         );
-        let p = Property::parse(&toml, "a").unwrap();
+        let p = Property::parse(&toml, "a", &Default::default()).unwrap();
         assert_eq!(p.version, Some(Version::Full(3, 20, 0)));
     }
 
     #[test]
     fn properties_parse_empty_for_none() {
-        let props = Properties::parse(None, "a");
+        let props = Properties::parse(None, "a", &Default::default());
         assert!(props.is_empty());
     }
 
@@ -203,7 +207,7 @@ name = "prop2"
 pattern = 'prop\d+'
 "#,
         );
-        let props = Properties::parse(Some(&toml), "a");
+        let props = Properties::parse(Some(&toml), "a", &Default::default());
         assert_eq!(props.len(), 3);
 
         assert_eq!(props.matched("prop1").len(), 2);
@@ -1,5 +1,5 @@
 use super::{
-    error::TomlHelper,
+    error::{TomlHelper, UnknownKeys},
     functions::Return,
     gobjects::GStatus,
     ident::Ident,
@@ -43,7 +43,7 @@ pub struct Parameter {
 }
 
 impl Parse for Parameter {
-    fn parse(toml: &Value, object_name: &str) -> Option<Parameter> {
+    fn parse(toml: &Value, object_name: &str, unknown_keys: &UnknownKeys) -> Option<Parameter> {
         let ident = match Ident::parse(toml, object_name, "signal parameter") {
             Some(ident) => ident,
             None => {
@@ -57,6 +57,7 @@ impl Parse for Parameter {
         toml.check_unwanted(
             &["nullable", "transformation", "new_name", "name", "pattern"],
             &format!("parameter {}", object_name),
+            unknown_keys,
         );
 
         let nullable = toml
@@ -107,6 +108,10 @@ pub struct Signal {
     pub concurrency: library::Concurrency,
     pub doc_hidden: bool,
     pub doc_trait_name: Option<String>,
+    /// Known details of a detailed signal, each getting its own `connect_*` function.
+    pub details: Vec<String>,
+    /// Generates (or prevents generating) the `*_stream` function of the signal.
+    pub stream: Option<bool>,
 }
 
 impl Signal {
@@ -114,6 +119,7 @@ impl Signal {
         toml: &Value,
         object_name: &str,
         concurrency: library::Concurrency,
+        unknown_keys: &UnknownKeys,
     ) -> Option<Signal> {
         let ident = match Ident::parse(toml, object_name, "signal") {
             Some(ident) => ident,
@@ -138,8 +144,11 @@ impl Signal {
                 "pattern",
                 "concurrency",
                 "doc_trait_name",
+                "details",
+                "stream",
             ],
             &format!("signal {}", object_name),
+            unknown_keys,
         );
 
         let status = {
@@ -168,8 +177,8 @@ impl Signal {
             .lookup("version")
             .and_then(Value::as_str)
             .and_then(|s| s.parse().ok());
-        let parameters = Parameters::parse(toml.lookup("parameter"), object_name);
-        let ret = Return::parse(toml.lookup("return"), object_name);
+        let parameters = Parameters::parse(toml.lookup("parameter"), object_name, unknown_keys);
+        let ret = Return::parse(toml.lookup("return"), object_name, unknown_keys);
 
         let concurrency = toml
             .lookup("concurrency")
@@ -185,6 +194,18 @@ impl Signal {
             .lookup("doc_trait_name")
             .and_then(Value::as_str)
             .map(ToOwned::to_owned);
+        let details = toml
+            .lookup("details")
+            .and_then(Value::as_array)
+            .map(|details| {
+                details
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let stream = toml.lookup("stream").and_then(Value::as_bool);
 
         Some(Signal {
             ident,
@@ -196,6 +217,8 @@ impl Signal {
             concurrency,
             doc_hidden,
             doc_trait_name,
+            details,
+            stream,
         })
     }
 }
@@ -233,7 +256,7 @@ mod tests {
 name = "signal1"
 "#,
         );
-        let f = Signal::parse(&toml, "a", Default::default()).unwrap();
+        let f = Signal::parse(&toml, "a", Default::default(), &Default::default()).unwrap();
         assert_eq!(f.ident, Ident::Name("signal1".into()));
         assert!(f.status.need_generate());
     }
@@ -246,7 +269,7 @@ name = "signal1"
 ignore = true
 "#,
         );
-        let f = Signal::parse(&toml, "a", Default::default()).unwrap();
+        let f = Signal::parse(&toml, "a", Default::default(), &Default::default()).unwrap();
         assert!(f.status.ignored());
     }
 
@@ -258,7 +281,31 @@ name = "signal1"
 manual = true
 "#,
         );
-        let f = Signal::parse(&toml, "a", Default::default()).unwrap();
+        let f = Signal::parse(&toml, "a", Default::default(), &Default::default()).unwrap();
         assert!(f.status.manual());
     }
+
+    #[test]
+    fn signal_parse_details() {
+        let toml = toml(
+            r#"
+name = "changed"
+details = ["font-name", "scale"]
+"#,
+        );
+        let f = Signal::parse(&toml, "a", Default::default(), &Default::default()).unwrap();
+        assert_eq!(f.details, vec!["font-name", "scale"]);
+    }
+
+    #[test]
+    fn signal_parse_stream() {
+        let toml = toml(
+            r#"
+name = "changed"
+stream = true
+"#,
+        );
+        let f = Signal::parse(&toml, "a", Default::default(), &Default::default()).unwrap();
+        assert_eq!(f.stream, Some(true));
+    }
 }
@@ -0,0 +1,299 @@
+//! Lookup of the config entries matching nothing in the library, usually left behind
+//! by an upstream rename.
+
+use super::{gobjects::GObjects, ident::Ident, parameter_matchable::Functionlike};
+use crate::{
+    library::{self, Library, Type, MAIN_NAMESPACE},
+    nameutil::mangle_keywords,
+};
+
+/// Describes every object, function, virtual method, signal, property, member, constant and parameter
+/// entry of the config which doesn't match anything in the library.
+///
+/// Must be called after `resolve_type_ids`.
+pub fn unmatched_entries(objects: &GObjects, library: &Library) -> Vec<String> {
+    let ns = library.namespace(MAIN_NAMESPACE);
+    let global_functions_name = format!("{}.*", ns.name);
+    let mut unmatched = Vec::new();
+
+    for (name, object) in objects {
+        let (functions, signals, properties, members): (_, &[_], &[_], &[_]) =
+            match object.type_id.map(|tid| library.type_(tid)) {
+                Some(Type::Class(class)) => (
+                    &class.functions[..],
+                    &class.signals[..],
+                    &class.properties[..],
+                    &[][..],
+                ),
+                Some(Type::Interface(interface)) => (
+                    &interface.functions[..],
+                    &interface.signals[..],
+                    &interface.properties[..],
+                    &[][..],
+                ),
+                Some(Type::Enumeration(enum_)) => {
+                    (&enum_.functions[..], &[][..], &[][..], &enum_.members[..])
+                }
+                Some(Type::Bitfield(bitfield)) => (
+                    &bitfield.functions[..],
+                    &[][..],
+                    &[][..],
+                    &bitfield.members[..],
+                ),
+                Some(typ) => (typ.functions(), &[][..], &[][..], &[][..]),
+                None if name == &global_functions_name => {
+                    (&ns.functions[..], &[][..], &[][..], &[][..])
+                }
+                None => {
+                    unmatched.push(format!("`{}`: object missing from the library", name));
+                    continue;
+                }
+            };
+
+        let mut function_names: Vec<&str> = functions.iter().map(|f| &f.name[..]).collect();
+        // The `get_type` entry configures the `glib:get-type` function of the type.
+        if object
+            .type_id
+            .and_then(|tid| library.type_(tid).glib_get_type())
+            .is_some()
+        {
+            function_names.push("get_type");
+        }
+        for function in &object.functions {
+            if !check_ident(
+                &mut unmatched,
+                name,
+                "function",
+                &function.ident,
+                &function_names,
+            ) {
+                continue;
+            }
+            let matched = functions
+                .iter()
+                .filter(|f| function.ident.is_match(&f.name))
+                .flat_map(|f| f.parameters.iter());
+            check_parameters(
+                &mut unmatched,
+                name,
+                "function",
+                &function.ident,
+                function,
+                matched,
+            );
+        }
+
+        let signal_names: Vec<&str> = signals.iter().map(|s| &s.name[..]).collect();
+        for signal in &object.signals {
+            if !check_ident(&mut unmatched, name, "signal", &signal.ident, &signal_names) {
+                continue;
+            }
+            let matched = signals
+                .iter()
+                .filter(|s| signal.ident.is_match(&s.name))
+                .flat_map(|s| s.parameters.iter());
+            check_parameters(
+                &mut unmatched,
+                name,
+                "signal",
+                &signal.ident,
+                signal,
+                matched,
+            );
+        }
+
+        let property_names: Vec<&str> = properties.iter().map(|p| &p.name[..]).collect();
+        for property in &object.properties {
+            check_ident(
+                &mut unmatched,
+                name,
+                "property",
+                &property.ident,
+                &property_names,
+            );
+        }
+
+        let virtual_methods: &[_] = match object.type_id.map(|tid| library.type_(tid)) {
+            Some(Type::Class(class)) => &class.virtual_methods,
+            Some(Type::Interface(interface)) => &interface.virtual_methods,
+            _ => &[],
+        };
+        let virtual_method_names: Vec<&str> = virtual_methods.iter().map(|f| &f.name[..]).collect();
+        for virtual_method in &object.virtual_methods {
+            check_ident(
+                &mut unmatched,
+                name,
+                "virtual method",
+                &virtual_method.ident,
+                &virtual_method_names,
+            );
+        }
+
+        let member_names: Vec<&str> = members.iter().map(|m| &m.name[..]).collect();
+        for member in &object.members {
+            check_ident(&mut unmatched, name, "member", &member.ident, &member_names);
+        }
+
+        // The constants are matched by their name, and by their full name in sys mode.
+        let constant_names: Vec<String> = if name == &global_functions_name {
+            ns.constants
+                .iter()
+                .flat_map(|c| vec![c.name.clone(), format!("{}.{}", ns.name, c.name)])
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let constant_names: Vec<&str> = constant_names.iter().map(|c| &c[..]).collect();
+        for constant in &object.constants {
+            check_ident(
+                &mut unmatched,
+                name,
+                "constant",
+                &constant.ident,
+                &constant_names,
+            );
+        }
+    }
+
+    unmatched
+}
+
+/// Returns whether `ident` matches one of the `names`, reporting it otherwise.
+fn check_ident(
+    unmatched: &mut Vec<String>,
+    object_name: &str,
+    what: &str,
+    ident: &Ident,
+    names: &[&str],
+) -> bool {
+    let found = names.iter().any(|name| ident.is_match(name));
+    if !found {
+        unmatched.push(format!(
+            "`{}`: {} {} matches nothing",
+            object_name, what, ident
+        ));
+    }
+    found
+}
+
+/// Checks the parameters configured for a function or a signal against the parameters
+/// of the functions or signals it matched.
+fn check_parameters<'a, P, T>(
+    unmatched: &mut Vec<String>,
+    object_name: &str,
+    what: &str,
+    ident: &Ident,
+    configured: &T,
+    parameters: impl Iterator<Item = &'a library::Parameter>,
+) where
+    P: AsRef<Ident>,
+    T: Functionlike<Parameter = P>,
+{
+    if configured.parameters().is_empty() {
+        return;
+    }
+    // Signal parameters are matched by their Rust name.
+    let names: Vec<String> = parameters
+        .flat_map(|par| vec![par.name.clone(), mangle_keywords(&*par.name).into_owned()])
+        .collect();
+    let names: Vec<&str> = names.iter().map(|n| &n[..]).collect();
+    for parameter in configured.parameters() {
+        check_ident(
+            unmatched,
+            object_name,
+            &format!("{} {} parameter", what, ident),
+            parameter.as_ref(),
+            &names,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::gobjects::{parse_toml, resolve_type_ids},
+        library::{Attributes, Concurrency, Enumeration, Member},
+    };
+
+    fn enumeration(library: &mut Library) {
+        let member = Member {
+            name: "first".into(),
+            c_identifier: "TEST_MODE_FIRST".into(),
+            value: "0".into(),
+            nick: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Attributes::default(),
+            version: None,
+            deprecated_version: None,
+        };
+        let enum_ = Enumeration {
+            name: "Mode".into(),
+            c_type: "TestMode".into(),
+            symbol_prefix: None,
+            members: vec![member],
+            functions: Vec::new(),
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            error_domain: None,
+            glib_get_type: Some("test_mode_get_type".into()),
+            attributes: Attributes::default(),
+            source_position: None,
+        };
+        library.add_type(MAIN_NAMESPACE, "Mode", Type::Enumeration(enum_));
+    }
+
+    #[test]
+    fn unmatched_config_entries() {
+        let mut library = Library::new("Test");
+        enumeration(&mut library);
+        let toml: toml::Value = toml::from_str(
+            r#"
+[[object]]
+name = "Test.Mode"
+status = "generate"
+    [[object.member]]
+    name = "first"
+    [[object.member]]
+    pattern = "old_.*"
+    [[object.function]]
+    name = "get_type"
+    [[object.function]]
+    name = "reset"
+
+[[object]]
+name = "Test.*"
+status = "generate"
+    [[object.function]]
+    name = "get_type"
+
+[[object]]
+name = "Test.Renamed"
+status = "generate"
+"#,
+        )
+        .unwrap();
+        let mut objects = parse_toml(
+            toml.get("object").unwrap(),
+            Concurrency::default(),
+            false,
+            false,
+            false,
+            &Default::default(),
+        );
+        resolve_type_ids(&mut objects, &library);
+
+        assert_eq!(
+            unmatched_entries(&objects, &library),
+            vec![
+                "`Test.*`: function get_type matches nothing",
+                "`Test.Mode`: function reset matches nothing",
+                "`Test.Mode`: member Regex ^old_.*$ matches nothing",
+                "`Test.Renamed`: object missing from the library",
+            ]
+        );
+    }
+}
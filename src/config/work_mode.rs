@@ -6,6 +6,7 @@ pub enum WorkMode {
     Sys,             // generate -sys with FFI
     Doc,             // generate documentation file
     DisplayNotBound, // Show not bound types
+    Subclass,        // generate subclassing traits only
 }
 
 impl WorkMode {
@@ -14,7 +15,7 @@ impl WorkMode {
     }
 
     pub fn is_generate_rust_files(self) -> bool {
-        matches!(self, WorkMode::Normal | WorkMode::Sys)
+        matches!(self, WorkMode::Normal | WorkMode::Sys | WorkMode::Subclass)
     }
 }
 
@@ -32,6 +33,7 @@ impl FromStr for WorkMode {
             "sys" => Ok(WorkMode::Sys),
             "doc" => Ok(WorkMode::Doc),
             "not_bound" => Ok(WorkMode::DisplayNotBound),
+            "subclass" => Ok(WorkMode::Subclass),
             _ => Err(format!("Wrong work mode '{}'", s)),
         }
     }
@@ -8,7 +8,7 @@ use crate::{
 
 impl Library {
     pub fn add_glib_priority(&mut self, work_mode: WorkMode) {
-        if work_mode != WorkMode::Normal {
+        if work_mode != WorkMode::Normal && work_mode != WorkMode::Subclass {
             return;
         }
 
@@ -56,3 +56,86 @@ impl Env {
         &self.namespaces[MAIN_NAMESPACE].sys_crate_name
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::config::{OutputFormat, WorkMode};
+    use std::{
+        fs,
+        path::PathBuf,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    pub fn girs_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("gir-files")
+    }
+
+    /// Reads `tests/gir-files/Test-1.0.gir` with the given `Gir.toml` and runs the analysis
+    /// like `main` does.
+    pub fn env(config: &str) -> Env {
+        static CONFIGS: AtomicUsize = AtomicUsize::new(0);
+        let config_path = std::env::temp_dir().join(format!(
+            "gir-test-{}-{}.toml",
+            std::process::id(),
+            CONFIGS.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::write(&config_path, config).unwrap();
+        let girs_dir = girs_dir().to_str().unwrap().to_owned();
+        let target_path = std::env::temp_dir().to_str().unwrap().to_owned();
+        let mut cfg = Config::new(
+            config_path.to_str(),
+            None::<WorkMode>,
+            &[girs_dir],
+            Some("Test"),
+            Some("1.0"),
+            Some(&*target_path),
+            None,
+            false,
+            false,
+            true,
+            false,
+            false,
+            OutputFormat::default(),
+        )
+        .unwrap();
+        fs::remove_file(&config_path).unwrap();
+
+        let mut library = Library::new(&cfg.library_name);
+        library
+            .read_file(&cfg.girs_dirs, &mut vec![cfg.library_full_name()])
+            .unwrap();
+        library.preprocessing(cfg.work_mode);
+        crate::update_version::apply_config(&mut library, &cfg);
+        library.postprocessing(&cfg);
+        cfg.resolve_type_ids(&library);
+        crate::update_version::check_function_real_version(&mut library);
+
+        let namespaces = analysis::namespaces::run(&library);
+        let symbols = analysis::symbols::run(&library, &namespaces);
+        let class_hierarchy = analysis::class_hierarchy::run(&library);
+        let mut env = Env {
+            library,
+            config: cfg,
+            namespaces,
+            symbols: RefCell::new(symbols),
+            class_hierarchy,
+            analysis: Default::default(),
+        };
+        if env.config.work_mode != WorkMode::Sys {
+            analysis::run(&mut env);
+        }
+        env
+    }
+
+    /// Collects what `generate` writes, indented with tabs only: `tabs` indents with spaces.
+    pub fn generated(
+        generate: impl FnOnce(&mut dyn std::io::Write) -> std::io::Result<()>,
+    ) -> String {
+        let mut out = Vec::new();
+        generate(&mut out).unwrap();
+        String::from_utf8(out).unwrap().replace("    ", "\t")
+    }
+}
@@ -1,29 +1,235 @@
-use crate::writer::untabber::Untabber;
+use crate::{config::Config, fmt, writer::untabber::Untabber};
 use std::{
-    fs::{self, File},
-    io::{BufWriter, Result, Write},
-    path::Path,
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    fs,
+    io::{Result, Write},
+    path::{Path, PathBuf},
 };
 
-pub fn save_to_file<P, F>(path: P, make_backup: bool, mut closure: F)
+/// Number of unchanged lines shown around the changes of a diff.
+const DIFF_CONTEXT: usize = 3;
+/// Maximum size of the table comparing the changed lines, bigger changes are shown as a
+/// replacement of all of them.
+const DIFF_MAX_CELLS: usize = 4_000_000;
+
+/// Files saved by a run, to report in check mode the out of date ones and the stale ones
+/// left over in the generated directory.
+#[derive(Debug, Default)]
+pub struct GeneratedFiles {
+    paths: RefCell<BTreeSet<PathBuf>>,
+    outdated: Cell<usize>,
+}
+
+impl GeneratedFiles {
+    fn add(&self, path: &Path, outdated: bool) {
+        self.paths.borrow_mut().insert(path.to_owned());
+        if outdated {
+            self.outdated.set(self.outdated.get() + 1);
+        }
+    }
+
+    /// Number of files which differ from the generated code, counted in check mode.
+    pub fn outdated(&self) -> usize {
+        self.outdated.get()
+    }
+
+    /// Files in `dir` and its subdirectories which weren't generated, except the backups.
+    pub fn stale_files(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut stale = Vec::new();
+        self.collect_stale(dir, &mut stale);
+        stale.sort();
+        stale
+    }
+
+    fn collect_stale(&self, dir: &Path, stale: &mut Vec<PathBuf>) {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.is_dir() {
+                self.collect_stale(&path, stale);
+            } else if path.extension().map_or(true, |e| e != "bak")
+                && !self.paths.borrow().contains(&path)
+            {
+                stale.push(path);
+            }
+        }
+    }
+}
+
+pub fn save_to_file<P, F>(path: P, config: &Config, mut closure: F)
 where
     P: AsRef<Path>,
     F: FnMut(&mut dyn Write) -> Result<()>,
 {
     let path = path.as_ref();
+    let mut content = Vec::new();
+    closure(&mut Untabber::new(Box::new(&mut content)))
+        .unwrap_or_else(|why| panic!("couldn't write to {:?}: {:?}", path, why));
+    // Format the content as it will be on disk, so unchanged files can be detected
+    if !config.disable_format && path.extension().map_or(false, |e| e == "rs") {
+        if let Some(formatted) = fmt::format_source(&content, path) {
+            content = formatted;
+        }
+    }
+    let existing = fs::read(path).ok();
+    let outdated = existing.as_ref() != Some(&content);
+    config.generated_files.add(path, outdated);
+    if !outdated {
+        return;
+    }
+
+    if config.check {
+        print!(
+            "{}",
+            unified_diff(
+                path,
+                &String::from_utf8_lossy(existing.as_deref().unwrap_or_default()),
+                &String::from_utf8_lossy(&content),
+            )
+        );
+        return;
+    }
+
     if let Some(parent) = path.parent() {
         let _ = fs::create_dir_all(parent);
     }
 
-    if make_backup {
+    if config.make_backup {
         let _backuped = create_backup(&path)
             .unwrap_or_else(|why| panic!("couldn't create backup for {:?}: {:?}", path, why));
     }
-    let file =
-        File::create(&path).unwrap_or_else(|why| panic!("couldn't create {:?}: {}", path, why));
-    let writer = BufWriter::new(file);
-    let mut untabber = Untabber::new(Box::new(writer));
-    closure(&mut untabber).unwrap_or_else(|why| panic!("couldn't write to {:?}: {:?}", path, why));
+    fs::write(&path, content).unwrap_or_else(|why| panic!("couldn't write {:?}: {}", path, why));
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// The unified diff between the `old` content of the file at `path` and the `new` one.
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let lines = diff_lines(&old, &new);
+
+    let mut diff = format!("--- {0}\n+++ {0}\n", path.display());
+    // Line numbers before each line of the diff
+    let mut old_line = 0;
+    let mut new_line = 0;
+    let mut i = 0;
+    while i < lines.len() {
+        if let DiffLine::Same(_) = lines[i] {
+            old_line += 1;
+            new_line += 1;
+            i += 1;
+            continue;
+        }
+        // Merge the changes whose contexts touch into one hunk
+        let start = i.saturating_sub(DIFF_CONTEXT);
+        let mut changes_end = i + 1;
+        let mut j = i + 1;
+        while j < lines.len() && j <= changes_end + 2 * DIFF_CONTEXT {
+            if !matches!(lines[j], DiffLine::Same(_)) {
+                changes_end = j + 1;
+            }
+            j += 1;
+        }
+        let end = (changes_end + DIFF_CONTEXT).min(lines.len());
+
+        let hunk = &lines[start..end];
+        let context_before = i - start;
+        let old_count = hunk
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Added(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Removed(_)))
+            .count();
+        let old_start = old_line - context_before;
+        let new_start = new_line - context_before;
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+        for line in hunk {
+            match line {
+                DiffLine::Same(s) => diff.push_str(&format!(" {}\n", s)),
+                DiffLine::Removed(s) => diff.push_str(&format!("-{}\n", s)),
+                DiffLine::Added(s) => diff.push_str(&format!("+{}\n", s)),
+            }
+        }
+        old_line = old_start + old_count;
+        new_line = new_start + new_count;
+        i = end;
+    }
+    diff
+}
+
+/// The `start,count` of a hunk header, `start` being the line before the hunk when empty.
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+/// Shortest edit turning the `old` lines into the `new` ones, from their longest common
+/// subsequence.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut lines: Vec<_> = old[..prefix].iter().map(|&s| DiffLine::Same(s)).collect();
+    if old_middle.len() * new_middle.len() > DIFF_MAX_CELLS {
+        lines.extend(old_middle.iter().map(|&s| DiffLine::Removed(s)));
+        lines.extend(new_middle.iter().map(|&s| DiffLine::Added(s)));
+    } else {
+        // Length of the longest common subsequence of `old_middle[i..]` and `new_middle[j..]`
+        let width = new_middle.len() + 1;
+        let mut lcs = vec![0u32; (old_middle.len() + 1) * width];
+        for i in (0..old_middle.len()).rev() {
+            for j in (0..new_middle.len()).rev() {
+                lcs[i * width + j] = if old_middle[i] == new_middle[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < old_middle.len() || j < new_middle.len() {
+            if i < old_middle.len() && j < new_middle.len() && old_middle[i] == new_middle[j] {
+                lines.push(DiffLine::Same(old_middle[i]));
+                i += 1;
+                j += 1;
+            } else if j == new_middle.len()
+                || (i < old_middle.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+            {
+                lines.push(DiffLine::Removed(old_middle[i]));
+                i += 1;
+            } else {
+                lines.push(DiffLine::Added(new_middle[j]));
+                j += 1;
+            }
+        }
+    }
+    lines.extend(old[old.len() - suffix..].iter().map(|&s| DiffLine::Same(s)));
+    lines
 }
 
 /// Create .bak file
@@ -34,3 +240,95 @@ pub fn create_backup<P: AsRef<Path>>(path: P) -> Result<bool> {
     let new_path = path.as_ref().with_extension("bak");
     fs::rename(path, new_path).map(|_| true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_diff_hunks() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n17\n18\n19\n20\n";
+        let new = "1\n2\nthree\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n17\n18\n20\n21\n";
+        assert_eq!(
+            unified_diff(Path::new("src/auto/a.rs"), old, new),
+            "--- src/auto/a.rs\n\
+             +++ src/auto/a.rs\n\
+             @@ -1,6 +1,6 @@\n 1\n 2\n-3\n+three\n 4\n 5\n 6\n\
+             @@ -16,5 +16,5 @@\n 16\n 17\n 18\n-19\n 20\n+21\n"
+        );
+        // The contexts of close changes are merged
+        assert_eq!(
+            unified_diff(
+                Path::new("a.rs"),
+                "a\nb\nc\nd\ne\nf\ng\nh\n",
+                "a\nB\nc\nd\ne\nf\ng\nH\n"
+            ),
+            "--- a.rs\n+++ a.rs\n\
+             @@ -1,8 +1,8 @@\n a\n-b\n+B\n c\n d\n e\n f\n g\n-h\n+H\n"
+        );
+        assert_eq!(
+            unified_diff(Path::new("a.rs"), "", "a\nb\n"),
+            "--- a.rs\n+++ a.rs\n@@ -0,0 +1,2 @@\n+a\n+b\n"
+        );
+        assert_eq!(
+            unified_diff(Path::new("a.rs"), "a\n", "a\n"),
+            "--- a.rs\n+++ a.rs\n"
+        );
+    }
+
+    #[test]
+    fn stale_files() {
+        let dir = std::env::temp_dir().join(format!("gir-stale-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("subclass")).unwrap();
+        for name in &[
+            "mod.rs",
+            "widget.rs",
+            "removed.rs",
+            "label.bak",
+            "subclass/old.rs",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        let files = GeneratedFiles::default();
+        files.add(&dir.join("mod.rs"), false);
+        files.add(&dir.join("widget.rs"), true);
+        files.add(&dir.join("new.rs"), true);
+
+        assert_eq!(files.outdated(), 2);
+        assert_eq!(
+            files.stale_files(&dir),
+            vec![dir.join("removed.rs"), dir.join("subclass").join("old.rs")]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unchanged_file_not_touched() {
+        let mut config = crate::env::tests::env("[options]\nwork_mode = \"normal\"\n").config;
+        config.disable_format = false;
+        let path = std::env::temp_dir().join(format!("gir-unchanged-{}.rs", std::process::id()));
+        let content = "fn main() {}\n";
+        fs::write(&path, content).unwrap();
+        let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        let set_old_mtime = || {
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(old)
+                .unwrap()
+        };
+        let mtime = || fs::metadata(&path).unwrap().modified().unwrap();
+        set_old_mtime();
+
+        save_to_file(&path, &config, |w| w.write_all(content.as_bytes()));
+        assert_eq!(mtime(), old);
+        assert_eq!(config.generated_files.outdated(), 0);
+
+        save_to_file(&path, &config, |w| w.write_all(b"fn other() {}\n"));
+        assert_ne!(mtime(), old);
+        assert_eq!(config.generated_files.outdated(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,6 +1,8 @@
 use log::warn;
+use std::fs;
+use std::io::Write;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 /// Check if `cargo fmt` available
 pub fn check_fmt() -> bool {
@@ -28,3 +30,85 @@ pub fn format(path: &Path) {
         Err(_) => { /*We checked `cargo` fmt presence in check_fmt, so can ignore errors*/ }
     }
 }
+
+/// Run `rustfmt` on the content of the file at `path`, which doesn't need to exist yet,
+/// with the edition of its crate like the final `cargo fmt`.
+///
+/// Returns `None` if the content can't be formatted.
+pub fn format_source(source: &[u8], path: &Path) -> Option<Vec<u8>> {
+    // `rustfmt` looks for its configuration from the current directory
+    let dir = path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| Path::new("."));
+    let mut child = Command::new("rustfmt")
+        .arg("--edition")
+        .arg(crate_edition(dir).unwrap_or_else(|| "2018".into()))
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(source).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if output.status.success() {
+        Some(output.stdout)
+    } else {
+        None
+    }
+}
+
+/// The edition of the crate containing `dir`, from the nearest `Cargo.toml`.
+fn crate_edition(dir: &Path) -> Option<String> {
+    let manifest = dir
+        .ancestors()
+        .map(|dir| dir.join("Cargo.toml"))
+        .find(|manifest| manifest.is_file())?;
+    let manifest: toml::Value = toml::from_str(&fs::read_to_string(manifest).ok()?).ok()?;
+    let package = manifest.get("package")?;
+    Some(
+        package
+            .get("edition")
+            .and_then(toml::Value::as_str)
+            // Cargo's default edition
+            .unwrap_or("2015")
+            .to_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edition_of_the_crate() {
+        let dir = std::env::temp_dir().join(format!("gir-edition-{}", std::process::id()));
+        let crate_dir = dir.join("test");
+        let src = crate_dir.join("src").join("auto");
+        fs::create_dir_all(&src).unwrap();
+        // Keeps the lookup from reaching the manifests above the temporary directory
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"test\"]\n",
+        )
+        .unwrap();
+        let in_workspace = crate_edition(&src);
+
+        fs::write(crate_dir.join("Cargo.toml"), "[package]\nname = \"test\"\n").unwrap();
+        let default = crate_edition(&src);
+
+        fs::write(
+            crate_dir.join("Cargo.toml"),
+            "[package]\nname = \"test\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        let configured = crate_edition(&src);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(in_workspace, None);
+        assert_eq!(default, Some("2015".into()));
+        assert_eq!(configured, Some("2021".into()));
+    }
+}
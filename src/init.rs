@@ -0,0 +1,412 @@
+//! `gir init`: writes the starting `Gir.toml` files and crate skeletons of the sys and
+//! the normal crates binding a library.
+
+use crate::{
+    library::*,
+    nameutil::{crate_name, exported_crate_name},
+    version::Version,
+};
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// What the generated files need to know about the library.
+struct Info {
+    namespace: String,
+    version: String,
+    crate_name: String,
+    /// Namespaces of the `<include>` chain, in loading order.
+    external_libraries: Vec<String>,
+    /// Types to generate in the normal crate, and `Ns.*` for the global functions.
+    generate: Vec<String>,
+    /// Parent classes and interfaces from the external libraries.
+    manual: Vec<String>,
+    versions: Vec<Version>,
+    has_flags: bool,
+    has_traits: bool,
+}
+
+impl Info {
+    fn new(library: &Library, version: &str) -> Info {
+        let ns = library.namespace(MAIN_NAMESPACE);
+        let min_version = version.parse::<Version>().ok();
+        let mut generate = BTreeSet::new();
+        let mut manual = BTreeSet::new();
+        let mut has_flags = false;
+        let mut has_traits = false;
+        let mut add_manual = |tid: TypeId| {
+            if tid.ns_id != MAIN_NAMESPACE {
+                manual.insert(tid.full_name(library));
+            }
+        };
+
+        for (id, typ) in ns.types.iter().enumerate() {
+            let typ = typ.as_ref().unwrap(); //Always contains something
+            let name = typ.get_name();
+            if name.starts_with('#') || !typ.is_standalone() {
+                continue;
+            }
+            match typ {
+                Type::Class(class) => {
+                    let tid = TypeId {
+                        ns_id: MAIN_NAMESPACE,
+                        id: id as u32,
+                    };
+                    // `final_type` is only set by the postprocessing, which needs a config.
+                    has_traits |= !library.is_final_class(tid, class);
+                    class.parent.into_iter().for_each(&mut add_manual);
+                    class.implements.iter().cloned().for_each(&mut add_manual);
+                }
+                Type::Interface(interface) => {
+                    has_traits = true;
+                    interface
+                        .prerequisites
+                        .iter()
+                        .cloned()
+                        .for_each(&mut add_manual);
+                }
+                // Only the boxed records can be generated without more configuration.
+                Type::Record(Record {
+                    glib_get_type: Some(_),
+                    gtype_struct_for: None,
+                    ..
+                }) => {}
+                Type::Enumeration(_) => {}
+                Type::Bitfield(_) => has_flags = true,
+                _ => continue,
+            }
+            generate.insert(format!("{}.{}", ns.name, name));
+        }
+        if !ns.functions.is_empty() || !ns.constants.is_empty() {
+            generate.insert(format!("{}.*", ns.name));
+        }
+
+        Info {
+            namespace: ns.name.clone(),
+            version: version.into(),
+            crate_name: crate_name(&ns.name),
+            external_libraries: library
+                .namespaces
+                .iter()
+                .skip(MAIN_NAMESPACE as usize + 1)
+                .map(|ns| ns.name.clone())
+                .collect(),
+            generate: generate.into_iter().collect(),
+            manual: manual.into_iter().collect(),
+            versions: ns
+                .versions
+                .iter()
+                .filter(|&&v| min_version.map_or(true, |min| v > min))
+                .cloned()
+                .collect(),
+            has_flags,
+            has_traits,
+        }
+    }
+}
+
+/// Reads the `library`-`version` GIR file and writes the `<crate>-sys` and `<crate>`
+/// directories into `target_path`, without overwriting any existing file.
+pub fn run(
+    library_name: &str,
+    version: &str,
+    girs_dirs: &[String],
+    target_path: &Path,
+) -> Result<(), String> {
+    if girs_dirs.is_empty() {
+        return Err("`gir init` needs the GIR directories, given with -d".into());
+    }
+    let mut library = Library::new(library_name);
+    library.read_file(
+        girs_dirs,
+        &mut vec![format!("{}-{}", library_name, version)],
+    )?;
+    let info = Info::new(&library, version);
+
+    let package_name = exported_crate_name(&info.crate_name);
+    let sys_path = target_path.join(format!("{}-sys", package_name));
+    let path = target_path.join(&package_name);
+    let sys_girs_dirs = relative_girs_dirs(girs_dirs, &sys_path)?;
+    let girs_dirs = relative_girs_dirs(girs_dirs, &path)?;
+
+    let files = vec![
+        (
+            sys_path.join("Gir.toml"),
+            sys_gir_toml(&info, &sys_girs_dirs),
+        ),
+        (path.join("Gir.toml"), gir_toml(&info, &girs_dirs)),
+        (path.join("Cargo.toml"), cargo_toml(&info)),
+        (path.join("src").join("lib.rs"), lib_rs(&info)),
+    ];
+    if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+        return Err(format!("`{}` already exists", path.display()));
+    }
+    for (path, content) in &files {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create `{}`: {}", parent.display(), e))?;
+        }
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to write `{}`: {}", path.display(), e))?;
+        println!("Created {}", path.display());
+    }
+    // The sys mode generates everything else but expects the `src` directory to exist.
+    let sys_src_path = sys_path.join("src");
+    fs::create_dir_all(&sys_src_path)
+        .map_err(|e| format!("Failed to create `{}`: {}", sys_src_path.display(), e))?;
+    Ok(())
+}
+
+/// The GIR directories as written in a `Gir.toml` in `crate_path`.
+fn relative_girs_dirs(girs_dirs: &[String], crate_path: &Path) -> Result<Vec<String>, String> {
+    let current_dir =
+        std::env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
+    let crate_path = current_dir.join(crate_path);
+    Ok(girs_dirs
+        .iter()
+        .map(|dir| {
+            let dir = current_dir.join(dir);
+            let dir = dir.canonicalize().unwrap_or(dir);
+            relative_path(&crate_path, &dir)
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect())
+}
+
+/// The path leading from the `from` directory to `to`, both being absolute.
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from = normalize(from);
+    let to = normalize(to);
+    let common = from
+        .iter()
+        .zip(to.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut path: PathBuf = from.iter().skip(common).map(|_| "..").collect();
+    path.extend(to.iter().skip(common));
+    if path.as_os_str().is_empty() {
+        path.push(".");
+    }
+    path
+}
+
+/// Removes the `.` and `..` components without touching the filesystem.
+fn normalize(path: &Path) -> Vec<Component<'_>> {
+    let mut components = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(components.last(), Some(Component::Normal(_))) => {
+                components.pop();
+            }
+            component => components.push(component),
+        }
+    }
+    components
+}
+
+fn string_array(values: &[String]) -> String {
+    let mut s = String::from("[\n");
+    for value in values {
+        s.push_str(&format!("    \"{}\",\n", value));
+    }
+    s.push(']');
+    s
+}
+
+fn options(info: &Info, girs_dirs: &[String], work_mode: &str) -> String {
+    format!(
+        "[options]
+girs_directories = {girs_dirs}
+library = \"{library}\"
+version = \"{version}\"
+min_cfg_version = \"{version}\"
+target_path = \".\"
+work_mode = \"{work_mode}\"
+external_libraries = {external_libraries}
+",
+        girs_dirs = string_array(girs_dirs),
+        library = info.namespace,
+        version = info.version,
+        work_mode = work_mode,
+        external_libraries = string_array(&info.external_libraries),
+    )
+}
+
+fn sys_gir_toml(info: &Info, girs_dirs: &[String]) -> String {
+    options(info, girs_dirs, "sys")
+}
+
+fn gir_toml(info: &Info, girs_dirs: &[String]) -> String {
+    format!(
+        "{options}generate_safety_asserts = true
+deprecate_by_min_version = true
+single_version_file = true
+
+generate = {generate}
+
+manual = {manual}
+",
+        options = options(info, girs_dirs, "normal"),
+        generate = string_array(&info.generate),
+        manual = string_array(&info.manual),
+    )
+}
+
+/// The crate dependency for an external namespace, as it is named in the generated code.
+fn dependency(namespace: &str) -> String {
+    match &crate_name(namespace)[..] {
+        "gobject" | "glib" => "glib = \"*\"".into(),
+        "cairo" => "cairo = { package = \"cairo-rs\", version = \"*\" }".into(),
+        name => format!("{} = \"*\"", exported_crate_name(name)),
+    }
+}
+
+fn features(info: &Info) -> String {
+    let mut s = String::new();
+    let mut prev: Option<String> = None;
+    for version in &info.versions {
+        let feature = version.to_feature();
+        match prev {
+            Some(prev) => s.push_str(&format!("{0} = [\"ffi/{0}\", \"{1}\"]\n", feature, prev)),
+            None => s.push_str(&format!("{0} = [\"ffi/{0}\"]\n", feature)),
+        }
+        prev = Some(feature);
+    }
+    s.push_str("dox = [\"ffi/dox\"]\n");
+    s
+}
+
+fn cargo_toml(info: &Info) -> String {
+    let package_name = exported_crate_name(&info.crate_name);
+    let mut dependencies = vec!["libc = \"0.2\"".to_owned()];
+    if info.has_flags {
+        dependencies.push("bitflags = \"1.0\"".into());
+    }
+    dependencies.push(format!(
+        "ffi = {{ package = \"{0}-sys\", path = \"../{0}-sys\" }}",
+        package_name
+    ));
+    for namespace in &info.external_libraries {
+        let dependency = dependency(namespace);
+        if !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
+        }
+    }
+    format!(
+        "[package]
+name = \"{package_name}\"
+version = \"0.0.1\"
+edition = \"2018\"
+
+[package.metadata.docs.rs]
+features = [\"dox\"]
+
+[lib]
+name = \"{crate_name}\"
+
+[features]
+{features}
+[dependencies]
+{dependencies}
+",
+        package_name = package_name,
+        crate_name = info.crate_name,
+        features = features(info),
+        dependencies = dependencies.join("\n"),
+    )
+}
+
+fn lib_rs(info: &Info) -> String {
+    let mut s = String::from(
+        "#![cfg_attr(feature = \"dox\", feature(doc_cfg))]
+
+pub use ffi;
+
+mod auto;
+pub use auto::*;
+",
+    );
+    if info.has_traits {
+        s.push_str(
+            "
+pub mod prelude {
+    pub use crate::auto::traits::*;
+}
+",
+        );
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_path_between_directories() {
+        assert_eq!(
+            relative_path(Path::new("/src/gtk-sys"), Path::new("/src/gir-files")),
+            PathBuf::from("../gir-files")
+        );
+        assert_eq!(
+            relative_path(Path::new("/src/./a/../gtk"), Path::new("/gir-files")),
+            PathBuf::from("../../gir-files")
+        );
+        assert_eq!(
+            relative_path(Path::new("/src/gtk"), Path::new("/src/gtk/gir-files")),
+            PathBuf::from("gir-files")
+        );
+        assert_eq!(
+            relative_path(Path::new("/src/gtk"), Path::new("/src/gtk")),
+            PathBuf::from(".")
+        );
+    }
+
+    #[test]
+    fn features_depend_on_previous_version() {
+        let info = Info {
+            namespace: "Test".into(),
+            version: "1.0".into(),
+            crate_name: "test".into(),
+            external_libraries: vec!["GLib".into(), "GObject".into()],
+            generate: Vec::new(),
+            manual: Vec::new(),
+            versions: vec![Version::Full(1, 2, 0), Version::Full(1, 4, 0)],
+            has_flags: false,
+            has_traits: false,
+        };
+        assert_eq!(
+            features(&info),
+            "v1_2 = [\"ffi/v1_2\"]\nv1_4 = [\"ffi/v1_4\", \"v1_2\"]\ndox = [\"ffi/dox\"]\n"
+        );
+        assert!(cargo_toml(&info)
+            .ends_with("ffi = { package = \"test-sys\", path = \"../test-sys\" }\nglib = \"*\"\n"));
+    }
+
+    #[test]
+    fn final_classes_without_postprocessing() {
+        let mut library = Library::new("Test");
+        library
+            .read_file(
+                &[crate::env::tests::girs_dir()],
+                &mut vec!["Test-1.0".into()],
+            )
+            .unwrap();
+        let class = |name| {
+            let tid = library.find_type(MAIN_NAMESPACE, name).unwrap();
+            match library.type_(tid) {
+                Type::Class(class) => library.is_final_class(tid, class),
+                _ => unreachable!(),
+            }
+        };
+        // Subclassed by `Button` and `Label`
+        assert!(!class("Widget"));
+        // No class struct and no subclass
+        assert!(class("Label"));
+        assert!(Info::new(&library, "1.0").has_traits);
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+/// Minimal JSON value, used for the machine-readable reports.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object() -> Json {
+        Json::Object(Vec::new())
+    }
+
+    /// Appends a field, when `self` is an object.
+    pub fn with<S: Into<String>, V: Into<Json>>(mut self, key: S, value: V) -> Json {
+        if let Json::Object(ref mut fields) = self {
+            fields.push((key.into(), value.into()));
+        }
+        self
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        match *self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Int(i) => write!(f, "{}", i),
+            Json::Float(v) if v.is_finite() => write!(f, "{}", v),
+            Json::Float(_) => f.write_str("null"),
+            Json::String(ref s) => write_string(f, s),
+            Json::Array(ref items) if items.is_empty() => f.write_str("[]"),
+            Json::Array(ref items) => {
+                f.write_str("[\n")?;
+                for (i, item) in items.iter().enumerate() {
+                    write_indent(f, indent + 1)?;
+                    item.write(f, indent + 1)?;
+                    f.write_str(if i + 1 < items.len() { ",\n" } else { "\n" })?;
+                }
+                write_indent(f, indent)?;
+                f.write_str("]")
+            }
+            Json::Object(ref fields) if fields.is_empty() => f.write_str("{}"),
+            Json::Object(ref fields) => {
+                f.write_str("{\n")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    write_indent(f, indent + 1)?;
+                    write_string(f, key)?;
+                    f.write_str(": ")?;
+                    value.write(f, indent + 1)?;
+                    f.write_str(if i + 1 < fields.len() { ",\n" } else { "\n" })?;
+                }
+                write_indent(f, indent)?;
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+fn write_indent(f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+    for _ in 0..indent {
+        f.write_str("  ")?;
+    }
+    Ok(())
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+impl From<i64> for Json {
+    fn from(i: i64) -> Json {
+        Json::Int(i)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(i: usize) -> Json {
+        Json::Int(i as i64)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(v: f64) -> Json {
+        Json::Float(v)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.into())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(v: Option<T>) -> Json {
+        v.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(v: Vec<T>) -> Json {
+        Json::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_scalars() {
+        assert_eq!(Json::Null.to_string(), "null");
+        assert_eq!(Json::from(true).to_string(), "true");
+        assert_eq!(Json::from(42usize).to_string(), "42");
+        assert_eq!(Json::from(0.5).to_string(), "0.5");
+        assert_eq!(Json::from(None::<&str>).to_string(), "null");
+    }
+
+    #[test]
+    fn display_escapes_strings() {
+        assert_eq!(
+            Json::from("a \"b\"\\\n\u{1}").to_string(),
+            r#""a \"b\"\\\n\u0001""#
+        );
+    }
+
+    #[test]
+    fn display_nested() {
+        let json = Json::object()
+            .with("name", "Gtk.Widget")
+            .with("items", vec![1usize, 2])
+            .with("empty", Vec::<Json>::new());
+        assert_eq!(
+            json.to_string(),
+            "{\n  \"name\": \"Gtk.Widget\",\n  \"items\": [\n    1,\n    2\n  ],\n  \"empty\": []\n}"
+        );
+    }
+}
@@ -18,6 +18,7 @@ macro_rules! warn_main {
 mod gir_version;
 
 pub mod analysis;
+pub mod api_diff;
 mod case;
 mod chunk;
 mod codegen;
@@ -28,12 +29,17 @@ mod env;
 mod file_saver;
 pub mod fmt;
 mod git;
+pub mod init;
+mod json;
 pub mod library;
+pub mod library_dump;
 mod library_postprocessing;
 mod library_preprocessing;
 mod nameutil;
 mod parser;
+pub mod report;
 mod traits;
+mod typelib;
 pub mod update_version;
 mod version;
 mod visitors;
@@ -46,7 +52,7 @@ pub use crate::{
         run as analysis_run, symbols::run as symbols_run,
     },
     codegen::generate as codegen_generate,
-    config::{Config, WorkMode},
+    config::{Config, OutputFormat, WorkMode},
     env::Env,
     library::Library,
 };
@@ -160,6 +160,7 @@ pub enum FunctionKind {
     Function,
     Method,
     Global,
+    VirtualMethod,
 }
 
 impl FromStr for FunctionKind {
@@ -172,6 +173,7 @@ impl FromStr for FunctionKind {
             "method" => Ok(Method),
             "callback" => Ok(Function),
             "global" => Ok(Global),
+            "virtual-method" => Ok(VirtualMethod),
             _ => Err(format!("Unknown function kind '{}'", name)),
         }
     }
@@ -347,6 +349,49 @@ impl TypeId {
     }
 }
 
+/// Key/value pairs from the `<attribute>` elements of a GIR item.
+///
+/// They are queried by the analysis only; the config entries match items by name, not by
+/// attributes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Attributes(BTreeMap<String, String>);
+
+impl Attributes {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, name: String, value: String) {
+        self.0.insert(name, value);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.0.remove(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// Location of a declaration in the C sources of the library.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourcePosition {
+    pub filename: String,
+    pub line: u32,
+}
+
+impl fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.filename, self.line)
+    }
+}
+
+/// Formats `position` as a suffix for diagnostics.
+pub fn source_hint(position: Option<&SourcePosition>) -> String {
+    position.map(|p| format!(" at {}", p)).unwrap_or_default()
+}
+
 #[derive(Debug)]
 pub struct Alias {
     pub name: String,
@@ -355,6 +400,8 @@ pub struct Alias {
     pub target_c_type: String,
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
 }
 
 #[derive(Debug)]
@@ -368,6 +415,8 @@ pub struct Constant {
     pub deprecated_version: Option<Version>,
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
 }
 
 #[derive(Debug)]
@@ -375,7 +424,12 @@ pub struct Member {
     pub name: String,
     pub c_identifier: String,
     pub value: String,
+    pub nick: Option<String>,
     pub doc: Option<String>,
+    pub doc_deprecated: Option<String>,
+    pub attributes: Attributes,
+    pub version: Option<Version>,
+    pub deprecated_version: Option<Version>,
 }
 
 #[derive(Debug)]
@@ -397,6 +451,8 @@ pub struct Enumeration {
     pub doc_deprecated: Option<String>,
     pub error_domain: Option<ErrorDomain>,
     pub glib_get_type: Option<String>,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
 }
 
 #[derive(Debug)]
@@ -411,6 +467,8 @@ pub struct Bitfield {
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
     pub glib_get_type: Option<String>,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
 }
 
 #[derive(Default, Debug)]
@@ -429,6 +487,8 @@ pub struct Record {
     /// A 'disguised' record is one where the c:type is a typedef that
     /// doesn't look like a pointer, but is internally: typedef struct _X *X;
     pub disguised: bool,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
 }
 
 #[derive(Default, Debug)]
@@ -440,6 +500,7 @@ pub struct Field {
     pub bits: Option<u8>,
     pub array_length: Option<u32>,
     pub doc: Option<String>,
+    pub attributes: Attributes,
 }
 
 #[derive(Default, Debug)]
@@ -451,6 +512,8 @@ pub struct Union {
     pub fields: Vec<Field>,
     pub functions: Vec<Function>,
     pub doc: Option<String>,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
 }
 
 #[derive(Debug)]
@@ -467,6 +530,46 @@ pub struct Property {
     pub deprecated_version: Option<Version>,
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
+    pub attributes: Attributes,
+}
+
+impl Property {
+    /// Returns the method declared in `functions` as the getter of this property,
+    /// either by the `org.gtk.Property.get` attribute or by `org.gtk.Method.get_property`.
+    pub fn getter<'a>(&self, functions: &'a [Function]) -> Option<&'a Function> {
+        self.accessor(
+            functions,
+            "org.gtk.Property.get",
+            "org.gtk.Method.get_property",
+        )
+    }
+
+    /// Returns the method declared in `functions` as the setter of this property,
+    /// either by the `org.gtk.Property.set` attribute or by `org.gtk.Method.set_property`.
+    pub fn setter<'a>(&self, functions: &'a [Function]) -> Option<&'a Function> {
+        self.accessor(
+            functions,
+            "org.gtk.Property.set",
+            "org.gtk.Method.set_property",
+        )
+    }
+
+    fn accessor<'a>(
+        &self,
+        functions: &'a [Function],
+        property_attr: &str,
+        method_attr: &str,
+    ) -> Option<&'a Function> {
+        if let Some(c_identifier) = self.attributes.get(property_attr) {
+            functions
+                .iter()
+                .find(|f| f.c_identifier.as_deref() == Some(c_identifier))
+        } else {
+            functions
+                .iter()
+                .find(|f| f.attributes.get(method_attr) == Some(self.name.as_str()))
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -488,6 +591,7 @@ pub struct Parameter {
     pub closure: Option<usize>,
     /// Index of the destroy notification parameter associated with the callback.
     pub destroy: Option<usize>,
+    pub attributes: Attributes,
 }
 
 #[derive(Debug)]
@@ -502,6 +606,66 @@ pub struct Function {
     pub deprecated_version: Option<Version>,
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
+    /// Name of the function finishing this async function (`glib:finish-func`).
+    pub finish_func: Option<String>,
+    /// Name of the async function finished by this function (`glib:async-func`).
+    pub async_func: Option<String>,
+}
+
+/// Long-form documentation of a library that is not attached to any symbol.
+#[derive(Debug)]
+pub struct DocSection {
+    pub name: String,
+    pub doc: Option<String>,
+}
+
+/// A C preprocessor macro with function-like arguments.
+///
+/// The GIR only records the names of the arguments, not their types nor the expansion.
+#[derive(Debug)]
+pub struct FunctionMacro {
+    pub name: String,
+    pub c_identifier: String,
+    pub parameters: Vec<String>,
+    pub version: Option<Version>,
+    pub deprecated_version: Option<Version>,
+    pub doc: Option<String>,
+    pub doc_deprecated: Option<String>,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
+}
+
+/// Emission stage in which the class handler of a signal runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignalWhen {
+    First,
+    Last,
+    Cleanup,
+}
+
+impl FromStr for SignalWhen {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<SignalWhen, String> {
+        match name {
+            "first" => Ok(SignalWhen::First),
+            "last" => Ok(SignalWhen::Last),
+            "cleanup" => Ok(SignalWhen::Cleanup),
+            _ => Err(format!("Unknown signal emission stage: {}", name)),
+        }
+    }
+}
+
+impl fmt::Display for SignalWhen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SignalWhen::First => "first",
+            SignalWhen::Last => "last",
+            SignalWhen::Cleanup => "cleanup",
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -511,10 +675,16 @@ pub struct Signal {
     pub ret: Parameter,
     pub is_action: bool,
     pub is_detailed: bool,
+    pub when: Option<SignalWhen>,
+    /// Recursive emission restarts the signal instead of recursing.
+    pub no_recurse: bool,
+    /// Emission hooks are not supported.
+    pub no_hooks: bool,
     pub version: Option<Version>,
     pub deprecated_version: Option<Version>,
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
+    pub attributes: Attributes,
 }
 
 #[derive(Default, Debug)]
@@ -526,6 +696,7 @@ pub struct Interface {
     pub c_class_type: Option<String>,
     pub glib_get_type: String,
     pub functions: Vec<Function>,
+    pub virtual_methods: Vec<Function>,
     pub signals: Vec<Signal>,
     pub properties: Vec<Property>,
     pub prerequisites: Vec<TypeId>,
@@ -533,6 +704,8 @@ pub struct Interface {
     pub deprecated_version: Option<Version>,
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
 }
 
 #[derive(Default, Debug)]
@@ -545,6 +718,7 @@ pub struct Class {
     pub glib_get_type: String,
     pub fields: Vec<Field>,
     pub functions: Vec<Function>,
+    pub virtual_methods: Vec<Function>,
     pub signals: Vec<Signal>,
     pub properties: Vec<Property>,
     pub parent: Option<TypeId>,
@@ -555,6 +729,8 @@ pub struct Class {
     pub doc: Option<String>,
     pub doc_deprecated: Option<String>,
     pub is_abstract: bool,
+    pub attributes: Attributes,
+    pub source_position: Option<SourcePosition>,
 }
 
 #[derive(Debug)]
@@ -674,6 +850,19 @@ impl Type {
         }
     }
 
+    pub fn get_version(&self) -> Option<Version> {
+        use self::Type::*;
+        match *self {
+            Enumeration(ref enum_) => enum_.version,
+            Bitfield(ref bit_field) => bit_field.version,
+            Record(ref rec) => rec.version,
+            Function(ref func) => func.version,
+            Interface(ref interface) => interface.version,
+            Class(ref class) => class.version,
+            _ => None,
+        }
+    }
+
     pub fn get_deprecated_version(&self) -> Option<Version> {
         use self::Type::*;
         match *self {
@@ -697,6 +886,21 @@ impl Type {
         }
     }
 
+    pub fn source_position(&self) -> Option<&SourcePosition> {
+        use self::Type::*;
+        match *self {
+            Alias(ref alias) => alias.source_position.as_ref(),
+            Enumeration(ref enum_) => enum_.source_position.as_ref(),
+            Bitfield(ref bit_field) => bit_field.source_position.as_ref(),
+            Record(ref rec) => rec.source_position.as_ref(),
+            Union(ref union) => union.source_position.as_ref(),
+            Function(ref func) => func.source_position.as_ref(),
+            Interface(ref interface) => interface.source_position.as_ref(),
+            Class(ref class) => class.source_position.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn get_glib_name(&self) -> Option<&str> {
         use self::Type::*;
         match *self {
@@ -712,6 +916,20 @@ impl Type {
         }
     }
 
+    /// The `glib:get-type` function registering the type, not listed in its functions.
+    pub fn glib_get_type(&self) -> Option<&str> {
+        use self::Type::*;
+        match *self {
+            Enumeration(ref enum_) => enum_.glib_get_type.as_deref(),
+            Bitfield(ref bit_field) => bit_field.glib_get_type.as_deref(),
+            Record(ref rec) => rec.glib_get_type.as_deref(),
+            Union(ref union) => union.glib_get_type.as_deref(),
+            Interface(ref interface) => Some(&interface.glib_get_type),
+            Class(ref class) => Some(&class.glib_get_type),
+            _ => None,
+        }
+    }
+
     pub fn c_array(
         library: &mut Library,
         inner: TypeId,
@@ -807,6 +1025,25 @@ impl Type {
         }
     }
 
+    /// Whether the type is part of the API on its own, judging by its name,
+    /// rather than a class structure, a callback or another helper type.
+    pub fn is_standalone(&self) -> bool {
+        const HELPER_ENDINGS: &[&str] = &[
+            "Class",
+            "Private",
+            "Func",
+            "Callback",
+            "Accessible",
+            "Iface",
+            "Type",
+            "Interface",
+        ];
+        let name = self.get_name();
+        !HELPER_ENDINGS.iter().any(|s| name.ends_with(s))
+            || self.is_enumeration()
+            || self.is_bitfield()
+    }
+
     pub fn is_fundamental(&self) -> bool {
         matches!(*self, Type::Fundamental(_))
     }
@@ -926,6 +1163,8 @@ pub struct Namespace {
     pub glib_name_index: HashMap<String, u32>,
     pub constants: Vec<Constant>,
     pub functions: Vec<Function>,
+    pub function_macros: Vec<FunctionMacro>,
+    pub doc_sections: Vec<DocSection>,
     pub package_name: Option<String>,
     pub versions: BTreeSet<Version>,
     pub doc: Option<String>,
@@ -953,6 +1192,14 @@ impl Namespace {
         self.functions.push(f);
     }
 
+    fn add_function_macro(&mut self, m: FunctionMacro) {
+        self.function_macros.push(m);
+    }
+
+    fn add_doc_section(&mut self, d: DocSection) {
+        self.doc_sections.push(d);
+    }
+
     fn type_(&self, id: u32) -> &Type {
         self.types[id as usize].as_ref().unwrap()
     }
@@ -1020,16 +1267,6 @@ impl Library {
     }
 
     pub fn show_non_bound_types(&self, env: &Env) {
-        let not_allowed_ending = [
-            "Class",
-            "Private",
-            "Func",
-            "Callback",
-            "Accessible",
-            "Iface",
-            "Type",
-            "Interface",
-        ];
         let namespace_name = self.namespaces[MAIN_NAMESPACE as usize].name.clone();
         let mut parents = HashSet::new();
 
@@ -1039,10 +1276,7 @@ impl Library {
                 let full_name = format!("{}.{}", namespace_name, name);
                 let mut check_methods = true;
 
-                if !not_allowed_ending.iter().any(|s| name.ends_with(s))
-                    || x.is_enumeration()
-                    || x.is_bitfield()
-                {
+                if x.is_standalone() {
                     let version = x.get_deprecated_version();
                     let depr_version = version.unwrap_or(env.config.min_cfg_version);
                     if !env.analysis.objects.contains_key(&full_name)
@@ -1051,10 +1285,14 @@ impl Library {
                         && depr_version >= env.config.min_cfg_version
                     {
                         check_methods = false;
+                        let hint = source_hint(x.source_position());
                         if let Some(version) = version {
-                            println!("[NOT GENERATED] {} (deprecated in {})", full_name, version);
+                            println!(
+                                "[NOT GENERATED] {} (deprecated in {}){}",
+                                full_name, version, hint
+                            );
                         } else {
-                            println!("[NOT GENERATED] {}", full_name);
+                            println!("[NOT GENERATED] {}{}", full_name, hint);
                         }
                     } else if let Type::Class(Class { properties, .. }) = x {
                         if !env
@@ -1114,6 +1352,56 @@ impl Library {
         );
     }
 
+    /// Full names of the ignored types used by the function parameters or return value.
+    pub fn ignored_dependencies(&self, env: &Env, func: &Function) -> Vec<String> {
+        let mut errors = func
+            .parameters
+            .iter()
+            .filter_map(|p| {
+                let mut ty = env.library.type_(p.typ);
+                let mut ns_id = p.typ.ns_id as usize;
+                if let Some((t, n)) = ty.get_inner_type(env) {
+                    ty = t;
+                    ns_id = n as usize;
+                }
+                if ty.is_fundamental() {
+                    return None;
+                }
+                let full_name = format!("{}.{}", self.namespaces[ns_id].name, ty.get_name());
+                if env.type_status(&p.typ.full_name(&env.library)).ignored()
+                    && !env.analysis.objects.contains_key(&full_name)
+                    && !env.analysis.records.contains_key(&full_name)
+                    && !env.config.objects.iter().any(|o| o.1.name == full_name)
+                {
+                    Some(full_name)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        {
+            let mut ty = env.library.type_(func.ret.typ);
+            let mut ns_id = func.ret.typ.ns_id as usize;
+            if let Some((t, n)) = ty.get_inner_type(env) {
+                ty = t;
+                ns_id = n as usize;
+            }
+            if !ty.is_fundamental() {
+                let full_name = format!("{}.{}", self.namespaces[ns_id].name, ty.get_name());
+                if env
+                    .type_status(&func.ret.typ.full_name(&env.library))
+                    .ignored()
+                    && !env.analysis.objects.contains_key(&full_name)
+                    && !env.analysis.records.contains_key(&full_name)
+                    && !env.config.objects.iter().any(|o| o.1.name == full_name)
+                {
+                    errors.push(full_name);
+                }
+            }
+        }
+        errors
+    }
+
     fn not_bound_functions(&self, env: &Env, prefix: &str, functions: &[Function], kind: &str) {
         for func in functions {
             let version = func.deprecated_version;
@@ -1123,68 +1411,25 @@ impl Library {
                 continue;
             }
 
-            let mut errors = func
-                .parameters
-                .iter()
-                .filter_map(|p| {
-                    let mut ty = env.library.type_(p.typ);
-                    let mut ns_id = p.typ.ns_id as usize;
-                    if let Some((t, n)) = ty.get_inner_type(env) {
-                        ty = t;
-                        ns_id = n as usize;
-                    }
-                    if ty.is_fundamental() {
-                        return None;
-                    }
-                    let full_name = format!("{}.{}", self.namespaces[ns_id].name, ty.get_name());
-                    if env.type_status(&p.typ.full_name(&env.library)).ignored()
-                        && !env.analysis.objects.contains_key(&full_name)
-                        && !env.analysis.records.contains_key(&full_name)
-                        && !env.config.objects.iter().any(|o| o.1.name == full_name)
-                    {
-                        Some(full_name)
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>();
-            {
-                let mut ty = env.library.type_(func.ret.typ);
-                let mut ns_id = func.ret.typ.ns_id as usize;
-                if let Some((t, n)) = ty.get_inner_type(env) {
-                    ty = t;
-                    ns_id = n as usize;
-                }
-                if !ty.is_fundamental() {
-                    let full_name = format!("{}.{}", self.namespaces[ns_id].name, ty.get_name());
-                    if env
-                        .type_status(&func.ret.typ.full_name(&env.library))
-                        .ignored()
-                        && !env.analysis.objects.contains_key(&full_name)
-                        && !env.analysis.records.contains_key(&full_name)
-                        && !env.config.objects.iter().any(|o| o.1.name == full_name)
-                    {
-                        errors.push(full_name);
-                    }
-                }
-            }
+            let mut errors = self.ignored_dependencies(env, func);
             if !errors.is_empty() {
                 let full_name = format!("{}{}", prefix, func.name);
                 let deprecated_version = match version {
                     Some(dv) => format!(" (deprecated in {})", dv),
                     None => String::new(),
                 };
+                let hint = source_hint(func.source_position.as_ref());
                 if errors.len() > 1 {
                     let end = errors.pop().unwrap();
                     let begin = errors.join(", ");
                     println!(
-                        "[NOT GENERATED {}] {}{} because of {} and {}",
-                        kind, full_name, deprecated_version, begin, end
+                        "[NOT GENERATED {}] {}{} because of {} and {}{}",
+                        kind, full_name, deprecated_version, begin, end, hint
                     );
                 } else {
                     println!(
-                        "[NOT GENERATED {}] {}{} because of {}",
-                        kind, full_name, deprecated_version, errors[0]
+                        "[NOT GENERATED {}] {}{} because of {}{}",
+                        kind, full_name, deprecated_version, errors[0], hint
                     );
                 }
             }
@@ -1222,6 +1467,14 @@ impl Library {
         self.namespace_mut(ns_id).add_function(f);
     }
 
+    pub fn add_function_macro(&mut self, ns_id: u16, m: FunctionMacro) {
+        self.namespace_mut(ns_id).add_function_macro(m);
+    }
+
+    pub fn add_doc_section(&mut self, ns_id: u16, d: DocSection) {
+        self.namespace_mut(ns_id).add_doc_section(d);
+    }
+
     pub fn add_type(&mut self, ns_id: u16, name: &str, typ: Type) -> TypeId {
         TypeId {
             ns_id,
@@ -1331,7 +1584,7 @@ impl Library {
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use super::*;
 
     #[test]
@@ -1344,4 +1597,96 @@ mod tests {
         assert_eq!(TypeId::tid_filename().full_name(&lib), "*.Filename");
         assert_eq!(TypeId::tid_os_string().full_name(&lib), "*.OsString");
     }
+
+    /// A function without parameters returning nothing, to be completed by the tests.
+    pub fn function(name: &str, c_identifier: &str, kind: FunctionKind) -> Function {
+        Function {
+            name: name.into(),
+            c_identifier: Some(c_identifier.into()),
+            kind,
+            parameters: Vec::new(),
+            ret: Parameter {
+                name: "".into(),
+                typ: TypeId::tid_none(),
+                c_type: "void".into(),
+                instance_parameter: false,
+                direction: ParameterDirection::Return,
+                transfer: Transfer::None,
+                caller_allocates: false,
+                nullable: Nullable(false),
+                allow_none: false,
+                array_length: None,
+                is_error: false,
+                doc: None,
+                scope: ParameterScope::None,
+                closure: None,
+                destroy: None,
+                attributes: Attributes::default(),
+            },
+            throws: false,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Attributes::default(),
+            source_position: None,
+            finish_func: None,
+            async_func: None,
+        }
+    }
+
+    fn method(name: &str, attributes: &[(&str, &str)]) -> Function {
+        let mut function = function(name, &format!("gtk_widget_{}", name), FunctionKind::Method);
+        for (name, value) in attributes {
+            function
+                .attributes
+                .insert((*name).to_owned(), (*value).to_owned());
+        }
+        function
+    }
+
+    #[test]
+    fn property_accessors() {
+        let functions = vec![
+            method("get_name", &[]),
+            method("fetch_title", &[("org.gtk.Method.get_property", "title")]),
+            method("store_title", &[]),
+        ];
+        let mut property = Property {
+            name: "title".into(),
+            readable: true,
+            writable: true,
+            construct: false,
+            construct_only: false,
+            typ: TypeId::tid_utf8(),
+            c_type: None,
+            transfer: Transfer::None,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: Attributes::default(),
+        };
+        assert_eq!(
+            property.getter(&functions).map(|f| f.name.as_str()),
+            Some("fetch_title")
+        );
+        assert!(property.setter(&functions).is_none());
+
+        property.attributes.insert(
+            "org.gtk.Property.set".into(),
+            "gtk_widget_store_title".into(),
+        );
+        property
+            .attributes
+            .insert("org.gtk.Property.get".into(), "gtk_widget_get_name".into());
+        assert_eq!(
+            property.getter(&functions).map(|f| f.name.as_str()),
+            Some("get_name")
+        );
+        assert_eq!(
+            property.setter(&functions).map(|f| f.name.as_str()),
+            Some("store_title")
+        );
+    }
 }
@@ -0,0 +1,502 @@
+//! JSON dump of the library model, with the type ids resolved to names.
+
+use crate::{json::Json, library::*, version::Version};
+
+/// Dumps the namespaces read from the GIR files, leaving out the internal one.
+pub fn dump(library: &Library) -> Json {
+    let namespaces = library
+        .namespaces
+        .iter()
+        .skip(MAIN_NAMESPACE as usize)
+        .map(|ns| namespace(library, ns))
+        .collect::<Vec<_>>();
+    Json::object().with("namespaces", namespaces)
+}
+
+fn namespace(library: &Library, ns: &Namespace) -> Json {
+    let types = ns
+        .types
+        .iter()
+        .flatten()
+        .filter_map(|typ| type_(library, typ))
+        .collect::<Vec<_>>();
+    Json::object()
+        .with("name", ns.name.as_str())
+        .with("package_name", ns.package_name.as_deref())
+        .with(
+            "versions",
+            ns.versions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        )
+        .with("shared_library", strings(&ns.shared_library))
+        .with("identifier_prefixes", strings(&ns.identifier_prefixes))
+        .with("symbol_prefixes", strings(&ns.symbol_prefixes))
+        .with("c_includes", strings(&ns.c_includes))
+        .with("doc", ns.doc.as_deref())
+        .with("doc_deprecated", ns.doc_deprecated.as_deref())
+        .with("types", types)
+        .with(
+            "constants",
+            ns.constants
+                .iter()
+                .map(|c| constant(library, c))
+                .collect::<Vec<_>>(),
+        )
+        .with("functions", functions(library, &ns.functions))
+        .with(
+            "function_macros",
+            ns.function_macros
+                .iter()
+                .map(function_macro)
+                .collect::<Vec<_>>(),
+        )
+        .with(
+            "doc_sections",
+            ns.doc_sections
+                .iter()
+                .map(|section| {
+                    Json::object()
+                        .with("name", section.name.as_str())
+                        .with("doc", section.doc.as_deref())
+                })
+                .collect::<Vec<_>>(),
+        )
+}
+
+/// Named types, containers and fundamentals are only dumped as references.
+fn type_(library: &Library, typ: &Type) -> Option<Json> {
+    let json = match *typ {
+        Type::Alias(ref alias) => Json::object()
+            .with("kind", "alias")
+            .with("name", alias.name.as_str())
+            .with("c_identifier", alias.c_identifier.as_str())
+            .with("type", type_ref(library, alias.typ))
+            .with("target_c_type", alias.target_c_type.as_str())
+            .with("doc", alias.doc.as_deref())
+            .with("doc_deprecated", alias.doc_deprecated.as_deref())
+            .with("attributes", attributes(&alias.attributes))
+            .with("source_position", source_position(&alias.source_position)),
+        Type::Enumeration(ref enum_) => Json::object()
+            .with("kind", "enumeration")
+            .with("name", enum_.name.as_str())
+            .with("c_type", enum_.c_type.as_str())
+            .with("symbol_prefix", enum_.symbol_prefix.as_deref())
+            .with("glib_get_type", enum_.glib_get_type.as_deref())
+            .with(
+                "error_domain",
+                enum_.error_domain.as_ref().map(|domain| match *domain {
+                    ErrorDomain::Quark(ref quark) => Json::object().with("quark", quark.as_str()),
+                    ErrorDomain::Function(ref func) => {
+                        Json::object().with("function", func.as_str())
+                    }
+                }),
+            )
+            .with("members", members(&enum_.members))
+            .with("functions", functions(library, &enum_.functions))
+            .with("version", version(enum_.version))
+            .with("deprecated_version", version(enum_.deprecated_version))
+            .with("doc", enum_.doc.as_deref())
+            .with("doc_deprecated", enum_.doc_deprecated.as_deref())
+            .with("attributes", attributes(&enum_.attributes))
+            .with("source_position", source_position(&enum_.source_position)),
+        Type::Bitfield(ref bitfield) => Json::object()
+            .with("kind", "bitfield")
+            .with("name", bitfield.name.as_str())
+            .with("c_type", bitfield.c_type.as_str())
+            .with("symbol_prefix", bitfield.symbol_prefix.as_deref())
+            .with("glib_get_type", bitfield.glib_get_type.as_deref())
+            .with("members", members(&bitfield.members))
+            .with("functions", functions(library, &bitfield.functions))
+            .with("version", version(bitfield.version))
+            .with("deprecated_version", version(bitfield.deprecated_version))
+            .with("doc", bitfield.doc.as_deref())
+            .with("doc_deprecated", bitfield.doc_deprecated.as_deref())
+            .with("attributes", attributes(&bitfield.attributes))
+            .with(
+                "source_position",
+                source_position(&bitfield.source_position),
+            ),
+        Type::Record(ref record) => Json::object()
+            .with("kind", "record")
+            .with("name", record.name.as_str())
+            .with("c_type", record.c_type.as_str())
+            .with("symbol_prefix", record.symbol_prefix.as_deref())
+            .with("glib_get_type", record.glib_get_type.as_deref())
+            .with("gtype_struct_for", record.gtype_struct_for.as_deref())
+            .with("disguised", record.disguised)
+            .with("fields", fields(library, &record.fields))
+            .with("functions", functions(library, &record.functions))
+            .with("version", version(record.version))
+            .with("deprecated_version", version(record.deprecated_version))
+            .with("doc", record.doc.as_deref())
+            .with("doc_deprecated", record.doc_deprecated.as_deref())
+            .with("attributes", attributes(&record.attributes))
+            .with("source_position", source_position(&record.source_position)),
+        Type::Union(ref union) => Json::object()
+            .with("kind", "union")
+            .with("name", union.name.as_str())
+            .with("c_type", union.c_type.as_deref())
+            .with("symbol_prefix", union.symbol_prefix.as_deref())
+            .with("glib_get_type", union.glib_get_type.as_deref())
+            .with("fields", fields(library, &union.fields))
+            .with("functions", functions(library, &union.functions))
+            .with("doc", union.doc.as_deref())
+            .with("attributes", attributes(&union.attributes))
+            .with("source_position", source_position(&union.source_position)),
+        Type::Function(ref func) => function(library, func, "callback"),
+        Type::Interface(ref iface) => Json::object()
+            .with("kind", "interface")
+            .with("name", iface.name.as_str())
+            .with("c_type", iface.c_type.as_str())
+            .with("symbol_prefix", iface.symbol_prefix.as_str())
+            .with("type_struct", iface.type_struct.as_deref())
+            .with("c_class_type", iface.c_class_type.as_deref())
+            .with("glib_get_type", iface.glib_get_type.as_str())
+            .with("prerequisites", type_refs(library, &iface.prerequisites))
+            .with("functions", functions(library, &iface.functions))
+            .with(
+                "virtual_methods",
+                functions(library, &iface.virtual_methods),
+            )
+            .with("signals", signals(library, &iface.signals))
+            .with("properties", properties(library, &iface.properties))
+            .with("version", version(iface.version))
+            .with("deprecated_version", version(iface.deprecated_version))
+            .with("doc", iface.doc.as_deref())
+            .with("doc_deprecated", iface.doc_deprecated.as_deref())
+            .with("attributes", attributes(&iface.attributes))
+            .with("source_position", source_position(&iface.source_position)),
+        Type::Class(ref class) => Json::object()
+            .with("kind", "class")
+            .with("name", class.name.as_str())
+            .with("c_type", class.c_type.as_str())
+            .with("symbol_prefix", class.symbol_prefix.as_str())
+            .with("type_struct", class.type_struct.as_deref())
+            .with("c_class_type", class.c_class_type.as_deref())
+            .with("glib_get_type", class.glib_get_type.as_str())
+            .with("parent", class.parent.map(|tid| type_ref(library, tid)))
+            .with("implements", type_refs(library, &class.implements))
+            .with("final", class.final_type)
+            .with("abstract", class.is_abstract)
+            .with("fields", fields(library, &class.fields))
+            .with("functions", functions(library, &class.functions))
+            .with(
+                "virtual_methods",
+                functions(library, &class.virtual_methods),
+            )
+            .with("signals", signals(library, &class.signals))
+            .with("properties", properties(library, &class.properties))
+            .with("version", version(class.version))
+            .with("deprecated_version", version(class.deprecated_version))
+            .with("doc", class.doc.as_deref())
+            .with("doc_deprecated", class.doc_deprecated.as_deref())
+            .with("attributes", attributes(&class.attributes))
+            .with("source_position", source_position(&class.source_position)),
+        Type::Custom(ref custom) => Json::object()
+            .with("kind", "custom")
+            .with("name", custom.name.as_str()),
+        _ => return None,
+    };
+    Some(json)
+}
+
+/// Reference to a type: its full name, or an object describing the container.
+pub(crate) fn type_ref(library: &Library, tid: TypeId) -> Json {
+    match *library.type_(tid) {
+        Type::Array(inner) => Json::object().with("array", type_ref(library, inner)),
+        Type::CArray(inner) => Json::object().with("c_array", type_ref(library, inner)),
+        Type::FixedArray(inner, size, ref c_type) => Json::object()
+            .with("fixed_array", type_ref(library, inner))
+            .with("size", size as usize)
+            .with("c_type", c_type.as_deref()),
+        Type::PtrArray(inner) => Json::object().with("ptr_array", type_ref(library, inner)),
+        Type::HashTable(key, value) => Json::object().with(
+            "hash_table",
+            vec![type_ref(library, key), type_ref(library, value)],
+        ),
+        Type::List(inner) => Json::object().with("list", type_ref(library, inner)),
+        Type::SList(inner) => Json::object().with("slist", type_ref(library, inner)),
+        Type::Function(ref func) if tid.ns_id == INTERNAL_NAMESPACE => Json::object().with(
+            "callback",
+            Json::object()
+                .with("parameters", parameters(library, &func.parameters))
+                .with("return", parameter(library, &func.ret)),
+        ),
+        _ if tid.ns_id == INTERNAL_NAMESPACE => {
+            // Fundamental types are registered under their GIR names.
+            let ns = library.namespace(INTERNAL_NAMESPACE);
+            match ns.index.iter().find(|&(_, &id)| id == tid.id) {
+                Some((name, _)) => name.as_str().into(),
+                None => library.type_(tid).get_name().into(),
+            }
+        }
+        _ => tid.full_name(library).into(),
+    }
+}
+
+fn type_refs(library: &Library, tids: &[TypeId]) -> Json {
+    tids.iter()
+        .map(|&tid| type_ref(library, tid))
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn constant(library: &Library, constant: &Constant) -> Json {
+    Json::object()
+        .with("name", constant.name.as_str())
+        .with("c_identifier", constant.c_identifier.as_str())
+        .with("type", type_ref(library, constant.typ))
+        .with("c_type", constant.c_type.as_str())
+        .with("value", constant.value.as_str())
+        .with("version", version(constant.version))
+        .with("deprecated_version", version(constant.deprecated_version))
+        .with("doc", constant.doc.as_deref())
+        .with("doc_deprecated", constant.doc_deprecated.as_deref())
+        .with("attributes", attributes(&constant.attributes))
+        .with(
+            "source_position",
+            source_position(&constant.source_position),
+        )
+}
+
+fn members(members: &[Member]) -> Json {
+    members
+        .iter()
+        .map(|member| {
+            Json::object()
+                .with("name", member.name.as_str())
+                .with("c_identifier", member.c_identifier.as_str())
+                .with("value", member.value.as_str())
+                .with("nick", member.nick.as_deref())
+                .with("version", version(member.version))
+                .with("deprecated_version", version(member.deprecated_version))
+                .with("doc", member.doc.as_deref())
+                .with("doc_deprecated", member.doc_deprecated.as_deref())
+                .with("attributes", attributes(&member.attributes))
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn fields(library: &Library, fields: &[Field]) -> Json {
+    fields
+        .iter()
+        .map(|field| {
+            Json::object()
+                .with("name", field.name.as_str())
+                .with("type", type_ref(library, field.typ))
+                .with("c_type", field.c_type.as_deref())
+                .with("private", field.private)
+                .with("bits", field.bits.map(|bits| bits as usize))
+                .with("array_length", field.array_length.map(|l| l as usize))
+                .with("doc", field.doc.as_deref())
+                .with("attributes", attributes(&field.attributes))
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn functions(library: &Library, functions: &[Function]) -> Json {
+    functions
+        .iter()
+        .map(|func| {
+            let kind = match func.kind {
+                FunctionKind::Constructor => "constructor",
+                FunctionKind::Function => "function",
+                FunctionKind::Method => "method",
+                FunctionKind::Global => "global",
+                FunctionKind::VirtualMethod => "virtual method",
+            };
+            function(library, func, kind)
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn function(library: &Library, func: &Function, kind: &str) -> Json {
+    Json::object()
+        .with("kind", kind)
+        .with("name", func.name.as_str())
+        .with("c_identifier", func.c_identifier.as_deref())
+        .with("parameters", parameters(library, &func.parameters))
+        .with("return", parameter(library, &func.ret))
+        .with("throws", func.throws)
+        .with("finish_func", func.finish_func.as_deref())
+        .with("async_func", func.async_func.as_deref())
+        .with("version", version(func.version))
+        .with("deprecated_version", version(func.deprecated_version))
+        .with("doc", func.doc.as_deref())
+        .with("doc_deprecated", func.doc_deprecated.as_deref())
+        .with("attributes", attributes(&func.attributes))
+        .with("source_position", source_position(&func.source_position))
+}
+
+fn function_macro(func: &FunctionMacro) -> Json {
+    Json::object()
+        .with("name", func.name.as_str())
+        .with("c_identifier", func.c_identifier.as_str())
+        .with("parameters", strings(&func.parameters))
+        .with("version", version(func.version))
+        .with("deprecated_version", version(func.deprecated_version))
+        .with("doc", func.doc.as_deref())
+        .with("doc_deprecated", func.doc_deprecated.as_deref())
+        .with("attributes", attributes(&func.attributes))
+        .with("source_position", source_position(&func.source_position))
+}
+
+fn parameters(library: &Library, parameters: &[Parameter]) -> Json {
+    parameters
+        .iter()
+        .map(|par| parameter(library, par))
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn parameter(library: &Library, par: &Parameter) -> Json {
+    let direction = match par.direction {
+        ParameterDirection::None => None,
+        ParameterDirection::In => Some("in"),
+        ParameterDirection::Out => Some("out"),
+        ParameterDirection::InOut => Some("inout"),
+        ParameterDirection::Return => Some("return"),
+    };
+    let scope = match par.scope {
+        ParameterScope::None => None,
+        ParameterScope::Call => Some("call"),
+        ParameterScope::Async => Some("async"),
+        ParameterScope::Notified => Some("notified"),
+    };
+    Json::object()
+        .with("name", par.name.as_str())
+        .with("type", type_ref(library, par.typ))
+        .with("c_type", par.c_type.as_str())
+        .with("instance_parameter", par.instance_parameter)
+        .with("direction", direction)
+        .with("transfer", transfer(par.transfer))
+        .with("caller_allocates", par.caller_allocates)
+        .with("nullable", *par.nullable)
+        .with("allow_none", par.allow_none)
+        .with("array_length", par.array_length.map(|l| l as usize))
+        .with("is_error", par.is_error)
+        .with("scope", scope)
+        .with("closure", par.closure)
+        .with("destroy", par.destroy)
+        .with("doc", par.doc.as_deref())
+        .with("attributes", attributes(&par.attributes))
+}
+
+fn signals(library: &Library, signals: &[Signal]) -> Json {
+    signals
+        .iter()
+        .map(|signal| {
+            Json::object()
+                .with("name", signal.name.as_str())
+                .with("parameters", parameters(library, &signal.parameters))
+                .with("return", parameter(library, &signal.ret))
+                .with("action", signal.is_action)
+                .with("detailed", signal.is_detailed)
+                .with("when", signal.when.map(|when| when.to_string()))
+                .with("no_recurse", signal.no_recurse)
+                .with("no_hooks", signal.no_hooks)
+                .with("version", version(signal.version))
+                .with("deprecated_version", version(signal.deprecated_version))
+                .with("doc", signal.doc.as_deref())
+                .with("doc_deprecated", signal.doc_deprecated.as_deref())
+                .with("attributes", attributes(&signal.attributes))
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn properties(library: &Library, properties: &[Property]) -> Json {
+    properties
+        .iter()
+        .map(|prop| {
+            Json::object()
+                .with("name", prop.name.as_str())
+                .with("type", type_ref(library, prop.typ))
+                .with("c_type", prop.c_type.as_deref())
+                .with("readable", prop.readable)
+                .with("writable", prop.writable)
+                .with("construct", prop.construct)
+                .with("construct_only", prop.construct_only)
+                .with("transfer", transfer(prop.transfer))
+                .with("version", version(prop.version))
+                .with("deprecated_version", version(prop.deprecated_version))
+                .with("doc", prop.doc.as_deref())
+                .with("doc_deprecated", prop.doc_deprecated.as_deref())
+                .with("attributes", attributes(&prop.attributes))
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn transfer(transfer: Transfer) -> &'static str {
+    match transfer {
+        Transfer::None => "none",
+        Transfer::Container => "container",
+        Transfer::Full => "full",
+    }
+}
+
+fn attributes(attributes: &Attributes) -> Json {
+    Json::Object(
+        attributes
+            .iter()
+            .map(|(name, value)| (name.to_owned(), value.into()))
+            .collect(),
+    )
+}
+
+fn source_position(position: &Option<SourcePosition>) -> Json {
+    position.as_ref().map(ToString::to_string).into()
+}
+
+fn version(version: Option<Version>) -> Json {
+    version.map(|v| v.to_string()).into()
+}
+
+fn strings(strings: &[String]) -> Json {
+    strings
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_ref_names() {
+        let mut library = Library::new("Test");
+        let point = library.add_type(
+            MAIN_NAMESPACE,
+            "Point",
+            Type::Record(Record {
+                name: "Point".into(),
+                c_type: "TestPoint".into(),
+                ..Default::default()
+            }),
+        );
+        let strings = Type::c_array(&mut library, TypeId::tid_utf8(), None, None);
+        let points = Type::c_array(&mut library, point, Some(4), None);
+
+        assert_eq!(type_ref(&library, TypeId::tid_utf8()), Json::from("utf8"));
+        assert_eq!(type_ref(&library, point), Json::from("Test.Point"));
+        assert_eq!(
+            type_ref(&library, strings),
+            Json::object().with("c_array", "utf8")
+        );
+        assert_eq!(
+            type_ref(&library, points),
+            Json::object()
+                .with("fixed_array", "Test.Point")
+                .with("size", 4usize)
+                .with("c_type", None::<&str>)
+        );
+    }
+}
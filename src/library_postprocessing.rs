@@ -450,8 +450,7 @@ impl Library {
 
     fn mark_final_types(&mut self, config: &Config) {
         // Here we mark all class types as final types if configured so in the config or
-        // otherwise if there is no public class struct for the type or the instance struct
-        // has no fields (i.e. is not known!), and there are no known subtypes.
+        // otherwise if `is_final_class` detects them.
         //
         // Final types can't have any subclasses and we handle them slightly different
         // for that reason.
@@ -479,25 +478,8 @@ impl Library {
                         // The config might also be used to override a type that is wrongly
                         // detected as final type otherwise
                         *final_type
-                    } else if klass.type_struct.is_none() {
-                        !self.has_subtypes(tid)
                     } else {
-                        let has_subtypes = self.has_subtypes(tid);
-                        let instance_struct_known = !klass.fields.is_empty();
-
-                        let class_struct_known = if let Some(class_record_tid) =
-                            self.find_type(ns_id as u16, klass.type_struct.as_ref().unwrap())
-                        {
-                            if let Type::Record(ref record) = self.type_(class_record_tid) {
-                                !record.disguised
-                            } else {
-                                unreachable!("Type {} with non-record class", full_name);
-                            }
-                        } else {
-                            unreachable!("Can't find class for {}", full_name);
-                        };
-
-                        !has_subtypes && (!instance_struct_known || !class_struct_known)
+                        self.is_final_class(tid, klass)
                     };
                     if is_final {
                         final_types.push(tid);
@@ -518,6 +500,32 @@ impl Library {
         }
     }
 
+    /// Whether a class can't be subclassed: there is no public class struct for the type or
+    /// the instance struct has no fields (i.e. is not known!), and there are no known subtypes.
+    ///
+    /// Used without config, before `postprocessing` sets `Class::final_type`.
+    pub fn is_final_class(&self, tid: TypeId, klass: &Class) -> bool {
+        if klass.type_struct.is_none() {
+            return !self.has_subtypes(tid);
+        }
+        let has_subtypes = self.has_subtypes(tid);
+        let instance_struct_known = !klass.fields.is_empty();
+
+        let class_struct_known = if let Some(class_record_tid) =
+            self.find_type(tid.ns_id, klass.type_struct.as_ref().unwrap())
+        {
+            if let Type::Record(ref record) = self.type_(class_record_tid) {
+                !record.disguised
+            } else {
+                unreachable!("Type {} with non-record class", tid.full_name(self));
+            }
+        } else {
+            unreachable!("Can't find class for {}", tid.full_name(self));
+        };
+
+        !has_subtypes && (!instance_struct_known || !class_struct_known)
+    }
+
     fn update_error_domain_functions(&mut self, config: &Config) {
         // Find find all error domains that have corresponding functions
         let mut error_domains = vec![];
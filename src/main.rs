@@ -5,11 +5,12 @@ use std::{cell::RefCell, str::FromStr};
 
 use getopts::Options;
 use hprof::Profiler;
-use libgir::{self as gir, Config, Library, WorkMode};
+use libgir::{self as gir, Config, Library, OutputFormat, WorkMode};
 
 fn print_usage(program: &str, opts: Options) {
     let brief = format!(
         "Usage: {program} [options] [<library> <version>]
+       {program} init <library> <version> -d GIRSPATH [-o PATH]
        {program} (-h | --help)",
         program = program
     );
@@ -30,6 +31,21 @@ impl<S: AsRef<str>> OptionStr for Option<S> {
 enum RunKind {
     Config(Config),
     CheckGirFile(String),
+    /// Print the library read with the given config as JSON instead of generating code.
+    DumpLibrary(Config),
+    /// Compare the library read with the given config to the one in older GIR directories.
+    DiffGirs {
+        cfg: Config,
+        old_girs_dirs: Vec<String>,
+        suggest_versions: bool,
+    },
+    /// Write the starting `Gir.toml` files and crates for a new binding.
+    Init {
+        library: String,
+        version: String,
+        girs_dirs: Vec<String>,
+        target_path: PathBuf,
+    },
 }
 
 fn build_config() -> Result<RunKind, String> {
@@ -53,14 +69,52 @@ fn build_config() -> Result<RunKind, String> {
     options.optopt(
         "m",
         "mode",
-        "Work mode: doc, normal, sys or not_bound",
+        "Work mode: doc, normal, sys, subclass or not_bound",
         "MODE",
     );
     options.optopt("o", "target", "Target path", "PATH");
     options.optopt("p", "doc-target-path", "Doc target path", "PATH");
-    options.optflag("b", "make-backup", "Make backup before generating");
-    options.optflag("s", "stats", "Show statistics");
+    options.optflag(
+        "b",
+        "make-backup",
+        "Make backup of changed files before generating",
+    );
+    options.optflag("s", "stats", "Show timing and binding coverage statistics");
     options.optflag("", "disable-format", "Disable formatting generated code");
+    options.optflag(
+        "",
+        "check",
+        "Show the differences with the generated code instead of writing it, \
+         failing if there are any",
+    );
+    options.optflag(
+        "",
+        "strict",
+        "Fail on unknown config keys and on config entries matching nothing",
+    );
+    options.optopt(
+        "",
+        "format",
+        "Format of the not_bound report: text (default) or json",
+        "FORMAT",
+    );
+    options.optflag(
+        "",
+        "dump-library",
+        "Print the library read from the GIR files as JSON instead of generating code",
+    );
+    options.optmulti(
+        "",
+        "diff-girs",
+        "Compare the API with the one of the older GIR files in this directory, \
+         listing the new API missing a version",
+        "OLD_GIRSPATH",
+    );
+    options.optflag(
+        "",
+        "suggest-versions",
+        "With --diff-girs, print version overrides for the new API in Gir.toml syntax",
+    );
     options.optopt(
         "",
         "check-gir-file",
@@ -79,6 +133,18 @@ fn build_config() -> Result<RunKind, String> {
         process::exit(0);
     }
 
+    if matches.free.get(0).as_str_ref() == Some("init") {
+        return match (matches.free.get(1), matches.free.get(2)) {
+            (Some(library), Some(version)) => Ok(RunKind::Init {
+                library: library.clone(),
+                version: version.clone(),
+                girs_dirs: matches.opt_strs("d"),
+                target_path: PathBuf::from(matches.opt_str("o").unwrap_or_else(|| ".".into())),
+            }),
+            _ => Err("`init` needs the library name and version".into()),
+        };
+    }
+
     let work_mode = match matches.opt_str("m") {
         None => None,
         Some(s) => match WorkMode::from_str(&s) {
@@ -90,6 +156,11 @@ fn build_config() -> Result<RunKind, String> {
         },
     };
 
+    let output_format = match matches.opt_str("format") {
+        None => OutputFormat::default(),
+        Some(s) => OutputFormat::from_str(&s)?,
+    };
+
     Config::new(
         matches.opt_str("c").as_str_ref(),
         work_mode,
@@ -101,8 +172,24 @@ fn build_config() -> Result<RunKind, String> {
         matches.opt_present("b"),
         matches.opt_present("s"),
         matches.opt_present("disable-format"),
+        matches.opt_present("check"),
+        matches.opt_present("strict"),
+        output_format,
     )
-    .map(RunKind::Config)
+    .map(|cfg| {
+        let old_girs_dirs = matches.opt_strs("diff-girs");
+        if !old_girs_dirs.is_empty() {
+            RunKind::DiffGirs {
+                cfg,
+                old_girs_dirs,
+                suggest_versions: matches.opt_present("suggest-versions"),
+            }
+        } else if matches.opt_present("dump-library") {
+            RunKind::DumpLibrary(cfg)
+        } else {
+            RunKind::Config(cfg)
+        }
+    })
 }
 
 #[cfg_attr(test, allow(dead_code))]
@@ -134,15 +221,42 @@ fn run_check(check_gir_file: &str) -> Result<(), String> {
     library.read_file(&[parent], &mut vec![lib_name.to_owned()])
 }
 
+fn run_diff(cfg: &Config, old_girs_dirs: &[String], suggest_versions: bool) -> Result<(), String> {
+    let mut old = Library::new(&cfg.library_name);
+    old.read_file(old_girs_dirs, &mut vec![cfg.library_full_name()])?;
+    let mut new = Library::new(&cfg.library_name);
+    new.read_file(&cfg.girs_dirs, &mut vec![cfg.library_full_name()])?;
+
+    let diff = gir::api_diff::Diff::new(&old, &new);
+    if suggest_versions {
+        diff.print_version_overrides();
+    } else {
+        diff.print();
+    }
+    Ok(())
+}
+
 fn do_main() -> Result<(), String> {
     if std::env::var_os("RUST_LOG").is_none() {
         std::env::set_var("RUST_LOG", "gir=warn,libgir=warn");
     }
     env_logger::init();
 
-    let mut cfg = match build_config() {
+    let (mut cfg, dump_library) = match build_config() {
         Ok(RunKind::CheckGirFile(check_gir_file)) => return run_check(&check_gir_file),
-        Ok(RunKind::Config(cfg)) => cfg,
+        Ok(RunKind::Config(cfg)) => (cfg, false),
+        Ok(RunKind::DumpLibrary(cfg)) => (cfg, true),
+        Ok(RunKind::DiffGirs {
+            cfg,
+            old_girs_dirs,
+            suggest_versions,
+        }) => return run_diff(&cfg, &old_girs_dirs, suggest_versions),
+        Ok(RunKind::Init {
+            library,
+            version,
+            girs_dirs,
+            target_path,
+        }) => return gir::init::run(&library, &version, &girs_dirs, &target_path),
         Err(err) => return Err(err),
     };
     cfg.check_disable_format();
@@ -178,6 +292,7 @@ fn do_main() -> Result<(), String> {
     {
         let _watcher = statistics.enter("Resolving type ids");
         cfg.resolve_type_ids(&library);
+        cfg.check_unmatched(&library)?;
     }
 
     {
@@ -185,6 +300,11 @@ fn do_main() -> Result<(), String> {
         gir::update_version::check_function_real_version(&mut library);
     }
 
+    if dump_library {
+        println!("{}", gir::library_dump::dump(&library));
+        return Ok(());
+    }
+
     let mut env = {
         let _watcher = statistics.enter("Namespace/symbol/class analysis");
 
@@ -212,7 +332,10 @@ fn do_main() -> Result<(), String> {
         gir::codegen_generate(&env);
     }
 
-    if !env.config.disable_format && env.config.work_mode.is_generate_rust_files() {
+    if !env.config.disable_format
+        && !env.config.check
+        && env.config.work_mode.is_generate_rust_files()
+    {
         let _watcher = statistics.enter("Formatting");
         gir::fmt::format(&env.config.target_path);
     }
@@ -222,9 +345,32 @@ fn do_main() -> Result<(), String> {
 
     if env.config.show_statistics {
         statistics.print_timing();
+        if matches!(env.config.work_mode, WorkMode::Normal | WorkMode::Sys) {
+            gir::report::print_coverage(&env);
+        }
     }
     if env.config.work_mode == WorkMode::DisplayNotBound {
-        env.library.show_non_bound_types(&env);
+        match env.config.output_format {
+            OutputFormat::Text => env.library.show_non_bound_types(&env),
+            OutputFormat::Json => gir::report::print_json(&env),
+        }
+    }
+
+    if env.config.check {
+        let mut outdated_files = env.config.generated_files.outdated();
+        if matches!(env.config.work_mode, WorkMode::Normal | WorkMode::Subclass) {
+            let stale_files = env
+                .config
+                .generated_files
+                .stale_files(&env.config.auto_path);
+            for path in &stale_files {
+                println!("{} is not generated anymore", path.display());
+            }
+            outdated_files += stale_files.len();
+        }
+        if outdated_files > 0 {
+            return Err(format!("{} generated file(s) out of date", outdated_files));
+        }
     }
 
     Ok(())
@@ -90,6 +90,11 @@ pub fn bitfield_member_name(name: &str) -> String {
     }
 }
 
+/// Name of the constant carrying the documentation of a `<docsection>`.
+pub fn doc_section_name(name: &str) -> String {
+    bitfield_member_name(&name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"))
+}
+
 pub fn needs_mangling(name: &str) -> bool {
     KEYWORDS.contains_key(name)
 }
@@ -253,6 +258,13 @@ mod tests {
         assert_eq!(signal_to_snake("move-active"), "move_active");
     }
 
+    #[test]
+    fn doc_section_name_works() {
+        assert_eq!(doc_section_name("running"), "RUNNING");
+        assert_eq!(doc_section_name("question-index"), "QUESTION_INDEX");
+        assert_eq!(doc_section_name("2d"), "_2D");
+    }
+
     #[test]
     fn lib_name_to_toml_works() {
         assert_eq!(lib_name_to_toml("gstreamer-1.0"), "gstreamer_1_0");
@@ -22,9 +22,10 @@ impl Library {
         dirs: &[P],
         libs: &mut Vec<String>,
     ) -> Result<(), String> {
+        // The typelibs lack the documentation and the C details, any GIR file is better
         for dir in dirs {
             let dir: &Path = dir.as_ref();
-            let file_name = make_file_name(dir, &libs[libs.len() - 1]);
+            let file_name = make_file_name(dir, &libs[libs.len() - 1], "gir");
             let mut parser = match XmlParser::from_path(&file_name) {
                 Ok(p) => p,
                 _ => continue,
@@ -35,6 +36,12 @@ impl Library {
                 })
             });
         }
+        for dir in dirs {
+            let typelib_name = make_file_name(dir.as_ref(), &libs[libs.len() - 1], "typelib");
+            if typelib_name.is_file() {
+                return self.read_typelib(dirs, &typelib_name, libs);
+            }
+        }
         Err(format!("Couldn't find `{}`...", &libs[libs.len() - 1]))
     }
 
@@ -133,7 +140,8 @@ impl Library {
                 "function" => self.read_global_function(parser, ns_id, elem),
                 "constant" => self.read_constant(parser, ns_id, elem),
                 "alias" => self.read_alias(parser, ns_id, elem),
-                "function-macro" | "docsection" => parser.ignore_element(),
+                "function-macro" => self.read_function_macro(parser, ns_id, elem),
+                "docsection" => self.read_doc_section(parser, ns_id, elem),
                 _ => {
                     warn!("<{} name={:?}>", elem.name(), elem.attr("name"));
                     parser.ignore_element()
@@ -159,6 +167,7 @@ impl Library {
         let is_abstract = elem.attr("abstract").map(|x| x == "1").unwrap_or(false);
 
         let mut fns = Vec::new();
+        let mut vfns = Vec::new();
         let mut signals = Vec::new();
         let mut properties = Vec::new();
         let mut impls = Vec::new();
@@ -166,6 +175,8 @@ impl Library {
         let mut doc = None;
         let mut doc_deprecated = None;
         let mut union_count = 1;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
 
         parser.elements(|parser, elem| match elem.name() {
             "constructor" | "function" | "method" => {
@@ -185,10 +196,14 @@ impl Library {
             "field" => self.read_field(parser, ns_id, elem).map(|f| {
                 fields.push(f);
             }),
-            "virtual-method" => parser.ignore_element(),
+            "virtual-method" => self
+                .read_virtual_method(parser, ns_id, elem)
+                .map(|f| vfns.push(f)),
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "source-position" => parser.ignore_element(),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
             "union" => self
                 .read_union(parser, ns_id, elem, Some(class_name), Some(c_type))
                 .map(|mut u| {
@@ -216,7 +231,7 @@ impl Library {
                     });
                     union_count += 1;
                 }),
-            "attribute" => parser.ignore_element(),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -231,6 +246,7 @@ impl Library {
             glib_get_type: get_type.into(),
             fields,
             functions: fns,
+            virtual_methods: vfns,
             signals,
             properties,
             parent,
@@ -242,6 +258,8 @@ impl Library {
             deprecated_version,
             symbol_prefix,
             is_abstract,
+            attributes,
+            source_position,
         });
         self.add_type(ns_id, class_name, typ);
         Ok(())
@@ -282,6 +300,8 @@ impl Library {
         let mut doc = None;
         let mut doc_deprecated = None;
         let mut union_count = 1;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
 
         parser.elements(|parser, elem| match elem.name() {
             "constructor" | "function" | "method" => {
@@ -347,8 +367,10 @@ impl Library {
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "source-position" => parser.ignore_element(),
-            "attribute" => parser.ignore_element(),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -365,6 +387,8 @@ impl Library {
             doc_deprecated,
             disguised,
             symbol_prefix,
+            attributes,
+            source_position,
         });
 
         Ok(Some(typ))
@@ -411,9 +435,13 @@ impl Library {
         let mut fns = Vec::new();
         let mut doc = None;
         let mut struct_count = 1;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
 
         parser.elements(|parser, elem| match elem.name() {
-            "source-position" => parser.ignore_element(),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
             "field" => self.read_field(parser, ns_id, elem).map(|f| {
                 fields.push(f);
             }),
@@ -482,7 +510,7 @@ impl Library {
                 Ok(())
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
-            "attribute" => parser.ignore_element(),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -494,6 +522,8 @@ impl Library {
             functions: fns,
             doc,
             symbol_prefix,
+            attributes,
+            source_position,
         })
     }
 
@@ -509,6 +539,7 @@ impl Library {
 
         let mut typ = None;
         let mut doc = None;
+        let mut attributes = Attributes::default();
 
         parser.elements(|parser, elem| match elem.name() {
             "type" | "array" => {
@@ -529,7 +560,7 @@ impl Library {
                     })
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
-            "attribute" => parser.ignore_element(),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -542,6 +573,7 @@ impl Library {
                 bits,
                 array_length,
                 doc,
+                attributes,
             })
         } else {
             Err(parser.fail("Missing <type> element"))
@@ -578,11 +610,14 @@ impl Library {
         let deprecated_version = self.read_deprecated_version(parser, ns_id, elem)?;
 
         let mut fns = Vec::new();
+        let mut vfns = Vec::new();
         let mut signals = Vec::new();
         let mut properties = Vec::new();
         let mut prereqs = Vec::new();
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
 
         parser.elements(|parser, elem| match elem.name() {
             "constructor" | "function" | "method" => {
@@ -601,9 +636,13 @@ impl Library {
             }),
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "virtual-method" => parser.ignore_element(),
-            "source-position" => parser.ignore_element(),
-            "attribute" => parser.ignore_element(),
+            "virtual-method" => self
+                .read_virtual_method(parser, ns_id, elem)
+                .map(|f| vfns.push(f)),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -614,6 +653,7 @@ impl Library {
             c_class_type: None, // this will be resolved during postprocessing
             glib_get_type: get_type.into(),
             functions: fns,
+            virtual_methods: vfns,
             signals,
             properties,
             prerequisites: prereqs,
@@ -622,6 +662,8 @@ impl Library {
             version,
             deprecated_version,
             symbol_prefix,
+            attributes,
+            source_position,
         });
         self.add_type(ns_id, interface_name, typ);
         Ok(())
@@ -644,16 +686,22 @@ impl Library {
         let mut fns = Vec::new();
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
 
         parser.elements(|parser, elem| match elem.name() {
-            "member" => self.read_member(parser, elem).map(|m| members.push(m)),
+            "member" => self
+                .read_member(parser, ns_id, elem)
+                .map(|m| members.push(m)),
             "constructor" | "function" | "method" => {
                 self.read_function_to_vec(parser, ns_id, elem, &mut fns)
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "source-position" => parser.ignore_element(),
-            "attribute" => parser.ignore_element(),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -668,6 +716,8 @@ impl Library {
             doc_deprecated,
             glib_get_type: get_type,
             symbol_prefix,
+            attributes,
+            source_position,
         });
         self.add_type(ns_id, bitfield_name, typ);
         Ok(())
@@ -693,16 +743,22 @@ impl Library {
         let mut fns = Vec::new();
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
 
         parser.elements(|parser, elem| match elem.name() {
-            "member" => self.read_member(parser, elem).map(|m| members.push(m)),
+            "member" => self
+                .read_member(parser, ns_id, elem)
+                .map(|m| members.push(m)),
             "constructor" | "function" | "method" => {
                 self.read_function_to_vec(parser, ns_id, elem, &mut fns)
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "source-position" => parser.ignore_element(),
-            "attribute" => parser.ignore_element(),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -718,6 +774,8 @@ impl Library {
             error_domain,
             glib_get_type: get_type,
             symbol_prefix,
+            attributes,
+            source_position,
         });
         self.add_type(ns_id, enum_name, typ);
         Ok(())
@@ -737,6 +795,83 @@ impl Library {
             })
     }
 
+    fn read_function_macro(
+        &mut self,
+        parser: &mut XmlParser<'_>,
+        ns_id: u16,
+        elem: &Element,
+    ) -> Result<(), String> {
+        let macro_name = elem.attr_required("name")?;
+        let c_identifier = elem.attr_required("identifier")?;
+        let version = self.read_version(parser, ns_id, elem)?;
+        let deprecated_version = self.read_deprecated_version(parser, ns_id, elem)?;
+
+        let mut params = Vec::new();
+        let mut doc = None;
+        let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
+
+        parser.elements(|parser, elem| match elem.name() {
+            "parameters" => parser
+                .elements(|parser, elem| match elem.name() {
+                    "parameter" => {
+                        let name = elem.attr_required("name")?.to_owned();
+                        parser.ignore_element().map(|_| name)
+                    }
+                    _ => Err(parser.unexpected_element(elem)),
+                })
+                .map(|names| params = names),
+            "doc" => parser.text().map(|t| doc = Some(t)),
+            "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
+            _ => Err(parser.unexpected_element(elem)),
+        })?;
+
+        self.add_function_macro(
+            ns_id,
+            FunctionMacro {
+                name: macro_name.into(),
+                c_identifier: c_identifier.into(),
+                parameters: params,
+                version,
+                deprecated_version,
+                doc,
+                doc_deprecated,
+                attributes,
+                source_position,
+            },
+        );
+        Ok(())
+    }
+
+    fn read_doc_section(
+        &mut self,
+        parser: &mut XmlParser<'_>,
+        ns_id: u16,
+        elem: &Element,
+    ) -> Result<(), String> {
+        let section_name = elem.attr_required("name")?;
+        let mut doc = None;
+
+        parser.elements(|parser, elem| match elem.name() {
+            "doc" => parser.text().map(|t| doc = Some(t)),
+            _ => Err(parser.unexpected_element(elem)),
+        })?;
+
+        self.add_doc_section(
+            ns_id,
+            DocSection {
+                name: section_name.into(),
+                doc,
+            },
+        );
+        Ok(())
+    }
+
     fn read_constant(
         &mut self,
         parser: &mut XmlParser<'_>,
@@ -752,6 +887,8 @@ impl Library {
         let mut inner = None;
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
 
         parser.elements(|parser, elem| match elem.name() {
             "type" | "array" => {
@@ -774,8 +911,10 @@ impl Library {
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "source-position" => parser.ignore_element(),
-            "attribute" => parser.ignore_element(),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -792,6 +931,8 @@ impl Library {
                     deprecated_version,
                     doc,
                     doc_deprecated,
+                    attributes,
+                    source_position,
                 },
             );
             Ok(())
@@ -815,9 +956,13 @@ impl Library {
         let mut inner = None;
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
 
         parser.elements(|parser, elem| match elem.name() {
-            "source-position" => parser.ignore_element(),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
             "type" | "array" => {
                 if inner.is_some() {
                     return Err(parser.fail_with_position(
@@ -835,7 +980,7 @@ impl Library {
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "attribute" => parser.ignore_element(),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -847,6 +992,8 @@ impl Library {
                 target_c_type: c_type,
                 doc,
                 doc_deprecated,
+                attributes,
+                source_position,
             });
             self.add_type(ns_id, alias_name, typ);
             Ok(())
@@ -858,24 +1005,41 @@ impl Library {
         }
     }
 
-    fn read_member(&self, parser: &mut XmlParser<'_>, elem: &Element) -> Result<Member, String> {
+    fn read_member(
+        &mut self,
+        parser: &mut XmlParser<'_>,
+        ns_id: u16,
+        elem: &Element,
+    ) -> Result<Member, String> {
         let member_name = elem.attr_required("name")?;
         let value = elem.attr_required("value")?;
         let c_identifier = elem.attr("identifier").map(|x| x.into());
+        let nick = elem.attr("nick").map(|x| x.into());
+        let version = self.read_version(parser, ns_id, elem)?;
+        let deprecated_version = self.read_deprecated_version(parser, ns_id, elem)?;
 
         let mut doc = None;
+        let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
 
         parser.elements(|parser, elem| match elem.name() {
             "doc" => parser.text().map(|t| doc = Some(t)),
-            "attribute" => parser.ignore_element(),
+            "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
+            "doc-version" => parser.ignore_element(),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
         Ok(Member {
             name: member_name.into(),
             value: value.into(),
+            nick,
             doc,
+            doc_deprecated,
             c_identifier: c_identifier.unwrap_or_else(|| member_name.into()),
+            attributes,
+            version,
+            deprecated_version,
         })
     }
 
@@ -889,7 +1053,7 @@ impl Library {
         let fn_name = elem.attr_required("name")?;
         let c_identifier = elem.attr("identifier").or_else(|| elem.attr("type"));
         let kind = FunctionKind::from_str(kind_str).map_err(|why| parser.fail(&why))?;
-        let is_method = kind == FunctionKind::Method;
+        let is_method = kind == FunctionKind::Method || kind == FunctionKind::VirtualMethod;
         let version = self.read_version(parser, ns_id, elem)?;
         let deprecated_version = self.read_deprecated_version(parser, ns_id, elem)?;
 
@@ -897,6 +1061,8 @@ impl Library {
         let mut ret = None;
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
+        let mut source_position = None;
 
         parser.elements(|parser, elem| match elem.name() {
             "parameters" => self
@@ -915,30 +1081,19 @@ impl Library {
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
             "doc-version" => parser.ignore_element(),
-            "source-position" => parser.ignore_element(),
-            "attribute" => parser.ignore_element(),
+            "source-position" => self
+                .read_source_position(parser, elem)
+                .map(|p| source_position = Some(p)),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
+        let finish_func = elem.attr("finish-func").map(ToOwned::to_owned);
+        let async_func = elem.attr("async-func").map(ToOwned::to_owned);
+
         let throws = elem.attr_bool("throws", false);
         if throws {
-            params.push(Parameter {
-                name: "error".into(),
-                typ: self.find_or_stub_type(ns_id, "GLib.Error"),
-                c_type: "GError**".into(),
-                instance_parameter: false,
-                direction: ParameterDirection::Out,
-                transfer: Transfer::Full,
-                caller_allocates: false,
-                nullable: Nullable(true),
-                array_length: None,
-                allow_none: true,
-                is_error: true,
-                doc: None,
-                scope: ParameterScope::None,
-                closure: None,
-                destroy: None,
-            });
+            params.push(self.error_parameter(ns_id));
         }
         if let Some(ret) = ret {
             Ok(Function {
@@ -952,6 +1107,10 @@ impl Library {
                 deprecated_version,
                 doc,
                 doc_deprecated,
+                attributes,
+                source_position,
+                finish_func,
+                async_func,
             })
         } else {
             Err(parser.fail_with_position(
@@ -999,6 +1158,38 @@ impl Library {
             })
     }
 
+    /// The `GError**` parameter appended to the functions that throw.
+    pub(crate) fn error_parameter(&mut self, ns_id: u16) -> Parameter {
+        Parameter {
+            name: "error".into(),
+            typ: self.find_or_stub_type(ns_id, "GLib.Error"),
+            c_type: "GError**".into(),
+            instance_parameter: false,
+            direction: ParameterDirection::Out,
+            transfer: Transfer::Full,
+            caller_allocates: false,
+            nullable: Nullable(true),
+            array_length: None,
+            allow_none: true,
+            is_error: true,
+            doc: None,
+            scope: ParameterScope::None,
+            closure: None,
+            destroy: None,
+            attributes: Attributes::default(),
+        }
+    }
+
+    fn read_virtual_method(
+        &mut self,
+        parser: &mut XmlParser<'_>,
+        ns_id: u16,
+        elem: &Element,
+    ) -> Result<Function, String> {
+        // Virtual methods have no C symbol: they are called through the class structure.
+        self.read_function(parser, ns_id, elem.name(), elem)
+    }
+
     fn read_signal(
         &mut self,
         parser: &mut XmlParser<'_>,
@@ -1008,6 +1199,9 @@ impl Library {
         let signal_name = elem.attr_required("name")?;
         let is_action = elem.attr_bool("action", false);
         let is_detailed = elem.attr_bool("detailed", false);
+        let when = elem.attr_from_str("when")?;
+        let no_recurse = elem.attr_bool("no-recurse", false);
+        let no_hooks = elem.attr_bool("no-hooks", false);
         let version = self.read_version(parser, ns_id, elem)?;
         let deprecated_version = self.read_deprecated_version(parser, ns_id, elem)?;
 
@@ -1015,6 +1209,7 @@ impl Library {
         let mut ret = None;
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
 
         parser.elements(|parser, elem| match elem.name() {
             "parameters" => self
@@ -1032,7 +1227,7 @@ impl Library {
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "attribute" => parser.ignore_element(),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
         if let Some(ret) = ret {
@@ -1042,10 +1237,14 @@ impl Library {
                 ret,
                 is_action,
                 is_detailed,
+                when,
+                no_recurse,
+                no_hooks,
                 version,
                 deprecated_version,
                 doc,
                 doc_deprecated,
+                attributes,
             })
         } else {
             Err(parser.fail_with_position(
@@ -1099,6 +1298,7 @@ impl Library {
         let mut typ = None;
         let mut varargs = false;
         let mut doc = None;
+        let mut attributes = Attributes::default();
 
         parser.elements(|parser, elem| match elem.name() {
             "type" | "array" => {
@@ -1126,7 +1326,7 @@ impl Library {
                 parser.ignore_element()
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
-            "attribute" => parser.ignore_element(),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -1150,6 +1350,7 @@ impl Library {
                 scope,
                 closure,
                 destroy,
+                attributes,
             })
         } else if varargs {
             Ok(Parameter {
@@ -1168,6 +1369,7 @@ impl Library {
                 scope,
                 closure,
                 destroy,
+                attributes,
             })
         } else {
             Err(parser.fail_with_position(
@@ -1197,6 +1399,7 @@ impl Library {
         let mut typ = None;
         let mut doc = None;
         let mut doc_deprecated = None;
+        let mut attributes = Attributes::default();
 
         parser.elements(|parser, elem| match elem.name() {
             "type" | "array" => {
@@ -1219,7 +1422,7 @@ impl Library {
             }
             "doc" => parser.text().map(|t| doc = Some(t)),
             "doc-deprecated" => parser.text().map(|t| doc_deprecated = Some(t)),
-            "attribute" => parser.ignore_element(),
+            "attribute" => self.read_attribute(parser, elem, &mut attributes),
             _ => Err(parser.unexpected_element(elem)),
         })?;
 
@@ -1241,6 +1444,7 @@ impl Library {
                 deprecated_version,
                 doc,
                 doc_deprecated,
+                attributes,
             }))
         } else {
             Err(parser.fail_with_position(
@@ -1311,6 +1515,34 @@ impl Library {
         }
     }
 
+    fn read_attribute(
+        &self,
+        parser: &mut XmlParser<'_>,
+        elem: &Element,
+        attributes: &mut Attributes,
+    ) -> Result<(), String> {
+        let name = elem.attr_required("name")?;
+        let value = elem.attr_required("value")?;
+        attributes.insert(name.into(), value.into());
+        parser.ignore_element()
+    }
+
+    fn read_source_position(
+        &self,
+        parser: &mut XmlParser<'_>,
+        elem: &Element,
+    ) -> Result<SourcePosition, String> {
+        let filename = elem.attr_required("filename")?;
+        let line = elem
+            .attr_from_str("line")?
+            .ok_or_else(|| parser.fail_with_position("Missing line", elem.position()))?;
+        parser.ignore_element()?;
+        Ok(SourcePosition {
+            filename: filename.into(),
+            line,
+        })
+    }
+
     fn read_version(
         &mut self,
         parser: &XmlParser<'_>,
@@ -1365,9 +1597,194 @@ impl Library {
     }
 }
 
-fn make_file_name(dir: &Path, name: &str) -> PathBuf {
+fn make_file_name(dir: &Path, name: &str, extension: &str) -> PathBuf {
     let mut path = dir.to_path_buf();
-    let name = format!("{}.gir", name);
+    let name = format!("{}.{}", name, extension);
     path.push(name);
     path
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{env::tests::girs_dir, traits::*};
+
+    fn library() -> Library {
+        let mut library = Library::new("Test");
+        library
+            .read_file(&[girs_dir()], &mut vec!["Test-1.0".into()])
+            .unwrap();
+        library
+    }
+
+    #[test]
+    fn doc_sections() {
+        let library = library();
+        let sections: Vec<_> = library
+            .namespace(MAIN_NAMESPACE)
+            .doc_sections
+            .iter()
+            .map(|section| (&section.name[..], section.doc.as_deref()))
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                (
+                    "running",
+                    Some("To use the library, create a #TestWidget with test_widget_activate().")
+                ),
+                ("question-index", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn virtual_methods() {
+        let library = library();
+        let widget: &Class = library
+            .type_(library.find_type(MAIN_NAMESPACE, "Widget").unwrap())
+            .maybe_ref()
+            .unwrap();
+        let names: Vec<&str> = widget.virtual_methods.iter().map(|f| &f.name[..]).collect();
+        assert_eq!(
+            names,
+            ["activate", "set_label", "get_name", "get_data", "reset"]
+        );
+        let set_label = &widget.virtual_methods[1];
+        assert_eq!(set_label.kind, FunctionKind::VirtualMethod);
+        assert_eq!(set_label.version, Some(Version::Full(1, 2, 0)));
+        assert!(set_label.parameters[0].instance_parameter);
+        assert_eq!(set_label.parameters[1].name, "label");
+        assert_eq!(
+            widget.virtual_methods[4].deprecated_version,
+            Some(Version::Full(1, 4, 0))
+        );
+
+        let sizable: &Interface = library
+            .type_(library.find_type(MAIN_NAMESPACE, "Sizable").unwrap())
+            .maybe_ref()
+            .unwrap();
+        assert_eq!(sizable.virtual_methods.len(), 1);
+        assert_eq!(sizable.virtual_methods[0].name, "get_size");
+        assert_eq!(sizable.type_struct.as_deref(), Some("SizableInterface"));
+    }
+
+    #[test]
+    fn attributes() {
+        let library = library();
+        let widget: &Class = library
+            .type_(library.find_type(MAIN_NAMESPACE, "Widget").unwrap())
+            .maybe_ref()
+            .unwrap();
+        assert_eq!(
+            widget.attributes.get("org.example.Stability"),
+            Some("stable")
+        );
+        let method = |name| widget.functions.iter().find(|f| f.name == name).unwrap();
+        assert_eq!(
+            method("fetch_title")
+                .attributes
+                .get("org.gtk.Method.get_property"),
+            Some("title")
+        );
+        assert_eq!(
+            method("store_title").parameters[1]
+                .attributes
+                .get("org.example.Format"),
+            Some("markup")
+        );
+        let title = widget
+            .properties
+            .iter()
+            .find(|p| p.name == "title")
+            .unwrap();
+        assert_eq!(
+            title.attributes.get("org.gtk.Property.set"),
+            Some("test_widget_store_title")
+        );
+        // Attributes of other items don't leak
+        assert_eq!(method("hidden_op").attributes.iter().count(), 0);
+        assert_eq!(title.attributes.get("org.example.Format"), None);
+    }
+
+    #[test]
+    fn members() {
+        let library = library();
+        let mode: &Enumeration = library
+            .type_(library.find_type(MAIN_NAMESPACE, "Mode").unwrap())
+            .maybe_ref()
+            .unwrap();
+        let normal = &mode.members[0];
+        assert_eq!(normal.nick.as_deref(), Some("normal"));
+        assert_eq!(normal.version, None);
+        assert_eq!(normal.deprecated_version, None);
+        assert_eq!(mode.members[1].version, Some(Version::Full(1, 2, 0)));
+        let slow = &mode.members[2];
+        assert_eq!(slow.deprecated_version, Some(Version::Full(1, 4, 0)));
+        assert_eq!(slow.doc_deprecated.as_deref(), Some("Use fast"));
+
+        let level: &Enumeration = library
+            .type_(library.find_type(MAIN_NAMESPACE, "Level").unwrap())
+            .maybe_ref()
+            .unwrap();
+        assert_eq!(level.members[1].nick, None);
+    }
+
+    #[test]
+    fn signal_emission_flags() {
+        let library = library();
+        let widget: &Class = library
+            .type_(library.find_type(MAIN_NAMESPACE, "Widget").unwrap())
+            .maybe_ref()
+            .unwrap();
+        let signal = |name| widget.signals.iter().find(|s| s.name == name).unwrap();
+        let clicked = signal("clicked");
+        assert_eq!(clicked.when, Some(SignalWhen::Last));
+        assert!(!clicked.no_recurse);
+        assert!(!clicked.no_hooks);
+        let changed = signal("changed");
+        assert_eq!(changed.when, Some(SignalWhen::First));
+        assert!(changed.is_detailed);
+        assert!(changed.no_recurse);
+        assert!(changed.no_hooks);
+        assert_eq!(signal("fetch-label").when, None);
+    }
+
+    #[test]
+    fn source_positions() {
+        let library = library();
+        let widget: &Class = library
+            .type_(library.find_type(MAIN_NAMESPACE, "Widget").unwrap())
+            .maybe_ref()
+            .unwrap();
+        assert_eq!(
+            widget.source_position,
+            Some(SourcePosition {
+                filename: "test/widget.h".into(),
+                line: 42,
+            })
+        );
+        let fetch_title = widget
+            .functions
+            .iter()
+            .find(|f| f.name == "fetch_title")
+            .unwrap();
+        assert_eq!(
+            fetch_title
+                .source_position
+                .as_ref()
+                .map(ToString::to_string),
+            Some("test-widget.h:42".to_owned())
+        );
+        let activate = widget
+            .functions
+            .iter()
+            .find(|f| f.name == "activate")
+            .unwrap();
+        assert_eq!(activate.source_position, None);
+        assert_eq!(
+            source_hint(fetch_title.source_position.as_ref()),
+            " at test-widget.h:42"
+        );
+    }
+}
@@ -0,0 +1,851 @@
+//! Binding status of every item of the main namespace, for the not bound report.
+
+use crate::{
+    analysis::{
+        functions::{self, Visibility},
+        rust_type::{RustType, TypeError},
+    },
+    codegen::sys,
+    config::{
+        gobjects::{GObject, GStatus},
+        matchable::Matchable,
+        WorkMode,
+    },
+    env::Env,
+    json::Json,
+    library::{self, FunctionKind, Type, MAIN_NAMESPACE},
+    version::Version,
+};
+use std::{collections::HashMap, fmt};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    Alias,
+    Bitfield,
+    Class,
+    Constant,
+    Constructor,
+    Enumeration,
+    Function,
+    Interface,
+    Member,
+    Method,
+    Property,
+    Record,
+    Signal,
+    Union,
+    VirtualMethod,
+}
+
+impl Kind {
+    pub fn as_str(self) -> &'static str {
+        use self::Kind::*;
+        match self {
+            Alias => "alias",
+            Bitfield => "bitfield",
+            Class => "class",
+            Constant => "constant",
+            Constructor => "constructor",
+            Enumeration => "enumeration",
+            Function => "function",
+            Interface => "interface",
+            Member => "member",
+            Method => "method",
+            Property => "property",
+            Record => "record",
+            Signal => "signal",
+            Union => "union",
+            VirtualMethod => "virtual method",
+        }
+    }
+
+    pub(crate) fn from_function(kind: FunctionKind) -> Kind {
+        match kind {
+            FunctionKind::Constructor => Kind::Constructor,
+            FunctionKind::Method => Kind::Method,
+            FunctionKind::VirtualMethod => Kind::VirtualMethod,
+            FunctionKind::Function | FunctionKind::Global => Kind::Function,
+        }
+    }
+
+    pub(crate) fn from_type(typ: &Type) -> Option<Kind> {
+        match *typ {
+            Type::Alias(_) => Some(Kind::Alias),
+            Type::Bitfield(_) => Some(Kind::Bitfield),
+            Type::Class(_) => Some(Kind::Class),
+            Type::Enumeration(_) => Some(Kind::Enumeration),
+            Type::Interface(_) => Some(Kind::Interface),
+            Type::Record(_) => Some(Kind::Record),
+            Type::Union(_) => Some(Kind::Union),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Status {
+    Generated,
+    /// Generated as a comment, because the analysis can't bind it.
+    Commented,
+    Manual,
+    Ignored,
+    NotBound,
+}
+
+impl Status {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Status::Generated => "generated",
+            Status::Commented | Status::NotBound => "not bound",
+            Status::Manual => "manual",
+            Status::Ignored => "ignored",
+        }
+    }
+
+    fn from_config(status: GStatus) -> Status {
+        match status {
+            GStatus::Generate => Status::Generated,
+            GStatus::Manual => Status::Manual,
+            GStatus::Ignore => Status::Ignored,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Item {
+    pub kind: Kind,
+    pub name: String,
+    /// Full name of the type the item belongs to, if any.
+    pub parent: Option<String>,
+    pub status: Status,
+    /// Why the item isn't bound, when it is known.
+    pub reason: Option<String>,
+    pub version: Option<Version>,
+    pub deprecated_version: Option<Version>,
+}
+
+impl Item {
+    fn new(kind: Kind, name: String, parent: Option<&str>, status: Status) -> Item {
+        Item {
+            kind,
+            name,
+            parent: parent.map(ToOwned::to_owned),
+            status,
+            reason: None,
+            version: None,
+            deprecated_version: None,
+        }
+    }
+
+    fn because<S: Into<String>>(mut self, reason: S) -> Item {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    fn versions(mut self, version: Option<Version>, deprecated_version: Option<Version>) -> Item {
+        self.version = version;
+        self.deprecated_version = deprecated_version;
+        self
+    }
+
+    pub fn to_json(&self) -> Json {
+        Json::object()
+            .with("kind", self.kind.as_str())
+            .with("name", self.name.as_str())
+            .with("status", self.status.as_str())
+            .with("reason", self.reason.as_deref())
+            .with("version", self.version.map(|v| v.to_string()))
+            .with(
+                "deprecated_version",
+                self.deprecated_version.map(|v| v.to_string()),
+            )
+    }
+}
+
+/// Collects the status of the types, functions, signals, properties and constants
+/// of the main namespace. Must be called after the analysis.
+pub fn collect(env: &Env) -> Vec<Item> {
+    let ns = env.library.namespace(MAIN_NAMESPACE);
+    let mut items = Vec::new();
+
+    for typ in ns.types.iter().flatten() {
+        let kind = match Kind::from_type(typ) {
+            Some(kind) if typ.is_standalone() => kind,
+            _ => continue,
+        };
+        let full_name = format!("{}.{}", ns.name, typ.get_name());
+        let item = type_item(env, kind, &full_name, typ)
+            .versions(typ.get_version(), typ.get_deprecated_version());
+        let status = item.status;
+        items.push(item);
+
+        if status == Status::Generated {
+            collect_generated_children(env, &full_name, typ, &mut items);
+        } else {
+            collect_children(&full_name, typ, status, &mut items);
+        }
+    }
+
+    let global_name = format!("{}.*", ns.name);
+    let global_status = env.config.objects.get(&global_name).map(|obj| obj.status);
+    for func in &ns.functions {
+        let name = format!("{}.{}", ns.name, func.name);
+        let item = match (global_status, &env.analysis.global_functions) {
+            (Some(GStatus::Generate), Some(info)) => {
+                function_item(env, &global_name, &info.functions, func, None)
+            }
+            (Some(status), _) => Item::new(Kind::Function, name, None, Status::from_config(status)),
+            (None, _) => Item::new(Kind::Function, name, None, Status::NotBound)
+                .because(format!("{} is not in the configuration", global_name)),
+        };
+        items.push(item.versions(func.version, func.deprecated_version));
+    }
+
+    for constant in &ns.constants {
+        let name = format!("{}.{}", ns.name, constant.name);
+        let item = match env.config.objects.get(&global_name) {
+            Some(obj) if obj.status.need_generate() => {
+                let configured = configured_status(
+                    obj.constants
+                        .matched(&constant.name)
+                        .iter()
+                        .map(|c| c.status),
+                );
+                if env
+                    .analysis
+                    .constants
+                    .iter()
+                    .any(|c| c.glib_name == constant.c_identifier)
+                {
+                    Item::new(Kind::Constant, name, None, Status::Generated)
+                } else if configured != GStatus::Generate {
+                    Item::new(Kind::Constant, name, None, Status::from_config(configured))
+                } else if env.is_totally_deprecated(constant.deprecated_version) {
+                    Item::new(Kind::Constant, name, None, Status::Ignored).because("deprecated")
+                } else {
+                    Item::new(Kind::Constant, name, None, Status::NotBound)
+                        .because(format!("unsupported type {}", constant.c_type))
+                }
+            }
+            Some(obj) => Item::new(Kind::Constant, name, None, Status::from_config(obj.status)),
+            None => Item::new(Kind::Constant, name, None, Status::NotBound)
+                .because(format!("{} is not in the configuration", global_name)),
+        };
+        items.push(item.versions(constant.version, constant.deprecated_version));
+    }
+
+    items
+}
+
+/// Collects the status of the functions of the main namespace, as generated in sys mode.
+pub fn collect_sys(env: &Env) -> Vec<Item> {
+    let ns = env.library.namespace(MAIN_NAMESPACE);
+    let mut items = Vec::new();
+
+    for typ in ns.types.iter().flatten() {
+        let full_name = format!("{}.{}", ns.name, typ.get_name());
+        let obj = env.config.objects.get(&full_name);
+        for func in typ.functions() {
+            items.push(sys_function_item(env, obj, func, Some(&full_name)));
+        }
+    }
+
+    let obj = env.config.objects.get(&format!("{}.*", ns.name));
+    for func in &ns.functions {
+        items.push(sys_function_item(env, obj, func, None));
+    }
+
+    items
+}
+
+fn sys_function_item(
+    env: &Env,
+    obj: Option<&GObject>,
+    func: &library::Function,
+    parent: Option<&str>,
+) -> Item {
+    let configured = obj.map_or(GStatus::Generate, |obj| {
+        configured_status(obj.functions.matched(&func.name).iter().map(|f| f.status))
+    });
+    let status = if configured != GStatus::Generate {
+        Status::from_config(configured)
+    } else if sys::functions::function_signature(env, func, false).0 {
+        Status::Commented
+    } else {
+        Status::Generated
+    };
+    Item::new(
+        Kind::from_function(func.kind),
+        function_name(env, func, parent),
+        parent,
+        status,
+    )
+    .versions(func.version, func.deprecated_version)
+}
+
+/// Number of items of a category per status.
+#[derive(Clone, Copy, Debug, Default)]
+struct Counts {
+    bound: usize,
+    commented: usize,
+    ignored: usize,
+    manual: usize,
+    not_bound: usize,
+}
+
+impl Counts {
+    fn add(&mut self, status: Status) {
+        match status {
+            Status::Generated => self.bound += 1,
+            Status::Commented => self.commented += 1,
+            Status::Ignored => self.ignored += 1,
+            Status::Manual => self.manual += 1,
+            Status::NotBound => self.not_bound += 1,
+        }
+    }
+
+    fn total(&self) -> usize {
+        self.bound + self.commented + self.ignored + self.manual + self.not_bound
+    }
+}
+
+impl fmt::Display for Counts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total();
+        let percent = |count: usize| 100.0 * count as f64 / total as f64;
+        write!(
+            f,
+            "{}/{} bound ({:.1}%), {} commented ({:.1}%), {} ignored ({:.1}%), \
+             {} manual ({:.1}%), {} not bound ({:.1}%)",
+            self.bound,
+            total,
+            percent(self.bound),
+            self.commented,
+            percent(self.commented),
+            self.ignored,
+            percent(self.ignored),
+            self.manual,
+            percent(self.manual),
+            self.not_bound,
+            percent(self.not_bound),
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Coverage {
+    functions: Counts,
+    methods: Counts,
+    signals: Counts,
+    properties: Counts,
+}
+
+impl Coverage {
+    /// Counts the item, returning false if it isn't a function, a signal or a property.
+    fn add(&mut self, item: &Item) -> bool {
+        let counts = match item.kind {
+            Kind::Function | Kind::Constructor => &mut self.functions,
+            Kind::Method | Kind::VirtualMethod => &mut self.methods,
+            Kind::Signal => &mut self.signals,
+            Kind::Property => &mut self.properties,
+            _ => return false,
+        };
+        counts.add(item.status);
+        true
+    }
+
+    fn print(&self, name: &str) {
+        let categories = [
+            ("functions", self.functions),
+            ("methods", self.methods),
+            ("signals", self.signals),
+            ("properties", self.properties),
+        ];
+        if categories.iter().all(|(_, counts)| counts.total() == 0) {
+            return;
+        }
+        println!("{}", name);
+        for (category, counts) in &categories {
+            if counts.total() > 0 {
+                println!("    {:<12}{}", category, counts);
+            }
+        }
+    }
+}
+
+/// Prints how much of the main namespace is bound, per object and in total.
+pub fn print_coverage(env: &Env) {
+    let items = if env.config.work_mode == WorkMode::Sys {
+        collect_sys(env)
+    } else {
+        collect(env)
+    };
+    let ns_name = &env.library.namespace(MAIN_NAMESPACE).name;
+    let global_name = format!("{}.*", ns_name);
+
+    let mut objects: Vec<(&str, Coverage)> = Vec::new();
+    let mut indices = HashMap::new();
+    let mut total = Coverage::default();
+    for item in &items {
+        if !total.add(item) {
+            continue;
+        }
+        let parent = item.parent.as_deref().unwrap_or(&global_name);
+        let index = *indices.entry(parent).or_insert_with(|| {
+            objects.push((parent, Coverage::default()));
+            objects.len() - 1
+        });
+        objects[index].1.add(item);
+    }
+
+    println!("Binding coverage:");
+    for (name, coverage) in &objects {
+        coverage.print(name);
+    }
+    total.print(&format!("{} (total)", ns_name));
+}
+
+/// Prints the not bound report as a JSON document.
+pub fn print_json(env: &Env) {
+    println!("{}", to_json(env));
+}
+
+fn to_json(env: &Env) -> Json {
+    let items = collect(env).iter().map(Item::to_json).collect::<Vec<_>>();
+    Json::object()
+        .with("namespace", env.config.library_name.as_str())
+        .with("version", env.config.library_version.as_str())
+        .with("items", items)
+}
+
+fn type_item(env: &Env, kind: Kind, full_name: &str, typ: &Type) -> Item {
+    let name = full_name.to_owned();
+    let status = match env.config.objects.get(full_name) {
+        Some(obj) => obj.status,
+        None => {
+            return Item::new(kind, name, None, Status::NotBound)
+                .because("not in the configuration")
+        }
+    };
+    if status != GStatus::Generate {
+        return Item::new(kind, name, None, Status::from_config(status));
+    }
+    if analyzed_functions(env, full_name).is_some() || kind == Kind::Alias {
+        Item::new(kind, name, None, Status::Generated)
+    } else if env.is_totally_deprecated(typ.get_deprecated_version()) {
+        Item::new(kind, name, None, Status::Ignored).because("deprecated")
+    } else {
+        Item::new(kind, name, None, Status::NotBound).because("rejected by the analysis")
+    }
+}
+
+/// Functions of a generated type, as kept by the analysis.
+fn analyzed_functions<'a>(env: &'a Env, full_name: &str) -> Option<&'a [functions::Info]> {
+    let analysis = &env.analysis;
+    if let Some(info) = analysis.objects.get(full_name) {
+        Some(&info.functions)
+    } else if let Some(info) = analysis.records.get(full_name) {
+        Some(&info.functions)
+    } else if let Some(info) = analysis
+        .enumerations
+        .iter()
+        .find(|e| e.full_name == full_name)
+    {
+        Some(&info.functions)
+    } else {
+        analysis
+            .flags
+            .iter()
+            .find(|f| f.full_name == full_name)
+            .map(|info| &info.functions[..])
+    }
+}
+
+/// Children of a type which isn't generated share its status.
+fn collect_children(full_name: &str, typ: &Type, status: Status, items: &mut Vec<Item>) {
+    let new = |kind, name: &str, version, deprecated_version| {
+        let item = Item::new(
+            kind,
+            format!("{}::{}", full_name, name),
+            Some(full_name),
+            status,
+        )
+        .versions(version, deprecated_version);
+        if status == Status::NotBound {
+            item.because(format!("{} is not bound", full_name))
+        } else {
+            item
+        }
+    };
+    for func in typ.functions() {
+        items.push(new(
+            Kind::from_function(func.kind),
+            &func.name,
+            func.version,
+            func.deprecated_version,
+        ));
+    }
+    let (signals, properties) = signals_and_properties(typ);
+    for signal in signals {
+        items.push(new(
+            Kind::Signal,
+            &signal.name,
+            signal.version,
+            signal.deprecated_version,
+        ));
+    }
+    for prop in properties {
+        items.push(new(
+            Kind::Property,
+            &prop.name,
+            prop.version,
+            prop.deprecated_version,
+        ));
+    }
+}
+
+fn collect_generated_children(env: &Env, full_name: &str, typ: &Type, items: &mut Vec<Item>) {
+    let obj = &env.config.objects[full_name];
+    let analyzed = analyzed_functions(env, full_name).unwrap_or(&[]);
+    for func in typ.functions() {
+        let item = function_item(env, full_name, analyzed, func, Some(full_name));
+        items.push(item.versions(func.version, func.deprecated_version));
+    }
+
+    let info = env.analysis.objects.get(full_name);
+    let (signals, properties) = signals_and_properties(typ);
+    for signal in signals {
+        let name = format!("{}::{}", full_name, signal.name);
+        let configured =
+            configured_status(obj.signals.matched(&signal.name).iter().map(|s| s.status));
+        let analyzed =
+            info.and_then(|info| info.signals.iter().find(|s| s.signal_name == signal.name));
+        let item = match analyzed {
+            Some(analyzed) => match analyzed.trampoline {
+                Ok(_) => Item::new(Kind::Signal, name, Some(full_name), Status::Generated),
+                Err(ref errors) => {
+                    Item::new(Kind::Signal, name, Some(full_name), Status::Commented)
+                        .because(errors.join(", "))
+                }
+            },
+            None if configured != GStatus::Generate => Item::new(
+                Kind::Signal,
+                name,
+                Some(full_name),
+                Status::from_config(configured),
+            ),
+            None if env.is_totally_deprecated(signal.deprecated_version) => {
+                Item::new(Kind::Signal, name, Some(full_name), Status::Ignored)
+                    .because("deprecated")
+            }
+            None => Item::new(Kind::Signal, name, Some(full_name), Status::NotBound),
+        };
+        items.push(item.versions(signal.version, signal.deprecated_version));
+    }
+
+    for prop in properties {
+        let name = format!("{}::{}", full_name, prop.name);
+        let configured =
+            configured_status(obj.properties.matched(&prop.name).iter().map(|p| p.status));
+        let notify_name = format!("notify::{}", prop.name);
+        let generated = info.map_or(false, |info| {
+            info.properties.iter().any(|p| p.name == prop.name)
+                || info
+                    .notify_signals
+                    .iter()
+                    .any(|s| s.signal_name == notify_name)
+        });
+        let item = if generated {
+            Item::new(Kind::Property, name, Some(full_name), Status::Generated)
+        } else if configured != GStatus::Generate {
+            Item::new(
+                Kind::Property,
+                name,
+                Some(full_name),
+                Status::from_config(configured),
+            )
+        } else if env.is_totally_deprecated(prop.deprecated_version) {
+            Item::new(Kind::Property, name, Some(full_name), Status::Ignored).because("deprecated")
+        } else {
+            let item = Item::new(Kind::Property, name, Some(full_name), Status::NotBound);
+            match RustType::try_new(env, prop.typ) {
+                Err(error) => item.because(type_error_reason(&error)),
+                Ok(_) => item,
+            }
+        };
+        items.push(item.versions(prop.version, prop.deprecated_version));
+    }
+}
+
+fn function_item(
+    env: &Env,
+    full_name: &str,
+    analyzed: &[functions::Info],
+    func: &library::Function,
+    parent: Option<&str>,
+) -> Item {
+    let kind = Kind::from_function(func.kind);
+    let name = function_name(env, func, parent);
+    let analyzed = func
+        .c_identifier
+        .as_ref()
+        .and_then(|c_identifier| analyzed.iter().find(|f| &f.glib_name == c_identifier));
+    match analyzed {
+        Some(info) if info.visibility == Visibility::Comment => {
+            let item = Item::new(kind, name, parent, Status::Commented);
+            match commented_reason(env, info) {
+                Some(reason) => item.because(reason),
+                None => item,
+            }
+        }
+        Some(info) => Item::new(kind, name, parent, Status::from_config(info.status)),
+        None => {
+            let configured = configured_status(
+                env.config.objects[full_name]
+                    .functions
+                    .matched(&func.name)
+                    .iter()
+                    .map(|f| f.status),
+            );
+            let dependencies = env.library.ignored_dependencies(env, func);
+            if configured != GStatus::Generate {
+                Item::new(kind, name, parent, Status::from_config(configured))
+            } else if env.is_totally_deprecated(func.deprecated_version) {
+                Item::new(kind, name, parent, Status::Ignored).because("deprecated")
+            } else if !dependencies.is_empty() {
+                Item::new(kind, name, parent, Status::NotBound).because(format!(
+                    "depends on ignored types {}",
+                    dependencies.join(", ")
+                ))
+            } else {
+                Item::new(kind, name, parent, Status::NotBound)
+            }
+        }
+    }
+}
+
+fn function_name(env: &Env, func: &library::Function, parent: Option<&str>) -> String {
+    match parent {
+        Some(parent) => format!("{}::{}", parent, func.name),
+        None => format!(
+            "{}.{}",
+            env.library.namespace(MAIN_NAMESPACE).name,
+            func.name
+        ),
+    }
+}
+
+/// Finds why the analysis generated the function as a comment.
+fn commented_reason(env: &Env, info: &functions::Info) -> Option<String> {
+    let mut errors = Vec::new();
+    for par in &info.parameters.c_parameters {
+        if let Err(error) = RustType::builder(env, par.typ)
+            .with_direction(par.direction)
+            .with_scope(par.scope)
+            .with_try_from_glib(&par.try_from_glib)
+            .try_build_param()
+        {
+            errors.push(type_error_reason(&error));
+        }
+    }
+    if let Some(ref par) = info.ret.parameter {
+        if let Err(error) = RustType::builder(env, par.lib_par.typ)
+            .with_direction(par.lib_par.direction)
+            .with_try_from_glib(&par.try_from_glib)
+            .try_build_param()
+        {
+            errors.push(format!("return value: {}", type_error_reason(&error)));
+        }
+    }
+    if !errors.is_empty() {
+        Some(errors.join(", "))
+    } else if info
+        .parameters
+        .c_parameters
+        .iter()
+        .any(|par| matches!(env.library.type_(par.typ), Type::Function(_)))
+    {
+        Some("unsupported callbacks".into())
+    } else {
+        None
+    }
+}
+
+fn type_error_reason(error: &TypeError) -> String {
+    match *error {
+        TypeError::Ignored(ref s) => format!("ignored type {}", s),
+        TypeError::Mismatch(ref s) => format!("metadata mismatch for {}", s),
+        TypeError::Unimplemented(ref s) => format!("unsupported type {}", s),
+    }
+}
+
+fn signals_and_properties(typ: &Type) -> (&[library::Signal], &[library::Property]) {
+    match *typ {
+        Type::Class(ref class) => (&class.signals, &class.properties),
+        Type::Interface(ref iface) => (&iface.signals, &iface.properties),
+        _ => (&[], &[]),
+    }
+}
+
+/// Combined status of the configurations matching an item.
+fn configured_status<I: Iterator<Item = GStatus>>(statuses: I) -> GStatus {
+    let mut result = GStatus::Generate;
+    for status in statuses {
+        match status {
+            GStatus::Ignore => return GStatus::Ignore,
+            GStatus::Manual => result = GStatus::Manual,
+            GStatus::Generate => (),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_display() {
+        let mut counts = Counts::default();
+        for &status in &[
+            Status::Generated,
+            Status::Generated,
+            Status::Generated,
+            Status::Commented,
+            Status::Ignored,
+            Status::NotBound,
+            Status::NotBound,
+            Status::Manual,
+        ] {
+            counts.add(status);
+        }
+        assert_eq!(counts.total(), 8);
+        assert_eq!(
+            counts.to_string(),
+            "3/8 bound (37.5%), 1 commented (12.5%), 1 ignored (12.5%), \
+             1 manual (12.5%), 2 not bound (25.0%)"
+        );
+    }
+
+    #[test]
+    fn configured_status_prefers_ignore() {
+        use GStatus::*;
+        assert_eq!(configured_status(vec![].into_iter()), Generate);
+        assert_eq!(
+            configured_status(vec![Generate, Manual].into_iter()),
+            Manual
+        );
+        assert_eq!(configured_status(vec![Manual, Ignore].into_iter()), Ignore);
+    }
+
+    #[test]
+    fn collect_statuses_and_reasons() {
+        let env = crate::env::tests::env(
+            r#"
+[options]
+work_mode = "normal"
+min_cfg_version = "1.0"
+manual = ["GLib.Error", "Gio.AsyncResult", "Gio.Cancellable"]
+
+[[object]]
+name = "Test.Button"
+status = "generate"
+
+[[object]]
+name = "Test.Widget"
+status = "generate"
+    [[object.function]]
+    name = "hidden_op"
+    manual = true
+
+[[object]]
+name = "Test.Mode"
+status = "ignore"
+
+[[object]]
+name = "Test.Options"
+status = "manual"
+"#,
+        );
+        let items = collect(&env);
+        let item = |name: &str| {
+            let item = items.iter().find(|item| item.name == name).unwrap();
+            (item.kind, item.status, item.reason.as_deref())
+        };
+        assert_eq!(item("Test.Widget"), (Kind::Class, Status::Generated, None));
+        assert_eq!(
+            item("Test.Widget::changed"),
+            (Kind::Signal, Status::Generated, None)
+        );
+        assert_eq!(
+            item("Test.Widget::set_data"),
+            (Kind::Method, Status::Commented, Some("ignored type Label"))
+        );
+        assert_eq!(
+            item("Test.Widget::hidden_op"),
+            (Kind::Method, Status::Manual, None)
+        );
+        assert_eq!(
+            item("Test.Mode"),
+            (Kind::Enumeration, Status::Ignored, None)
+        );
+        assert_eq!(item("Test.Options"), (Kind::Bitfield, Status::Manual, None));
+        assert_eq!(
+            item("Test.Label"),
+            (
+                Kind::Class,
+                Status::NotBound,
+                Some("not in the configuration")
+            )
+        );
+        assert_eq!(
+            item("Test.Label::set_text"),
+            (
+                Kind::Method,
+                Status::NotBound,
+                Some("Test.Label is not bound")
+            )
+        );
+        assert_eq!(
+            item("Test.Button::set_data"),
+            (
+                Kind::Method,
+                Status::NotBound,
+                Some("depends on ignored types Test.Label")
+            )
+        );
+        assert_eq!(
+            item("Test.Button::watch"),
+            (
+                Kind::Method,
+                Status::Commented,
+                Some("unsupported callbacks")
+            )
+        );
+
+        let json = to_json(&env);
+        let items_json = match json {
+            Json::Object(ref fields) => {
+                assert_eq!(fields[0], ("namespace".to_owned(), Json::from("Test")));
+                assert_eq!(fields[1], ("version".to_owned(), Json::from("1.0")));
+                &fields[2].1
+            }
+            _ => panic!("{}", json),
+        };
+        assert_eq!(
+            *items_json,
+            Json::Array(items.iter().map(Item::to_json).collect())
+        );
+        assert_eq!(
+            items
+                .iter()
+                .find(|item| item.name == "Test.Button::watch")
+                .unwrap()
+                .to_json()
+                .to_string(),
+            "{\n  \"kind\": \"method\",\n  \"name\": \"Test.Button::watch\",\n  \
+             \"status\": \"not bound\",\n  \"reason\": \"unsupported callbacks\",\n  \
+             \"version\": null,\n  \"deprecated_version\": null\n}"
+        );
+    }
+}
@@ -0,0 +1,1237 @@
+//! Reader of the binary `.typelib` files compiled from the GIR files.
+//!
+//! Typelibs carry no documentation, versions, C includes nor C types: the C names of types
+//! and symbols are rebuilt from the namespace prefixes following the GObject conventions.
+
+use crate::{case::CaseExt, library::*};
+use log::{trace, warn};
+use std::{collections::HashMap, fs, path::Path};
+
+const MAGIC: &[u8] = b"GOBJ\nMETADATA\r\n\x1a";
+const MAJOR_VERSION: u8 = 4;
+const HEADER_SIZE: usize = 112;
+
+const BLOB_FUNCTION: u16 = 1;
+const BLOB_CALLBACK: u16 = 2;
+const BLOB_STRUCT: u16 = 3;
+const BLOB_BOXED: u16 = 4;
+const BLOB_ENUM: u16 = 5;
+const BLOB_FLAGS: u16 = 6;
+const BLOB_OBJECT: u16 = 7;
+const BLOB_INTERFACE: u16 = 8;
+const BLOB_CONSTANT: u16 = 9;
+const BLOB_UNION: u16 = 11;
+
+const TAG_BOOLEAN: u32 = 1;
+const TAG_INT8: u32 = 2;
+const TAG_UINT8: u32 = 3;
+const TAG_INT16: u32 = 4;
+const TAG_UINT16: u32 = 5;
+const TAG_INT32: u32 = 6;
+const TAG_UINT32: u32 = 7;
+const TAG_INT64: u32 = 8;
+const TAG_UINT64: u32 = 9;
+const TAG_FLOAT: u32 = 10;
+const TAG_DOUBLE: u32 = 11;
+const TAG_UTF8: u32 = 13;
+const TAG_FILENAME: u32 = 14;
+const TAG_ARRAY: u32 = 15;
+const TAG_INTERFACE: u32 = 16;
+const TAG_GLIST: u32 = 17;
+const TAG_GSLIST: u32 = 18;
+const TAG_GHASH: u32 = 19;
+const TAG_ERROR: u32 = 20;
+const TAG_UNICHAR: u32 = 21;
+
+/// GIR names and C types of the basic type tags, indexed by tag.
+const BASIC_TYPES: &[(&str, &str)] = &[
+    ("none", "void"),
+    ("gboolean", "gboolean"),
+    ("gint8", "gint8"),
+    ("guint8", "guint8"),
+    ("gint16", "gint16"),
+    ("guint16", "guint16"),
+    ("gint32", "gint32"),
+    ("guint32", "guint32"),
+    ("gint64", "gint64"),
+    ("guint64", "guint64"),
+    ("gfloat", "gfloat"),
+    ("gdouble", "gdouble"),
+    ("GType", "GType"),
+    ("utf8", "gchar*"),
+    ("filename", "gchar*"),
+];
+
+impl Library {
+    pub(crate) fn read_typelib<P: AsRef<Path>>(
+        &mut self,
+        dirs: &[P],
+        path: &Path,
+        libs: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let bytes =
+            fs::read(path).map_err(|e| format!("Can't read file \"{}\": {}", path.display(), e))?;
+        let data = Data(&bytes);
+        let header = Header::read(data).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let dependencies = if header.dependencies == 0 {
+            ""
+        } else {
+            data.string(header.dependencies)?
+        };
+        for lib in dependencies.split('|') {
+            let name = lib.split('-').next().unwrap_or(lib);
+            if name.is_empty() || self.find_namespace(name).is_some() {
+                continue;
+            }
+            if libs.iter().any(|x| x == lib) {
+                return Err(format!(
+                    "`{}` includes itself (full path:`{}`)!",
+                    lib,
+                    libs.join("::")
+                ));
+            }
+            libs.push(lib.to_owned());
+            self.read_file(dirs, libs)?;
+            libs.pop();
+        }
+
+        Reader::new(self, data, header)
+            .and_then(|mut reader| reader.read())
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// Bounds-checked little-endian view of the typelib.
+#[derive(Clone, Copy)]
+struct Data<'a>(&'a [u8]);
+
+impl<'a> Data<'a> {
+    fn bytes(self, offset: usize, len: usize) -> Result<&'a [u8], String> {
+        self.0
+            .get(offset..offset + len)
+            .ok_or_else(|| format!("Offset {} is out of bounds", offset))
+    }
+
+    fn u8(self, offset: usize) -> Result<u8, String> {
+        self.bytes(offset, 1).map(|b| b[0])
+    }
+
+    fn u16(self, offset: usize) -> Result<u16, String> {
+        self.bytes(offset, 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(self, offset: usize) -> Result<u32, String> {
+        self.bytes(offset, 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(self, offset: usize) -> Result<u64, String> {
+        Ok(u64::from(self.u32(offset)?) | u64::from(self.u32(offset + 4)?) << 32)
+    }
+
+    /// Nul-terminated string starting at `offset`.
+    fn string(self, offset: usize) -> Result<&'a str, String> {
+        let tail = self
+            .0
+            .get(offset..)
+            .ok_or_else(|| format!("Offset {} is out of bounds", offset))?;
+        let len = tail
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| format!("Unterminated string at offset {}", offset))?;
+        std::str::from_utf8(&tail[..len]).map_err(|e| format!("Offset {}: {}", offset, e))
+    }
+
+    /// String referenced by the offset stored at `offset`.
+    fn string_at(self, offset: usize) -> Result<&'a str, String> {
+        self.string(self.u32(offset)? as usize)
+    }
+
+    /// Same as `string_at`, for the optional strings stored as a zero offset when missing.
+    fn opt_string_at(self, offset: usize) -> Result<Option<&'a str>, String> {
+        match self.u32(offset)? {
+            0 => Ok(None),
+            offset => self.string(offset as usize).map(Some),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Header {
+    n_local_entries: u16,
+    directory: usize,
+    n_attributes: usize,
+    attributes: usize,
+    dependencies: usize,
+    namespace: usize,
+    shared_library: usize,
+    c_prefix: usize,
+    entry_size: usize,
+    function_size: usize,
+    callback_size: usize,
+    signal_size: usize,
+    vfunc_size: usize,
+    arg_size: usize,
+    property_size: usize,
+    field_size: usize,
+    value_size: usize,
+    attribute_size: usize,
+    enum_size: usize,
+    struct_size: usize,
+    object_size: usize,
+    interface_size: usize,
+    union_size: usize,
+}
+
+impl Header {
+    fn read(data: Data<'_>) -> Result<Header, String> {
+        if data.bytes(0, MAGIC.len()).ok() != Some(MAGIC) || data.0.len() < HEADER_SIZE {
+            return Err("Not a typelib file".into());
+        }
+        let major = data.u8(16)?;
+        if major != MAJOR_VERSION {
+            return Err(format!("Unsupported typelib version {}", major));
+        }
+        let offset = |at| data.u32(at).map(|v| v as usize);
+        let size = |at| data.u16(at).map(usize::from);
+        Ok(Header {
+            n_local_entries: data.u16(22)?,
+            directory: offset(24)?,
+            n_attributes: offset(28)?,
+            attributes: offset(32)?,
+            dependencies: offset(36)?,
+            namespace: offset(44)?,
+            shared_library: offset(52)?,
+            c_prefix: offset(56)?,
+            entry_size: size(60)?,
+            function_size: size(62)?,
+            callback_size: size(64)?,
+            signal_size: size(66)?,
+            vfunc_size: size(68)?,
+            arg_size: size(70)?,
+            property_size: size(72)?,
+            field_size: size(74)?,
+            value_size: size(76)?,
+            attribute_size: size(78)?,
+            enum_size: size(86)?,
+            struct_size: size(88)?,
+            object_size: size(90)?,
+            interface_size: size(92)?,
+            union_size: size(94)?,
+        })
+    }
+}
+
+/// An item of the typelib directory.
+struct Entry<'a> {
+    blob_type: u16,
+    local: bool,
+    name: &'a str,
+    /// Offset of the blob for the local entries, of the namespace name otherwise.
+    offset: usize,
+}
+
+/// The type receiving the instance parameter of methods.
+struct Owner {
+    tid: TypeId,
+    name: String,
+    c_type: String,
+}
+
+struct Reader<'a> {
+    library: &'a mut Library,
+    data: Data<'a>,
+    header: Header,
+    ns_id: u16,
+    ns_name: String,
+    c_prefix: String,
+    symbol_prefix: String,
+    attributes: HashMap<usize, Attributes>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(library: &'a mut Library, data: Data<'a>, header: Header) -> Result<Self, String> {
+        let ns_name = data.string(header.namespace)?.to_owned();
+        let ns_id = library.add_namespace(&ns_name);
+        let prefixes = if header.c_prefix == 0 {
+            Vec::new()
+        } else {
+            data.string(header.c_prefix)?
+                .split(',')
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        let c_prefix = prefixes.first().cloned().unwrap_or_default();
+        let symbol_prefix = c_prefix.to_snake();
+
+        {
+            let ns = library.namespace_mut(ns_id);
+            if header.shared_library != 0 {
+                ns.shared_library = data
+                    .string(header.shared_library)?
+                    .split(',')
+                    .map(String::from)
+                    .collect();
+            }
+            ns.symbol_prefixes = prefixes.iter().map(|p| p.to_snake()).collect();
+            ns.identifier_prefixes = prefixes;
+        }
+
+        let mut attributes: HashMap<usize, Attributes> = HashMap::new();
+        for i in 0..header.n_attributes {
+            let offset = header.attributes + i * header.attribute_size;
+            attributes
+                .entry(data.u32(offset)? as usize)
+                .or_default()
+                .insert(
+                    data.string_at(offset + 4)?.into(),
+                    data.string_at(offset + 8)?.into(),
+                );
+        }
+
+        Ok(Reader {
+            library,
+            data,
+            header,
+            ns_id,
+            ns_name,
+            c_prefix,
+            symbol_prefix,
+            attributes,
+        })
+    }
+
+    fn read(&mut self) -> Result<(), String> {
+        trace!("Reading typelib of {}", self.ns_name);
+
+        let mut type_structs = Vec::new();
+        for index in 1..=self.header.n_local_entries {
+            let entry = self.entry(index)?;
+            trace!("<blob {} name={:?}>", entry.blob_type, entry.name);
+            let offset = entry.offset;
+            match entry.blob_type {
+                BLOB_FUNCTION => {
+                    let func = self.read_function(offset, None)?;
+                    self.library.add_function(self.ns_id, func);
+                }
+                BLOB_CALLBACK => {
+                    let func = self.read_callback(offset)?;
+                    self.library
+                        .add_type(self.ns_id, entry.name, Type::Function(func));
+                }
+                BLOB_STRUCT | BLOB_BOXED => {
+                    let typ = self.read_record(entry.name, offset)?;
+                    self.library.add_type(self.ns_id, entry.name, typ);
+                }
+                BLOB_UNION => {
+                    let typ = self.read_union(entry.name, offset)?;
+                    self.library.add_type(self.ns_id, entry.name, typ);
+                }
+                BLOB_ENUM | BLOB_FLAGS => {
+                    let typ = self.read_enum(entry.name, offset, entry.blob_type == BLOB_FLAGS)?;
+                    self.library.add_type(self.ns_id, entry.name, typ);
+                }
+                BLOB_OBJECT => {
+                    let typ = self.read_class(entry.name, offset)?;
+                    if let Type::Class(Class {
+                        type_struct: Some(ref s),
+                        ..
+                    }) = typ
+                    {
+                        type_structs.push((s.clone(), entry.name.to_owned()));
+                    }
+                    self.library.add_type(self.ns_id, entry.name, typ);
+                }
+                BLOB_INTERFACE => {
+                    let typ = self.read_interface(entry.name, offset)?;
+                    if let Type::Interface(Interface {
+                        type_struct: Some(ref s),
+                        ..
+                    }) = typ
+                    {
+                        type_structs.push((s.clone(), entry.name.to_owned()));
+                    }
+                    self.library.add_type(self.ns_id, entry.name, typ);
+                }
+                BLOB_CONSTANT => {
+                    if let Some(constant) = self.read_constant(offset)? {
+                        self.library.add_constant(self.ns_id, constant);
+                    }
+                }
+                _ => warn!(
+                    "Unknown blob type {} of `{}.{}`",
+                    entry.blob_type, self.ns_name, entry.name
+                ),
+            }
+        }
+
+        for (record_name, owner_name) in type_structs {
+            if let Some(tid) = self.library.find_type(self.ns_id, &record_name) {
+                if let Type::Record(ref mut record) = *self.library.type_mut(tid) {
+                    record.gtype_struct_for = Some(owner_name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Directory entry at the 1-based `index`.
+    fn entry(&self, index: u16) -> Result<Entry<'a>, String> {
+        if index == 0 {
+            return Err("Invalid directory index 0".into());
+        }
+        let offset = self.header.directory + usize::from(index - 1) * self.header.entry_size;
+        Ok(Entry {
+            blob_type: self.data.u16(offset)?,
+            local: self.data.u16(offset + 2)? & 1 != 0,
+            name: self.data.string_at(offset + 4)?,
+            offset: self.data.u32(offset + 8)? as usize,
+        })
+    }
+
+    /// Full name and C type of the type at directory `index`.
+    fn entry_type(&self, index: u16) -> Result<(String, String), String> {
+        let entry = self.entry(index)?;
+        if entry.local {
+            return Ok((
+                format!("{}.{}", self.ns_name, entry.name),
+                format!("{}{}", self.c_prefix, entry.name),
+            ));
+        }
+        let ns_name = self.data.string(entry.offset)?;
+        let full_name = format!("{}.{}", ns_name, entry.name);
+        let c_type = self
+            .library
+            .find_type(INTERNAL_NAMESPACE, &full_name)
+            .and_then(|tid| {
+                self.library.namespace(tid.ns_id).types[tid.id as usize]
+                    .as_ref()
+                    .and_then(Type::get_glib_name)
+                    .map(ToOwned::to_owned)
+            })
+            .unwrap_or_else(|| {
+                let prefix = self
+                    .library
+                    .find_namespace(ns_name)
+                    .and_then(|ns_id| self.library.namespace(ns_id).identifier_prefixes.first())
+                    .map_or("", String::as_str);
+                format!("{}{}", prefix, entry.name)
+            });
+        Ok((full_name, c_type))
+    }
+
+    fn type_name(&self, index: u16) -> Result<String, String> {
+        self.entry_type(index).map(|(name, _)| name)
+    }
+
+    /// Symbol prefix of a type, taken from its `get_type` function when it has one.
+    fn type_symbol_prefix(&self, name: &str, get_type: Option<&str>) -> String {
+        let ns_prefix = format!("{}_", self.symbol_prefix);
+        get_type
+            .and_then(|s| s.strip_suffix("_get_type"))
+            .map(|s| s.strip_prefix(&ns_prefix).unwrap_or(s).to_owned())
+            .unwrap_or_else(|| name.to_snake())
+    }
+
+    fn attributes(&self, offset: usize) -> Attributes {
+        self.attributes.get(&offset).cloned().unwrap_or_default()
+    }
+
+    fn read_type(&mut self, offset: usize) -> Result<(TypeId, String, Option<u32>), String> {
+        let value = self.data.u32(offset)?;
+        if value & 0x00ff_ffff == 0 {
+            // Basic types are stored inline
+            let (tid, c_type) = self.basic_type(value >> 27, value & (1 << 24) != 0)?;
+            return Ok((tid, c_type, None));
+        }
+
+        let offset = value as usize;
+        let pointer = self.data.u8(offset)? & 1 != 0;
+        let tag = u32::from(self.data.u8(offset)? >> 3);
+        match tag {
+            TAG_INTERFACE => {
+                let index = self.data.u16(offset + 2)?;
+                let (name, mut c_type) = self.entry_type(index)?;
+                if pointer {
+                    c_type.push('*');
+                }
+                let tid = match self.library.find_type(self.ns_id, &name) {
+                    Some(tid) => tid,
+                    None if self.entry(index)?.local => {
+                        self.library.find_or_stub_type(self.ns_id, &name)
+                    }
+                    None => {
+                        // Aliases aren't compiled, they remain as unresolved entries
+                        trace!("Unresolved type {}, using gpointer", name);
+                        self.basic_type(0, true)?.0
+                    }
+                };
+                Ok((tid, c_type, None))
+            }
+            TAG_ARRAY => {
+                let flags = self.data.u16(offset)?;
+                let dimension = self.data.u16(offset + 2)?;
+                let (inner, inner_c_type, _) = self.read_type(offset + 4)?;
+                let array_length = if flags & (1 << 9) != 0 {
+                    Some(u32::from(dimension))
+                } else {
+                    None
+                };
+                let (tid, c_type) = match (flags >> 11) & 3 {
+                    0 => {
+                        let size = if flags & (1 << 10) != 0 {
+                            Some(dimension)
+                        } else {
+                            None
+                        };
+                        let c_type = if size.is_some() {
+                            inner_c_type.clone()
+                        } else {
+                            format!("{}*", inner_c_type)
+                        };
+                        let tid = Type::c_array(self.library, inner, size, Some(inner_c_type));
+                        (tid, c_type)
+                    }
+                    1 => (self.container("GLib.Array", vec![inner])?, "GArray*".into()),
+                    2 => (
+                        self.container("GLib.PtrArray", vec![inner])?,
+                        "GPtrArray*".into(),
+                    ),
+                    _ => (
+                        self.library.find_or_stub_type(self.ns_id, "GLib.ByteArray"),
+                        "GByteArray*".into(),
+                    ),
+                };
+                Ok((tid, c_type, array_length))
+            }
+            TAG_GLIST | TAG_GSLIST | TAG_GHASH => {
+                let (name, c_type) = match tag {
+                    TAG_GLIST => ("GLib.List", "GList*"),
+                    TAG_GSLIST => ("GLib.SList", "GSList*"),
+                    _ => ("GLib.HashTable", "GHashTable*"),
+                };
+                let n_types = usize::from(self.data.u16(offset + 2)?);
+                let mut inner = Vec::with_capacity(n_types);
+                for i in 0..n_types {
+                    inner.push(self.read_type(offset + 4 + 4 * i)?.0);
+                }
+                let tid = if inner.is_empty() {
+                    self.library.find_or_stub_type(self.ns_id, name)
+                } else {
+                    self.container(name, inner)?
+                };
+                Ok((tid, c_type.into(), None))
+            }
+            TAG_ERROR => Ok((
+                self.library.find_or_stub_type(self.ns_id, "GLib.Error"),
+                "GError*".into(),
+                None,
+            )),
+            _ => {
+                let (tid, c_type) = self.basic_type(tag, pointer)?;
+                Ok((tid, c_type, None))
+            }
+        }
+    }
+
+    fn basic_type(&self, tag: u32, pointer: bool) -> Result<(TypeId, String), String> {
+        let (name, c_type) = if tag == 0 && pointer {
+            ("gpointer", "gpointer")
+        } else if tag == TAG_UNICHAR {
+            ("gunichar", "gunichar")
+        } else {
+            *BASIC_TYPES
+                .get(tag as usize)
+                .ok_or_else(|| format!("Unknown type tag {}", tag))?
+        };
+        self.library
+            .find_type(INTERNAL_NAMESPACE, name)
+            .map(|tid| (tid, c_type.to_owned()))
+            .ok_or_else(|| format!("Missing fundamental type {}", name))
+    }
+
+    fn container(&mut self, name: &str, inner: Vec<TypeId>) -> Result<TypeId, String> {
+        Type::container(self.library, name, inner)
+            .ok_or_else(|| format!("Wrong number of inner types in `{}`", name))
+    }
+
+    fn read_function(&mut self, offset: usize, owner: Option<&Owner>) -> Result<Function, String> {
+        let flags = self.data.u16(offset + 2)?;
+        let is_static = self.data.u16(offset + 16)? & 1 != 0;
+        let kind = match owner {
+            None => FunctionKind::Global,
+            Some(_) if flags & (1 << 3) != 0 => FunctionKind::Constructor,
+            Some(_) if is_static => FunctionKind::Function,
+            Some(_) => FunctionKind::Method,
+        };
+        let instance = if kind == FunctionKind::Method {
+            owner
+        } else {
+            None
+        };
+        let signature = self.data.u32(offset + 12)? as usize;
+        let (parameters, ret, throws) =
+            self.read_signature(signature, instance, flags & (1 << 5) != 0)?;
+
+        Ok(Function {
+            name: self.data.string_at(offset + 4)?.into(),
+            c_identifier: Some(self.data.string_at(offset + 8)?.into()),
+            kind,
+            parameters,
+            ret,
+            throws,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: self.attributes(offset),
+            source_position: None,
+            finish_func: None,
+            async_func: None,
+        })
+    }
+
+    fn read_functions(
+        &mut self,
+        offset: &mut usize,
+        count: u16,
+        owner: &Owner,
+    ) -> Result<Vec<Function>, String> {
+        let mut functions = Vec::with_capacity(usize::from(count));
+        for _ in 0..count {
+            functions.push(self.read_function(*offset, Some(owner))?);
+            *offset += self.header.function_size;
+        }
+        Ok(functions)
+    }
+
+    fn read_callback(&mut self, offset: usize) -> Result<Function, String> {
+        let name = self.data.string_at(offset + 4)?;
+        let signature = self.data.u32(offset + 8)? as usize;
+        let (parameters, ret, throws) = self.read_signature(signature, None, false)?;
+
+        Ok(Function {
+            name: name.into(),
+            c_identifier: Some(format!("{}{}", self.c_prefix, name)),
+            kind: FunctionKind::Function,
+            parameters,
+            ret,
+            throws,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: self.attributes(offset),
+            source_position: None,
+            finish_func: None,
+            async_func: None,
+        })
+    }
+
+    fn read_virtual_methods(
+        &mut self,
+        offset: &mut usize,
+        count: u16,
+        owner: &Owner,
+    ) -> Result<Vec<Function>, String> {
+        let mut functions = Vec::with_capacity(usize::from(count));
+        for _ in 0..count {
+            let throws = self.data.u16(*offset + 4)? & (1 << 4) != 0;
+            let signature = self.data.u32(*offset + 16)? as usize;
+            let (parameters, ret, throws) = self.read_signature(signature, Some(owner), throws)?;
+            functions.push(Function {
+                name: self.data.string_at(*offset)?.into(),
+                c_identifier: None,
+                kind: FunctionKind::VirtualMethod,
+                parameters,
+                ret,
+                throws,
+                version: None,
+                deprecated_version: None,
+                doc: None,
+                doc_deprecated: None,
+                attributes: self.attributes(*offset),
+                source_position: None,
+                finish_func: None,
+                async_func: None,
+            });
+            *offset += self.header.vfunc_size;
+        }
+        Ok(functions)
+    }
+
+    /// Reads the parameters, the return value and whether the signature throws.
+    fn read_signature(
+        &mut self,
+        offset: usize,
+        instance: Option<&Owner>,
+        throws: bool,
+    ) -> Result<(Vec<Parameter>, Parameter, bool), String> {
+        let flags = self.data.u16(offset + 4)?;
+        let n_arguments = usize::from(self.data.u16(offset + 6)?);
+        let for_method = instance.is_some();
+
+        let mut parameters = Vec::with_capacity(n_arguments + 2);
+        if let Some(owner) = instance {
+            parameters.push(Parameter {
+                name: owner.name.to_snake(),
+                typ: owner.tid,
+                c_type: owner.c_type.clone(),
+                instance_parameter: true,
+                direction: ParameterDirection::In,
+                transfer: transfer(flags & (1 << 4) != 0, false),
+                caller_allocates: false,
+                nullable: Nullable(false),
+                allow_none: false,
+                array_length: None,
+                is_error: false,
+                doc: None,
+                scope: ParameterScope::None,
+                closure: None,
+                destroy: None,
+                attributes: Attributes::default(),
+            });
+        }
+        for i in 0..n_arguments {
+            let arg = offset + 8 + i * self.header.arg_size;
+            parameters.push(self.read_argument(arg, for_method)?);
+        }
+
+        let (typ, c_type, mut array_length) = self.read_type(offset)?;
+        if for_method {
+            array_length = array_length.map(|l| l + 1);
+        }
+        let ret = Parameter {
+            name: "".into(),
+            typ,
+            c_type,
+            instance_parameter: false,
+            direction: ParameterDirection::Return,
+            transfer: transfer(flags & (1 << 1) != 0, flags & (1 << 2) != 0),
+            caller_allocates: false,
+            nullable: Nullable(flags & 1 != 0),
+            allow_none: false,
+            array_length,
+            is_error: false,
+            doc: None,
+            scope: ParameterScope::None,
+            closure: None,
+            destroy: None,
+            attributes: Attributes::default(),
+        };
+
+        let throws = throws || flags & (1 << 5) != 0;
+        if throws {
+            parameters.push(self.library.error_parameter(self.ns_id));
+        }
+        Ok((parameters, ret, throws))
+    }
+
+    fn read_argument(&mut self, offset: usize, for_method: bool) -> Result<Parameter, String> {
+        let flags = self.data.u32(offset + 4)?;
+        let closure = self.data.u8(offset + 8)? as i8;
+        let destroy = self.data.u8(offset + 9)? as i8;
+        let (typ, mut c_type, mut array_length) = self.read_type(offset + 12)?;
+
+        let direction = match (flags & 1 != 0, flags & (1 << 1) != 0) {
+            (true, true) => ParameterDirection::InOut,
+            (false, true) => ParameterDirection::Out,
+            _ => ParameterDirection::In,
+        };
+        let caller_allocates = flags & (1 << 2) != 0;
+        if direction != ParameterDirection::In && !caller_allocates {
+            c_type.push('*');
+        }
+        if for_method {
+            array_length = array_length.map(|l| l + 1);
+        }
+        let scope = match (flags >> 8) & 7 {
+            1 => ParameterScope::Call,
+            2 => ParameterScope::Async,
+            3 => ParameterScope::Notified,
+            _ => ParameterScope::None,
+        };
+
+        Ok(Parameter {
+            name: self.data.string_at(offset)?.into(),
+            typ,
+            c_type,
+            instance_parameter: false,
+            direction,
+            transfer: transfer(flags & (1 << 5) != 0, flags & (1 << 6) != 0),
+            caller_allocates,
+            nullable: Nullable(flags & (1 << 3) != 0),
+            allow_none: flags & ((1 << 3) | (1 << 4)) != 0,
+            array_length,
+            is_error: false,
+            doc: None,
+            scope,
+            closure: if closure < 0 {
+                None
+            } else {
+                Some(closure as usize)
+            },
+            destroy: if destroy < 0 {
+                None
+            } else {
+                Some(destroy as usize)
+            },
+            attributes: self.attributes(offset),
+        })
+    }
+
+    /// Reads `count` fields, with the callbacks embedded after some of them.
+    fn read_fields(&mut self, offset: &mut usize, count: u16) -> Result<Vec<Field>, String> {
+        let mut fields = Vec::with_capacity(usize::from(count));
+        for _ in 0..count {
+            let field = *offset;
+            let flags = self.data.u8(field + 4)?;
+            let bits = self.data.u8(field + 5)?;
+            *offset += self.header.field_size;
+
+            let (typ, c_type, array_length) = if flags & (1 << 2) != 0 {
+                let mut func = self.read_callback(*offset)?;
+                func.c_identifier = None;
+                *offset += self.header.callback_size;
+                (Type::function(self.library, func), None, None)
+            } else {
+                let (typ, c_type, array_length) = self.read_type(field + 12)?;
+                (typ, Some(c_type), array_length)
+            };
+
+            fields.push(Field {
+                name: self.data.string_at(field)?.into(),
+                typ,
+                c_type,
+                private: flags & 1 == 0,
+                bits: if bits == 0 { None } else { Some(bits) },
+                array_length,
+                doc: None,
+                attributes: self.attributes(field),
+            });
+        }
+        Ok(fields)
+    }
+
+    fn read_properties(&mut self, offset: &mut usize, count: u16) -> Result<Vec<Property>, String> {
+        let mut properties = Vec::with_capacity(usize::from(count));
+        for _ in 0..count {
+            let flags = self.data.u32(*offset + 4)?;
+            let (typ, c_type, _) = self.read_type(*offset + 12)?;
+            properties.push(Property {
+                name: self.data.string_at(*offset)?.into(),
+                readable: flags & (1 << 1) != 0,
+                writable: flags & (1 << 2) != 0,
+                construct: flags & (1 << 3) != 0,
+                construct_only: flags & (1 << 4) != 0,
+                typ,
+                c_type: Some(c_type),
+                transfer: transfer(flags & (1 << 5) != 0, flags & (1 << 6) != 0),
+                version: None,
+                deprecated_version: None,
+                doc: None,
+                doc_deprecated: None,
+                attributes: self.attributes(*offset),
+            });
+            *offset += self.header.property_size;
+        }
+        Ok(properties)
+    }
+
+    fn read_signals(&mut self, offset: &mut usize, count: u16) -> Result<Vec<Signal>, String> {
+        let mut signals = Vec::with_capacity(usize::from(count));
+        for _ in 0..count {
+            let flags = self.data.u16(*offset)?;
+            let signature = self.data.u32(*offset + 12)? as usize;
+            let (parameters, ret, _) = self.read_signature(signature, None, false)?;
+            let when = if flags & (1 << 1) != 0 {
+                Some(SignalWhen::First)
+            } else if flags & (1 << 2) != 0 {
+                Some(SignalWhen::Last)
+            } else if flags & (1 << 3) != 0 {
+                Some(SignalWhen::Cleanup)
+            } else {
+                None
+            };
+            signals.push(Signal {
+                name: self.data.string_at(*offset + 4)?.into(),
+                parameters,
+                ret,
+                is_action: flags & (1 << 6) != 0,
+                is_detailed: flags & (1 << 5) != 0,
+                when,
+                no_recurse: flags & (1 << 4) != 0,
+                no_hooks: flags & (1 << 7) != 0,
+                version: None,
+                deprecated_version: None,
+                doc: None,
+                doc_deprecated: None,
+                attributes: self.attributes(*offset),
+            });
+            *offset += self.header.signal_size;
+        }
+        Ok(signals)
+    }
+
+    /// Reads a list of directory indices, padded to an even count.
+    fn read_type_list(&mut self, offset: &mut usize, count: u16) -> Result<Vec<TypeId>, String> {
+        let mut tids = Vec::with_capacity(usize::from(count));
+        for i in 0..usize::from(count) {
+            let name = self.type_name(self.data.u16(*offset + 2 * i)?)?;
+            tids.push(self.library.find_or_stub_type(self.ns_id, &name));
+        }
+        *offset += 2 * usize::from(count + count % 2);
+        Ok(tids)
+    }
+
+    fn owner(&mut self, name: &str, c_type: String) -> Owner {
+        Owner {
+            tid: self.library.find_or_stub_type(self.ns_id, name),
+            name: name.into(),
+            c_type,
+        }
+    }
+
+    fn read_record(&mut self, name: &str, offset: usize) -> Result<Type, String> {
+        let get_type = self.data.opt_string_at(offset + 12)?;
+        let n_fields = self.data.u16(offset + 20)?;
+        let n_methods = self.data.u16(offset + 22)?;
+        let c_type = format!("{}{}", self.c_prefix, name);
+        let owner = self.owner(name, format!("{}*", c_type));
+
+        let mut pos = offset + self.header.struct_size;
+        let fields = self.read_fields(&mut pos, n_fields)?;
+        let functions = self.read_functions(&mut pos, n_methods, &owner)?;
+
+        Ok(Type::Record(Record {
+            name: name.into(),
+            c_type,
+            symbol_prefix: Some(self.type_symbol_prefix(name, get_type)),
+            glib_get_type: get_type.map(ToOwned::to_owned),
+            gtype_struct_for: None, // this will be set once all the types are read
+            fields,
+            functions,
+            attributes: self.attributes(offset),
+            ..Record::default()
+        }))
+    }
+
+    fn read_union(&mut self, name: &str, offset: usize) -> Result<Type, String> {
+        let get_type = self.data.opt_string_at(offset + 12)?;
+        let n_fields = self.data.u16(offset + 20)?;
+        let n_functions = self.data.u16(offset + 22)?;
+        let c_type = format!("{}{}", self.c_prefix, name);
+        let owner = self.owner(name, format!("{}*", c_type));
+
+        let mut pos = offset + self.header.union_size;
+        let fields = self.read_fields(&mut pos, n_fields)?;
+        let functions = self.read_functions(&mut pos, n_functions, &owner)?;
+
+        Ok(Type::Union(Union {
+            name: name.into(),
+            c_type: Some(c_type),
+            symbol_prefix: Some(self.type_symbol_prefix(name, get_type)),
+            glib_get_type: get_type.map(ToOwned::to_owned),
+            fields,
+            functions,
+            doc: None,
+            attributes: self.attributes(offset),
+            source_position: None,
+        }))
+    }
+
+    fn read_enum(&mut self, name: &str, offset: usize, is_flags: bool) -> Result<Type, String> {
+        let get_type = self.data.opt_string_at(offset + 12)?;
+        let n_values = self.data.u16(offset + 16)?;
+        let n_methods = self.data.u16(offset + 18)?;
+        let error_domain = self.data.opt_string_at(offset + 20)?;
+        let c_type = format!("{}{}", self.c_prefix, name);
+        let symbol_prefix = self.type_symbol_prefix(name, get_type);
+        let owner = self.owner(name, c_type.clone());
+        // Members of error enums are named after the domain: `g-io-error-quark` gives `G_IO_ERROR_`
+        let member_prefix = error_domain
+            .and_then(|s| s.strip_suffix("-quark"))
+            .map(|s| s.replace('-', "_"))
+            .unwrap_or_else(|| format!("{}_{}", self.symbol_prefix, symbol_prefix));
+
+        let mut pos = offset + self.header.enum_size;
+        let mut members = Vec::with_capacity(usize::from(n_values));
+        for _ in 0..n_values {
+            let flags = self.data.u32(pos)?;
+            let member_name = self.data.string_at(pos + 4)?;
+            let value = self.data.u32(pos + 8)?;
+            // Recent compilers keep the C identifier, which doesn't always follow the prefix
+            let mut attributes = self.attributes(pos);
+            let c_identifier = attributes
+                .remove("c:identifier")
+                .unwrap_or_else(|| format!("{}_{}", member_prefix, member_name).to_uppercase());
+            members.push(Member {
+                name: member_name.into(),
+                c_identifier,
+                value: if flags & (1 << 1) != 0 {
+                    value.to_string()
+                } else {
+                    (value as i32).to_string()
+                },
+                nick: None,
+                doc: None,
+                doc_deprecated: None,
+                attributes,
+                version: None,
+                deprecated_version: None,
+            });
+            pos += self.header.value_size;
+        }
+        let functions = self.read_functions(&mut pos, n_methods, &owner)?;
+
+        let glib_get_type = get_type.map(ToOwned::to_owned);
+        let symbol_prefix = Some(symbol_prefix);
+        let attributes = self.attributes(offset);
+        Ok(if is_flags {
+            Type::Bitfield(Bitfield {
+                name: name.into(),
+                c_type,
+                symbol_prefix,
+                members,
+                functions,
+                version: None,
+                deprecated_version: None,
+                doc: None,
+                doc_deprecated: None,
+                glib_get_type,
+                attributes,
+                source_position: None,
+            })
+        } else {
+            Type::Enumeration(Enumeration {
+                name: name.into(),
+                c_type,
+                symbol_prefix,
+                members,
+                functions,
+                version: None,
+                deprecated_version: None,
+                doc: None,
+                doc_deprecated: None,
+                error_domain: error_domain.map(|s| ErrorDomain::Quark(s.into())),
+                glib_get_type,
+                attributes,
+                source_position: None,
+            })
+        })
+    }
+
+    fn read_class(&mut self, name: &str, offset: usize) -> Result<Type, String> {
+        let flags = self.data.u16(offset + 2)?;
+        let get_type = self.data.opt_string_at(offset + 12)?;
+        let parent = match self.data.u16(offset + 16)? {
+            0 => None,
+            index => Some(self.type_name(index)?),
+        };
+        let type_struct = match self.data.u16(offset + 18)? {
+            0 => None,
+            index => Some(self.entry(index)?.name.to_owned()),
+        };
+        let count = |i: usize| self.data.u16(offset + 20 + 2 * i);
+        let n_interfaces = count(0)?;
+        let n_fields = count(1)?;
+        let n_properties = count(2)?;
+        let n_methods = count(3)?;
+        let n_signals = count(4)?;
+        let n_vfuncs = count(5)?;
+        let c_type = format!("{}{}", self.c_prefix, name);
+        let owner = self.owner(name, format!("{}*", c_type));
+
+        let mut pos = offset + self.header.object_size;
+        let implements = self.read_type_list(&mut pos, n_interfaces)?;
+        let fields = self.read_fields(&mut pos, n_fields)?;
+        let properties = self.read_properties(&mut pos, n_properties)?;
+        let functions = self.read_functions(&mut pos, n_methods, &owner)?;
+        let signals = self.read_signals(&mut pos, n_signals)?;
+        let virtual_methods = self.read_virtual_methods(&mut pos, n_vfuncs, &owner)?;
+        let parent = parent.map(|s| self.library.find_or_stub_type(self.ns_id, &s));
+
+        Ok(Type::Class(Class {
+            name: name.into(),
+            c_type,
+            type_struct,
+            c_class_type: None, // this will be resolved during postprocessing
+            glib_get_type: get_type.unwrap_or_default().into(),
+            fields,
+            functions,
+            virtual_methods,
+            signals,
+            properties,
+            parent,
+            implements,
+            final_type: false, // this will be set during postprocessing
+            doc: None,
+            doc_deprecated: None,
+            version: None,
+            deprecated_version: None,
+            symbol_prefix: self.type_symbol_prefix(name, get_type),
+            is_abstract: flags & (1 << 1) != 0,
+            attributes: self.attributes(offset),
+            source_position: None,
+        }))
+    }
+
+    fn read_interface(&mut self, name: &str, offset: usize) -> Result<Type, String> {
+        let get_type = self.data.opt_string_at(offset + 12)?;
+        let type_struct = match self.data.u16(offset + 16)? {
+            0 => None,
+            index => Some(self.entry(index)?.name.to_owned()),
+        };
+        let count = |i: usize| self.data.u16(offset + 18 + 2 * i);
+        let n_prerequisites = count(0)?;
+        let n_properties = count(1)?;
+        let n_methods = count(2)?;
+        let n_signals = count(3)?;
+        let n_vfuncs = count(4)?;
+        let c_type = format!("{}{}", self.c_prefix, name);
+        let owner = self.owner(name, format!("{}*", c_type));
+
+        let mut pos = offset + self.header.interface_size;
+        let prerequisites = self.read_type_list(&mut pos, n_prerequisites)?;
+        let properties = self.read_properties(&mut pos, n_properties)?;
+        let functions = self.read_functions(&mut pos, n_methods, &owner)?;
+        let signals = self.read_signals(&mut pos, n_signals)?;
+        let virtual_methods = self.read_virtual_methods(&mut pos, n_vfuncs, &owner)?;
+
+        Ok(Type::Interface(Interface {
+            name: name.into(),
+            c_type,
+            symbol_prefix: self.type_symbol_prefix(name, get_type),
+            type_struct,
+            c_class_type: None, // this will be resolved during postprocessing
+            glib_get_type: get_type.unwrap_or_default().into(),
+            functions,
+            virtual_methods,
+            signals,
+            properties,
+            prerequisites,
+            attributes: self.attributes(offset),
+            ..Interface::default()
+        }))
+    }
+
+    fn read_constant(&mut self, offset: usize) -> Result<Option<Constant>, String> {
+        let name = self.data.string_at(offset + 4)?;
+        let type_tag = self.data.u32(offset + 8)? >> 27;
+        let (typ, c_type, _) = self.read_type(offset + 8)?;
+        let size = self.data.u32(offset + 12)? as usize;
+        let at = self.data.u32(offset + 16)? as usize;
+
+        let value = match type_tag {
+            TAG_BOOLEAN => (self.data.u32(at)? != 0).to_string(),
+            TAG_INT8 => (self.data.u8(at)? as i8).to_string(),
+            TAG_UINT8 => self.data.u8(at)?.to_string(),
+            TAG_INT16 => (self.data.u16(at)? as i16).to_string(),
+            TAG_UINT16 => self.data.u16(at)?.to_string(),
+            TAG_INT32 => (self.data.u32(at)? as i32).to_string(),
+            TAG_UINT32 => self.data.u32(at)?.to_string(),
+            TAG_INT64 => (self.data.u64(at)? as i64).to_string(),
+            TAG_UINT64 => self.data.u64(at)?.to_string(),
+            TAG_FLOAT => f32::from_bits(self.data.u32(at)?).to_string(),
+            TAG_DOUBLE => f64::from_bits(self.data.u64(at)?).to_string(),
+            TAG_UTF8 | TAG_FILENAME => {
+                let bytes = self.data.bytes(at, size)?;
+                let bytes = bytes.split(|&b| b == 0).next().unwrap_or(bytes);
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+            _ => {
+                warn!("Unsupported type of constant `{}.{}`", self.ns_name, name);
+                return Ok(None);
+            }
+        };
+
+        Ok(Some(Constant {
+            name: name.into(),
+            c_identifier: format!("{}_{}", self.symbol_prefix.to_uppercase(), name),
+            typ,
+            c_type,
+            value,
+            version: None,
+            deprecated_version: None,
+            doc: None,
+            doc_deprecated: None,
+            attributes: self.attributes(offset),
+            source_position: None,
+        }))
+    }
+}
+
+fn transfer(full: bool, container: bool) -> Transfer {
+    if full {
+        Transfer::Full
+    } else if container {
+        Transfer::Container
+    } else {
+        Transfer::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_reads_little_endian_values() {
+        let bytes = [0x34, 0x12, 0x78, 0x56, b'a', b'b', 0, 4, 0, 0, 0];
+        let data = Data(&bytes);
+        assert_eq!(data.u16(0), Ok(0x1234));
+        assert_eq!(data.u32(0), Ok(0x5678_1234));
+        assert_eq!(data.string(4), Ok("ab"));
+        assert_eq!(data.string_at(7), Ok("ab"));
+        assert!(data.u32(8).is_err());
+        assert!(data.string(11).is_err());
+    }
+
+    #[test]
+    fn header_rejects_other_files() {
+        assert!(Header::read(Data(b"<?xml version=\"1.0\"?>")).is_err());
+        let mut bytes = vec![0; HEADER_SIZE];
+        bytes[..MAGIC.len()].copy_from_slice(MAGIC);
+        bytes[16] = 3;
+        assert_eq!(
+            Header::read(Data(&bytes)).unwrap_err(),
+            "Unsupported typelib version 3"
+        );
+    }
+
+    fn read(dirs: &[&str]) -> Library {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let dirs: Vec<_> = dirs.iter().map(|dir| root.join(dir)).collect();
+        let mut library = Library::new("GModule");
+        library
+            .read_file(&dirs, &mut vec!["GModule-2.0".into()])
+            .unwrap();
+        library
+    }
+
+    #[test]
+    fn same_model_as_the_gir() {
+        // The GIR file describes the typelib without what typelibs don't carry
+        let from_typelib = read(&["typelibs", "gir-files"]);
+        let mut from_gir = read(&["typelibs/gir", "gir-files"]);
+        assert!(from_typelib.namespace(MAIN_NAMESPACE).c_includes.is_empty());
+        assert_eq!(
+            from_gir.namespace(MAIN_NAMESPACE).c_includes,
+            vec!["gmodule.h".to_owned()]
+        );
+        from_gir.namespace_mut(MAIN_NAMESPACE).c_includes.clear();
+        assert_eq!(
+            crate::library_dump::dump(&from_typelib).to_string(),
+            crate::library_dump::dump(&from_gir).to_string()
+        );
+
+        // A GIR file is read even if a typelib is found first
+        let library = read(&["typelibs", "typelibs/gir", "gir-files"]);
+        assert_eq!(
+            library.namespace(MAIN_NAMESPACE).c_includes,
+            vec!["gmodule.h".to_owned()]
+        );
+    }
+}
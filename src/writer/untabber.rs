@@ -1,17 +1,17 @@
 use super::TAB;
 use std::io::{Result, Write};
 
-pub struct Untabber {
-    orig: Box<dyn Write>,
+pub struct Untabber<'a> {
+    orig: Box<dyn Write + 'a>,
 }
 
-impl Untabber {
-    pub fn new(orig: Box<dyn Write>) -> Untabber {
+impl<'a> Untabber<'a> {
+    pub fn new(orig: Box<dyn Write + 'a>) -> Untabber<'a> {
         Untabber { orig }
     }
 }
 
-impl Write for Untabber {
+impl Write for Untabber<'_> {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
         let mut chunks = buf.split(|b| b == &b'\t').peekable();
         loop {